@@ -1,15 +1,66 @@
+#![allow(clippy::needless_return)]
+
+use tree_collections::algorithms::merge_sort;
 use tree_collections::prelude::*;
 
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::measurement::WallTime;
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion,
+};
 use rand::seq::{IteratorRandom, SliceRandom};
-use rand::{rngs::StdRng, SeedableRng};
-
-const N: usize = 64;
-pub struct MyRngSeed(pub [u8; N]);
-pub struct MyRng(MyRngSeed);
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 const TREE_SIZE: [u32; 5] = [10_000, 40_000, 70_000, 100_000, 130_000];
 
+// The operations every benchmarked tree provides, so that each benchmark
+// is written once and run on all of them
+trait BenchmarkableTree<T: Ord + Copy>: Clone {
+    fn new() -> Self;
+    fn insert(&mut self, v: T);
+    fn delete(&mut self, v: T);
+    fn contains(&self, v: T) -> bool;
+}
+
+// Forward to the methods of the trees, `contains` comes from CommonTreeTrait
+// for some of them so its path is given to keep the call unambiguous
+macro_rules! impl_benchmarkable_tree {
+    ($contains:path => $($tree:ident),*) => {
+        $(
+            impl BenchmarkableTree<u32> for $tree<u32> {
+                fn new() -> Self {
+                    return $tree::new();
+                }
+
+                fn insert(&mut self, v: u32) {
+                    $tree::insert(self, v);
+                }
+
+                fn delete(&mut self, v: u32) {
+                    $tree::delete(self, v);
+                }
+
+                fn contains(&self, v: u32) -> bool {
+                    return $contains(self, v);
+                }
+            }
+        )*
+    };
+}
+
+impl_benchmarkable_tree!(CommonTreeTrait::contains => AVLTree, RBTree, FastRBTree, BSTree);
+impl_benchmarkable_tree!(ArenaRBTree::contains => ArenaRBTree);
+impl_benchmarkable_tree!(BoxRBTree::contains => BoxRBTree);
+
+// Run a benchmark once for each compared tree, adding a tree to the
+// comparison only takes one more line here
+macro_rules! for_each_tree {
+    ($bench:ident($($arg:expr),*)) => {
+        $bench::<AVLTree<u32>>("AVL", $($arg),*);
+        $bench::<RBTree<u32>>("RBT", $($arg),*);
+        $bench::<FastRBTree<u32>>("Fast RBT", $($arg),*);
+    };
+}
+
 fn create_shuffled_data(tree_size: u32) -> Vec<u32> {
     let seed = [0u8; 32];
     let mut rng: StdRng = SeedableRng::from_seed(seed);
@@ -18,223 +69,213 @@ fn create_shuffled_data(tree_size: u32) -> Vec<u32> {
     return data;
 }
 
-fn benchmark_avl_ordered_insert(tree_size: u32) {
-    let mut avl = AVLTree::new();
-    for v in 0..tree_size {
-        avl.insert(v);
-    }
+// 1% of the shuffled data, always the same values for a given size
+fn create_sample(tree_size: u32) -> Vec<u32> {
+    let data: Vec<u32> = create_shuffled_data(tree_size);
+    let seed = [0u8; 32];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+    return data
+        .into_iter()
+        .choose_multiple(&mut rng, (tree_size / 100) as usize);
 }
 
-fn benchmark_fast_rbt_ordered_insert(tree_size: u32) {
-    let mut rbt = FastRBTree::new();
-    for v in 0..tree_size {
-        rbt.insert(v);
-    }
+// 70% contains, 20% insert and 10% delete
+enum Operation {
+    Contains(u32),
+    Insert(u32),
+    Delete(u32),
 }
 
-fn benchmark_rbt_ordered_insert(tree_size: u32) {
-    let mut rbt = RBTree::new();
-    for v in 0..tree_size {
-        rbt.insert(v);
+fn create_mixed_operations(tree_size: u32) -> Vec<Operation> {
+    let seed = [0u8; 32];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+    let mut operations = Vec::new();
+    for _ in 0..tree_size / 10 {
+        let value = rng.gen_range(0, tree_size * 2);
+        let operation = match rng.gen_range(0, 10) {
+            0..=6 => Operation::Contains(value),
+            7 | 8 => Operation::Insert(value),
+            _ => Operation::Delete(value),
+        };
+        operations.push(operation);
     }
+    return operations;
 }
 
-fn benchmark_avl_ordered_delete(mut avl: AVLTree<u32>, tree_size: u32) {
-    for v in 0..tree_size / 10 {
-        avl.delete(v);
+fn build_tree<Tree: BenchmarkableTree<u32>>(data: &[u32]) -> Tree {
+    let mut tree = Tree::new();
+    for v in data {
+        tree.insert(*v);
     }
+    return tree;
 }
 
-fn benchmark_rbt_ordered_delete(mut rbt: RBTree<u32>, tree_size: u32) {
-    for v in 0..tree_size / 10 {
-        rbt.delete(v);
+fn benchmark_ordered_insert<Tree: BenchmarkableTree<u32>>(tree_size: u32) {
+    let mut tree = Tree::new();
+    for v in 0..tree_size {
+        tree.insert(v);
     }
 }
 
-// fn benchmark_fast_rbt_ordered_delete(mut fast_rbt: FastRBTree<u32>, tree_size: u32) {
-//     for v in 0..tree_size / 10 {
-//         fast_rbt.delete(v);
-//     }
-// }
+fn benchmark_random_insert<Tree: BenchmarkableTree<u32>>(tree_size: u32) {
+    let data: Vec<u32> = create_shuffled_data(tree_size);
+    build_tree::<Tree>(&data);
+}
 
-fn benchmark_avl_ordered_search(avl: AVLTree<u32>, tree_size: u32) {
+fn benchmark_ordered_delete<Tree: BenchmarkableTree<u32>>(mut tree: Tree, tree_size: u32) {
     for v in 0..tree_size / 10 {
-        avl.contains(v);
+        tree.delete(v);
     }
 }
 
-fn benchmark_rbt_ordered_search(rbt: RBTree<u32>, tree_size: u32) {
-    for v in 0..tree_size / 10 {
-        rbt.contains(v);
+fn benchmark_random_delete<Tree: BenchmarkableTree<u32>>(mut tree: Tree, sample: &[u32]) {
+    for v in sample.iter() {
+        tree.delete(*v);
     }
 }
 
-fn benchmark_fast_rbt_ordered_search(fast_rbt: FastRBTree<u32>, tree_size: u32) {
+fn benchmark_ordered_search<Tree: BenchmarkableTree<u32>>(tree: &Tree, tree_size: u32) {
     for v in 0..tree_size / 10 {
-        fast_rbt.contains(v);
+        black_box(tree.contains(v));
     }
 }
 
-fn benchmark_avl_random_insert(tree_size: u32) {
-    let data: Vec<u32> = create_shuffled_data(tree_size);
-    let mut avl = AVLTree::new();
-    for v in &data {
-        avl.insert(*v);
+fn benchmark_random_search<Tree: BenchmarkableTree<u32>>(tree: &Tree, sample: &[u32]) {
+    for v in sample.iter() {
+        black_box(tree.contains(*v));
     }
 }
 
-fn benchmark_rbt_random_insert(tree_size: u32) {
-    let data: Vec<u32> = create_shuffled_data(tree_size);
-    let mut rbt = RBTree::new();
-    for v in &data {
-        rbt.insert(*v);
+fn benchmark_mixed_workload<Tree: BenchmarkableTree<u32>>(
+    mut tree: Tree,
+    operations: &[Operation],
+) {
+    for operation in operations {
+        match operation {
+            Operation::Contains(v) => {
+                black_box(tree.contains(*v));
+            }
+            Operation::Insert(v) => tree.insert(*v),
+            Operation::Delete(v) => tree.delete(*v),
+        }
     }
 }
 
-fn benchmark_fast_rbt_random_insert(tree_size: u32) {
-    let data: Vec<u32> = create_shuffled_data(tree_size);
-    let mut fast_rbt = FastRBTree::new();
-    for v in &data {
-        fast_rbt.insert(*v);
-    }
+fn benchmark_avl_from_sorted_slice(data: &[u32]) {
+    AVLTree::from_sorted_slice(data);
 }
 
-// fn benchmark_bst_random_insert(tree_size: u32) {
-//     let data: Vec<u32> = create_shuffled_data(tree_size);
-//     let mut avl = BSTree::new();
-//     for v in &data {
-//         avl.insert(v);
-//     }
-// }
-
-// fn benchmark_bst_random_delete(mut bst: BSTree<u32>, tree_size: u32) {
-//     let data: Vec<u32> = create_shuffled_data(tree_size);
-//     let seed = [0u8; 32];
-//     let mut rng: StdRng = SeedableRng::from_seed(seed);
-//     let sample = data
-//         .iter()
-//         .choose_multiple(&mut rng, (tree_size / 100) as usize);
-
-//     for v in sample.iter() {
-//         bst.delete(**v);
-//     }
-// }
-
-fn benchmark_avl_random_delete(mut avl: AVLTree<u32>, tree_size: u32) {
-    let data: Vec<u32> = create_shuffled_data(tree_size);
-    let seed = [0u8; 32];
-    let mut rng: StdRng = SeedableRng::from_seed(seed);
-    let sample = data
-        .iter()
-        .choose_multiple(&mut rng, (tree_size / 100) as usize);
-
-    for v in sample.iter() {
-        avl.delete(**v);
-    }
+fn benchmark_fast_rbt_from_sorted_slice(data: &[u32]) {
+    FastRBTree::from_sorted_slice(data);
 }
 
-fn benchmark_rbt_random_delete(mut rbt: RBTree<u32>, tree_size: u32) {
-    let data: Vec<u32> = create_shuffled_data(tree_size);
-    let seed = [0u8; 32];
-    let mut rng: StdRng = SeedableRng::from_seed(seed);
-    let sample = data
-        .iter()
-        .choose_multiple(&mut rng, (tree_size / 100) as usize);
-
-    for v in sample.iter() {
-        rbt.delete(**v);
-    }
+#[cfg(feature = "rayon")]
+fn benchmark_fast_rbt_parallel_from_sorted_slice(data: &[u32]) {
+    FastRBTree::parallel_from_sorted_slice(data);
 }
 
-// fn benchmark_fast_rbt_random_delete(fast_rbt: FastRBTree<u32>, tree_size: u32) {
-//     let data: Vec<u32> = create_shuffled_data(tree_size);
-//     let seed = [0u8; 32];
-//     let mut rng: StdRng = SeedableRng::from_seed(seed);
-//     let sample = data.iter().choose_multiple(&mut rng, (tree_size / 100) as usize);
-
-//     for v in sample.iter() {
-//         fast_rbt.delete(**v);
-//     }
-// }
-
-fn benchmark_avl_random_search(avl: AVLTree<u32>, tree_size: u32) {
-    let data: Vec<u32> = create_shuffled_data(tree_size);
-    let seed = [0u8; 32];
-    let mut rng: StdRng = SeedableRng::from_seed(seed);
-    let sample = data
-        .iter()
-        .choose_multiple(&mut rng, (tree_size / 100) as usize);
-
+fn benchmark_compact_random_search(compact: &CompactTree<u32>, sample: &[u32]) {
     for v in sample.iter() {
-        avl.contains(**v);
+        black_box(compact.contains(*v));
     }
 }
 
-fn benchmark_rbt_random_search(rbt: RBTree<u32>, tree_size: u32) {
-    let data: Vec<u32> = create_shuffled_data(tree_size);
-    let seed = [0u8; 32];
-    let mut rng: StdRng = SeedableRng::from_seed(seed);
-    let sample = data
-        .iter()
-        .choose_multiple(&mut rng, (tree_size / 100) as usize);
-
-    for v in sample.iter() {
-        rbt.contains(**v);
-    }
+// Helper function for bench_compare_insert, add the benchmarks of one tree
+fn insert_tree<Tree: BenchmarkableTree<u32>>(
+    name: &str,
+    group: &mut BenchmarkGroup<WallTime>,
+    i: usize,
+    size: u32,
+) {
+    group.bench_with_input(
+        BenchmarkId::new(format!("Ordered {}", name), i),
+        &size,
+        |b, n| b.iter(|| benchmark_ordered_insert::<Tree>(*n)),
+    );
+    group.bench_with_input(
+        BenchmarkId::new(format!("Random {}", name), i),
+        &size,
+        |b, n| b.iter(|| benchmark_random_insert::<Tree>(*n)),
+    );
 }
 
-fn benchmark_fast_rbt_random_search(fast_rbt: FastRBTree<u32>, tree_size: u32) {
-    let data: Vec<u32> = create_shuffled_data(tree_size);
-    let seed = [0u8; 32];
-    let mut rng: StdRng = SeedableRng::from_seed(seed);
-    let sample = data
-        .iter()
-        .choose_multiple(&mut rng, (tree_size / 100) as usize);
-
-    for v in sample.iter() {
-        fast_rbt.contains(**v);
-    }
+// Helper function for bench_compare_search, add the benchmarks of one tree
+fn search_tree<Tree: BenchmarkableTree<u32>>(
+    name: &str,
+    group: &mut BenchmarkGroup<WallTime>,
+    i: usize,
+    size: u32,
+    ordered_data: &[u32],
+    random_data: &[u32],
+    sample: &[u32],
+) {
+    group.bench_function(BenchmarkId::new(format!("Ordered {}", name), i), |b| {
+        let tree = build_tree::<Tree>(ordered_data);
+        b.iter(|| benchmark_ordered_search(&tree, size))
+    });
+    group.bench_function(BenchmarkId::new(format!("Random {}", name), i), |b| {
+        let tree = build_tree::<Tree>(random_data);
+        b.iter(|| benchmark_random_search(&tree, sample))
+    });
 }
 
-// fn benchmark_bst_random_search(bst: BSTree<u32>, tree_size: u32) {
-//     let data: Vec<u32> = create_shuffled_data(tree_size);
-//     let seed = [0u8; 32];
-//     let mut rng: StdRng = SeedableRng::from_seed(seed);
-//     let sample = data
-//         .iter()
-//         .choose_multiple(&mut rng, (tree_size / 100) as usize);
+// Helper function for bench_search_hit_vs_miss, add the benchmarks of one tree
+fn search_hit_vs_miss_tree<Tree: BenchmarkableTree<u32>>(
+    name: &str,
+    group: &mut BenchmarkGroup<WallTime>,
+    i: usize,
+    data: &[u32],
+    miss_sample: &[u32],
+    half_hit_sample: &[u32],
+) {
+    group.bench_function(BenchmarkId::new(format!("Miss {}", name), i), |b| {
+        let tree = build_tree::<Tree>(data);
+        b.iter(|| benchmark_random_search(&tree, miss_sample))
+    });
+    group.bench_function(BenchmarkId::new(format!("Half Hit {}", name), i), |b| {
+        let tree = build_tree::<Tree>(data);
+        b.iter(|| benchmark_random_search(&tree, half_hit_sample))
+    });
+}
 
-//     for v in sample.iter() {
-//         bst.contains(**v);
-//     }
-// }
+// Helper function for bench_compare_delete, add the benchmarks of one tree
+fn delete_tree<Tree: BenchmarkableTree<u32>>(
+    name: &str,
+    group: &mut BenchmarkGroup<WallTime>,
+    i: usize,
+    size: u32,
+    ordered_data: &[u32],
+    random_data: &[u32],
+    sample: &[u32],
+) {
+    group.bench_function(BenchmarkId::new(format!("Ordered {}", name), i), |b| {
+        let tree = build_tree::<Tree>(ordered_data);
+        b.iter(|| benchmark_ordered_delete(tree.clone(), size))
+    });
+    group.bench_function(BenchmarkId::new(format!("Random {}", name), i), |b| {
+        let tree = build_tree::<Tree>(random_data);
+        b.iter(|| benchmark_random_delete(tree.clone(), sample))
+    });
+}
 
+// Helper function for bench_mixed_workload, add the benchmarks of one tree
+fn mixed_workload_tree<Tree: BenchmarkableTree<u32>>(
+    name: &str,
+    group: &mut BenchmarkGroup<WallTime>,
+    size: u32,
+    data: &[u32],
+    operations: &[Operation],
+) {
+    group.bench_function(BenchmarkId::new(name, size), |b| {
+        let tree = build_tree::<Tree>(data);
+        b.iter(|| benchmark_mixed_workload(tree.clone(), operations))
+    });
+}
 
 fn bench_compare_insert(c: &mut Criterion) {
     let mut group = c.benchmark_group("Insert");
     for (i, size) in TREE_SIZE.iter().enumerate() {
-        group.bench_with_input(BenchmarkId::new("Ordered AVL", i), size, |b, n| {
-            b.iter(|| benchmark_avl_ordered_insert(*n))
-        });
-        group.bench_with_input(BenchmarkId::new("Ordered RBT", i), size, |b, n| {
-            b.iter(|| benchmark_rbt_ordered_insert(*n));
-        });
-        group.bench_with_input(BenchmarkId::new("Ordered Fast RBT", i), size, |b, n| {
-            b.iter(|| benchmark_fast_rbt_ordered_insert(*n));
-        });
-
-        group.bench_with_input(BenchmarkId::new("Ramdom AVL", i), size, |b, n| {
-            b.iter(|| benchmark_avl_random_insert(*n))
-        });
-        group.bench_with_input(BenchmarkId::new("Ramdom RBT", i), size, |b, n| {
-            b.iter(|| benchmark_rbt_random_insert(*n));
-        });
-        group.bench_with_input(BenchmarkId::new("Ramdom Fast RBT", i), size, |b, n| {
-            b.iter(|| benchmark_fast_rbt_random_insert(*n));
-        });
-        // group.bench_with_input(BenchmarkId::new("Ramdom BST", i), size, |b, n| {
-        //     b.iter(|| benchmark_bst_random_insert(*n));
-        // });
+        for_each_tree!(insert_tree(&mut group, i, *size));
     }
     group.finish();
 }
@@ -242,151 +283,256 @@ fn bench_compare_insert(c: &mut Criterion) {
 fn bench_compare_search(c: &mut Criterion) {
     let mut group = c.benchmark_group("Search");
     for (i, size) in TREE_SIZE.iter().enumerate() {
-        group.bench_function(BenchmarkId::new("Ordered AVL", i), |b| {
-            let mut avl = AVLTree::new();
-            for v in 0..*size {
-                avl.insert(v);
-            }
+        let ordered_data: Vec<u32> = (0..*size).collect();
+        let random_data = create_shuffled_data(*size);
+        let sample = create_sample(*size);
+        for_each_tree!(search_tree(
+            &mut group,
+            i,
+            *size,
+            &ordered_data,
+            &random_data,
+            &sample
+        ));
+    }
+    group.finish();
+}
 
-            b.iter(|| {
-                let avl_cp = avl.clone();
-                benchmark_avl_ordered_search(avl_cp, *size);
-            })
-        });
-        group.bench_function(BenchmarkId::new("Ordered RBT", i), |b| {
-            let mut rbt = RBTree::new();
-            for v in 0..*size {
-                rbt.insert(v);
-            }
+// A search for a missing value always goes down to a leaf, while
+// a search for a value in the tree may stop higher up
+fn bench_search_hit_vs_miss(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Search Hit vs Miss");
+    for (i, size) in TREE_SIZE.iter().enumerate() {
+        let data = create_shuffled_data(*size);
+        // the tree holds 0..size, so size..2 * size are all missing
+        let sample = create_sample(*size);
+        let miss_sample: Vec<u32> = sample.iter().map(|v| v + size).collect();
+        let half_hit_sample: Vec<u32> = sample
+            .iter()
+            .enumerate()
+            .map(|(j, v)| if j % 2 == 0 { *v } else { v + size })
+            .collect();
+        for_each_tree!(search_hit_vs_miss_tree(
+            &mut group,
+            i,
+            &data,
+            &miss_sample,
+            &half_hit_sample
+        ));
+    }
+    group.finish();
+}
 
-            b.iter(|| {
-                let rbt_cp = rbt.clone();
-                benchmark_rbt_ordered_search(rbt_cp, *size);
-            })
-        });
-        group.bench_function(BenchmarkId::new("Ordered Fast RBT", i), |b| {
-            let mut fast_rbt = FastRBTree::new();
-            for v in 0..*size {
-                fast_rbt.insert(v);
-            }
+fn bench_compare_delete(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Delete");
+    for (i, size) in TREE_SIZE.iter().enumerate() {
+        let ordered_data: Vec<u32> = (0..*size).collect();
+        let random_data = create_shuffled_data(*size);
+        let sample = create_sample(*size);
+        for_each_tree!(delete_tree(
+            &mut group,
+            i,
+            *size,
+            &ordered_data,
+            &random_data,
+            &sample
+        ));
+    }
+    group.finish();
+}
 
-            b.iter(|| {
-                let fast_rbt_cp = fast_rbt.clone();
-                benchmark_fast_rbt_ordered_search(fast_rbt_cp, *size);
-            })
-        });
-        group.bench_function(BenchmarkId::new("Random AVL", i), |b| {
-            let data: Vec<u32> = create_shuffled_data(*size);
-            let mut avl = AVLTree::new();
-            for v in &data {
-                avl.insert(*v);
-            }
+fn bench_mixed_workload(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Mixed Workload");
+    for size in TREE_SIZE.iter() {
+        let data: Vec<u32> = create_shuffled_data(*size);
+        let operations = create_mixed_operations(*size);
+        for_each_tree!(mixed_workload_tree(&mut group, *size, &data, &operations));
+    }
+    group.finish();
+}
 
-            b.iter(|| {
-                let avl_cp = avl.clone();
-                benchmark_avl_random_search(avl_cp, *size);
-            })
-        });
-        group.bench_function(BenchmarkId::new("Random RBT", i), |b| {
-            let data: Vec<u32> = create_shuffled_data(*size);
-            let mut rbt = RBTree::new();
-            for v in &data {
-                rbt.insert(*v);
-            }
+fn bench_compare_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Build");
+    for (i, size) in TREE_SIZE.iter().enumerate() {
+        let data: Vec<u32> = (0..*size).collect();
+        group.bench_with_input(
+            BenchmarkId::new("Sequential Inserts AVL", i),
+            size,
+            |b, n| b.iter(|| benchmark_ordered_insert::<AVLTree<u32>>(*n)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Sequential Inserts FastRBT", i),
+            size,
+            |b, n| b.iter(|| benchmark_ordered_insert::<FastRBTree<u32>>(*n)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("FromSortedSlice AVL", i),
+            &data,
+            |b, data| b.iter(|| benchmark_avl_from_sorted_slice(data)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("FromSortedSlice FastRBT", i),
+            &data,
+            |b, data| b.iter(|| benchmark_fast_rbt_from_sorted_slice(data)),
+        );
+        #[cfg(feature = "rayon")]
+        group.bench_with_input(
+            BenchmarkId::new("ParallelFromSortedSlice FastRBT", i),
+            &data,
+            |b, data| b.iter(|| benchmark_fast_rbt_parallel_from_sorted_slice(data)),
+        );
+    }
+    group.finish();
+}
 
-            b.iter(|| {
-                let rbt_cp = rbt.clone();
-                benchmark_rbt_random_search(rbt_cp, *size);
-            })
+fn bench_arena_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Arena Insert");
+    group.sample_size(10);
+    group.bench_with_input("Random ArenaRBTree", &100_000, |b, n| {
+        b.iter(|| benchmark_random_insert::<ArenaRBTree<u32>>(*n))
+    });
+    group.bench_with_input("Random BoxRBTree", &100_000, |b, n| {
+        b.iter(|| benchmark_random_insert::<BoxRBTree<u32>>(*n))
+    });
+    group.bench_with_input("Random Fast RBT", &100_000, |b, n| {
+        b.iter(|| benchmark_random_insert::<FastRBTree<u32>>(*n))
+    });
+    group.finish();
+}
+
+fn bench_conversion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Conversion");
+    group.sample_size(10);
+    let data: Vec<u32> = (0..100_000).collect();
+    let fast_rbt = FastRBTree::from_sorted_slice(&data);
+    let avl = AVLTree::from_sorted_slice(&data);
+    let mut rbt = RBTree::new();
+    rbt.extend(data.iter().copied());
+    group.bench_function("FastRBT into AVL", |b| {
+        b.iter(|| fast_rbt.clone().into_avl())
+    });
+    group.bench_function("FastRBT from AVL", |b| {
+        b.iter(|| FastRBTree::from_avl(avl.clone()))
+    });
+    group.bench_function("FastRBT into RBT", |b| {
+        b.iter(|| fast_rbt.clone().into_rbt())
+    });
+    group.bench_function("FastRBT from RBT", |b| {
+        b.iter(|| FastRBTree::from_rbt(rbt.clone()))
+    });
+    group.finish();
+}
+
+// Searches only, so that the cache behaviour of the two layouts is compared
+fn bench_compact_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Compact Search");
+    for (i, size) in TREE_SIZE.iter().enumerate() {
+        let data: Vec<u32> = (0..*size).collect();
+        let sample = create_shuffled_data(*size);
+        let compact = CompactTree::from_sorted_slice(&data);
+        let fast_rbt = FastRBTree::from_sorted_slice(&data);
+        group.bench_function(BenchmarkId::new("Random CompactTree", i), |b| {
+            b.iter(|| benchmark_compact_random_search(&compact, &sample))
         });
         group.bench_function(BenchmarkId::new("Random Fast RBT", i), |b| {
-            let mut fast_rbt = FastRBTree::new();
-            for v in 0..*size {
-                fast_rbt.insert(v);
-            }
-
-            b.iter(|| {
-                let fast_rbt_cp = fast_rbt.clone();
-                benchmark_fast_rbt_random_search(fast_rbt_cp, *size);
-            })
+            b.iter(|| benchmark_random_search(&fast_rbt, &sample))
         });
-        // group.bench_function(BenchmarkId::new("Random BST", i), |b| {
-        //     let mut bst = BSTree::new();
-        //     for v in 0..*size {
-        //         bst.insert(v);
-        //     }
-
-        //     b.iter(|| {
-        //         let bst_cp = bst.clone();
-        //         benchmark_bst_random_search(bst_cp, *size);
-        //     })
-        // });
     }
     group.finish();
 }
 
-fn bench_compare_delete(c: &mut Criterion) {
-    let mut group = c.benchmark_group("Delete");
-    for (i, size) in TREE_SIZE.iter().enumerate() {
-        group.bench_function(BenchmarkId::new("Ordered AVL", i), |b| {
-            let mut avl = AVLTree::new();
-            for v in 0..*size {
-                avl.insert(v);
-            }
-
-            b.iter(|| {
-                let avl_cp = avl.clone();
-                benchmark_avl_ordered_delete(avl_cp, *size);
-            })
-        });
-        group.bench_function(BenchmarkId::new("Ordered RBT", i), |b| {
-            let mut rbt = RBTree::new();
-            for v in 0..*size {
-                rbt.insert(v);
-            }
+// The lazy intersection against the same values inserted into a new tree
+fn bench_intersection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Intersection");
+    group.sample_size(10);
+    let evens: Vec<u32> = (0..100_000).map(|v| v * 2).collect();
+    let threes: Vec<u32> = (0..100_000).map(|v| v * 3).collect();
+    let first = FastRBTree::from_sorted_slice(&evens);
+    let second = FastRBTree::from_sorted_slice(&threes);
+    group.bench_function("Lazy intersection_iter", |b| {
+        b.iter(|| black_box(first.intersection_iter(&second).count()))
+    });
+    group.bench_function("Materialized intersection", |b| {
+        b.iter(|| {
+            let mut common = FastRBTree::new();
+            first
+                .iter()
+                .filter(|v| CommonTreeTrait::contains(&second, *v))
+                .for_each(|v| common.insert(v));
+            black_box(common.iter().count())
+        })
+    });
+    group.finish();
+}
 
-            b.iter(|| {
-                let rbt_cp = rbt.clone();
-                benchmark_rbt_ordered_delete(rbt_cp, *size);
-            })
-        });
-        group.bench_function(BenchmarkId::new("Random AVL", i), |b| {
-            let data: Vec<u32> = create_shuffled_data(*size);
-            let mut avl = AVLTree::new();
-            for v in &data {
-                avl.insert(*v);
-            }
+// Sorting through a tree against the standard library sort
+fn bench_merge_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Sort");
+    group.sample_size(10);
+    let data = create_shuffled_data(100_000);
+    group.bench_function("algorithms::merge_sort", |b| {
+        b.iter(|| merge_sort(data.clone()))
+    });
+    group.bench_function("Vec::sort", |b| {
+        b.iter(|| {
+            let mut sorted = data.clone();
+            sorted.sort();
+            sorted
+        })
+    });
+    group.finish();
+}
 
-            b.iter(|| {
-                let avl_cp = avl.clone();
-                benchmark_avl_random_delete(avl_cp, *size);
-            })
-        });
-        group.bench_function(BenchmarkId::new("Random RBT", i), |b| {
-            let data: Vec<u32> = create_shuffled_data(*size);
-            let mut rbt = RBTree::new();
-            for v in &data {
-                rbt.insert(*v);
-            }
+// Searches in a tree built by random inserts, before and after rebuilding it
+fn bench_rebalance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Rebalance");
+    let sample = create_shuffled_data(100_000);
+    let degraded: FastRBTree<u32> = build_tree(&sample);
+    let mut rebalanced = degraded.clone();
+    rebalanced.rebalance_from_scratch();
+    group.bench_function("Search before rebalance", |b| {
+        b.iter(|| benchmark_random_search(&degraded, &sample))
+    });
+    group.bench_function("Search after rebalance", |b| {
+        b.iter(|| benchmark_random_search(&rebalanced, &sample))
+    });
+    group.finish();
+}
 
-            b.iter(|| {
-                let rbt_cp = rbt.clone();
-                benchmark_rbt_random_delete(rbt_cp, *size);
-            })
-        });
-        // group.bench_function(BenchmarkId::new("Random BST", i), |b| {
-        //     let data: Vec<u32> = create_shuffled_data(*size);
-        //     let mut bst = BSTree::new();
-        //     for v in &data {
-        //         bst.insert(*v);
-        //     }
-
-        //     b.iter(|| {
-        //         let bst_cp = bst.clone();
-        //         benchmark_bst_random_delete(bst_cp, *size);
-        //     })
-        // });
-    }
+// The unbalanced BSTree as a baseline. It is kept out of for_each_tree since
+// ordered inserts make it a list, which takes O(n) per operation and recurses
+// once per value, so the ordered data is much smaller than TREE_SIZE.
+fn bench_bst_baseline(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BST Baseline");
+    group.sample_size(10);
+    let size = 10_000;
+    group.bench_with_input("Random insert BSTree", &size, |b, n| {
+        b.iter(|| benchmark_random_insert::<BSTree<u32>>(*n))
+    });
+    group.bench_with_input("Random insert Fast RBT", &size, |b, n| {
+        b.iter(|| benchmark_random_insert::<FastRBTree<u32>>(*n))
+    });
+    let random_data = create_shuffled_data(size);
+    let sample = create_sample(size);
+    let bst: BSTree<u32> = build_tree(&random_data);
+    let fast_rbt: FastRBTree<u32> = build_tree(&random_data);
+    group.bench_function("Random search BSTree", |b| {
+        b.iter(|| benchmark_random_search(&bst, &sample))
+    });
+    group.bench_function("Random search Fast RBT", |b| {
+        b.iter(|| benchmark_random_search(&fast_rbt, &sample))
+    });
+
+    let ordered_size = 2_000;
+    let ordered_data: Vec<u32> = (0..ordered_size).collect();
+    let bst: BSTree<u32> = build_tree(&ordered_data);
+    let fast_rbt: FastRBTree<u32> = build_tree(&ordered_data);
+    group.bench_function("Ordered search BSTree", |b| {
+        b.iter(|| benchmark_ordered_search(&bst, ordered_size))
+    });
+    group.bench_function("Ordered search Fast RBT", |b| {
+        b.iter(|| benchmark_ordered_search(&fast_rbt, ordered_size))
+    });
     group.finish();
 }
 
@@ -394,6 +540,16 @@ criterion_group!(
     benches,
     bench_compare_insert,
     bench_compare_search,
-    bench_compare_delete
+    bench_search_hit_vs_miss,
+    bench_compare_delete,
+    bench_mixed_workload,
+    bench_compare_build,
+    bench_compact_search,
+    bench_arena_insert,
+    bench_conversion,
+    bench_intersection,
+    bench_merge_sort,
+    bench_rebalance,
+    bench_bst_baseline
 );
 criterion_main!(benches);
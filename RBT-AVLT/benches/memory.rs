@@ -0,0 +1,102 @@
+#![allow(clippy::needless_return)]
+
+// Heap usage of the trees, in its own bench target so that the counting
+// allocator does not slow down the timings of benchmarks.rs
+
+use tree_collections::prelude::*;
+
+use rand::seq::SliceRandom;
+use rand::{rngs::StdRng, SeedableRng};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const TREE_SIZE: [u32; 5] = [10_000, 40_000, 70_000, 100_000, 130_000];
+
+// Keep track of the bytes currently allocated on the heap
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        return ptr;
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn create_shuffled_data(tree_size: u32) -> Vec<u32> {
+    let seed = [0u8; 32];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+    let mut data: Vec<u32> = (0..tree_size).collect();
+    data.shuffle(&mut rng);
+    return data;
+}
+
+// Return the bytes still allocated by the tree once it is built,
+// divided by the number of values
+fn bytes_per_element<Tree, F: FnOnce() -> Tree>(tree_size: u32, build: F) -> f64 {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let tree = build();
+    let after = ALLOCATED.load(Ordering::Relaxed);
+    drop(tree);
+    return after.saturating_sub(before) as f64 / tree_size as f64;
+}
+
+fn bench_compare_memory() {
+    println!(
+        "{:>8} {:>8} {:>8} {:>10} {:>12} {:>10} {:>12}",
+        "size", "AVL", "RBT", "Fast RBT", "ArenaRBTree", "BoxRBTree", "CompactTree"
+    );
+    for size in TREE_SIZE.iter() {
+        let data = create_shuffled_data(*size);
+        let mut sorted = data.clone();
+        sorted.sort_unstable();
+        let avl = bytes_per_element(*size, || {
+            let mut tree = AVLTree::new();
+            data.iter().for_each(|v| tree.insert(*v));
+            tree
+        });
+        let rbt = bytes_per_element(*size, || {
+            let mut tree = RBTree::new();
+            data.iter().for_each(|v| tree.insert(*v));
+            tree
+        });
+        let fast_rbt = bytes_per_element(*size, || {
+            let mut tree = FastRBTree::new();
+            data.iter().for_each(|v| tree.insert(*v));
+            tree
+        });
+        let arena_rbt = bytes_per_element(*size, || {
+            let mut tree = ArenaRBTree::new();
+            data.iter().for_each(|v| tree.insert(*v));
+            tree
+        });
+        let box_rbt = bytes_per_element(*size, || {
+            let mut tree = BoxRBTree::new();
+            data.iter().for_each(|v| tree.insert(*v));
+            tree
+        });
+        // inserting rebuilds the whole array, the final layout is the same
+        let compact = bytes_per_element(*size, || CompactTree::from_sorted_slice(&sorted));
+        println!(
+            "{:>8} {:>8.1} {:>8.1} {:>10.1} {:>12.1} {:>10.1} {:>12.1}",
+            size, avl, rbt, fast_rbt, arena_rbt, box_rbt, compact
+        );
+    }
+}
+
+fn main() {
+    println!("Heap bytes per element once the tree is built");
+    bench_compare_memory();
+}
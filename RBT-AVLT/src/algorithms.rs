@@ -0,0 +1,368 @@
+//! Classic algorithms built on the trees
+//!
+//! # Example
+//!
+//! ```
+//! use tree_collections::algorithms::count_inversions;
+//! assert_eq!(4, count_inversions(&[4, 1, 3, 2]));
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+
+use crate::common_trait::CommonTreeTrait;
+use crate::rb_tree_fast::FastRBTree;
+
+// Helper struct for count_inversions and tree_sort_stable, equal values are told apart
+// by their position so that the tree, which keeps one copy of a value, stores all of them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Indexed<T> {
+    value: T,
+    index: usize,
+}
+
+impl<T: Ord> Ord for Indexed<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return self
+            .value
+            .cmp(&other.value)
+            .then(self.index.cmp(&other.index));
+    }
+}
+
+impl<T: Ord> PartialOrd for Indexed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl<T: Display> Display for Indexed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// Return the number of pairs `i < j` with `data[i] > data[j]`
+///
+/// The values are inserted into a [FastRBTree](../rb_tree_fast/struct.FastRBTree.html)
+/// one by one, and the `rank` of each value counts the earlier values which are not
+/// greater than it, the others being the inversions it closes. Equal values are
+/// not an inversion.
+///
+/// The nodes do not store the size of their subtree, so each
+/// [rank](../common_trait/trait.CommonTreeTrait.html#method.rank) walks the values
+/// before the item in O(rank + log n), and the whole count takes O(n²) time in the
+/// worst case, when the data is mostly increasing. It is meant to show the order
+/// statistics of the trees; a merge sort counts the inversions in O(n log n) for
+/// large inputs.
+///
+/// # Example
+///
+/// ```
+/// use tree_collections::algorithms::count_inversions;
+/// assert_eq!(2, count_inversions(&[3, 1, 2]));
+/// assert_eq!(0, count_inversions(&[1, 2, 3]));
+/// assert_eq!(0, count_inversions(&[2, 2]));
+/// ```
+#[must_use = "calling count_inversions without using the result has no effect"]
+pub fn count_inversions<T: Ord + Copy + Debug + Display>(data: &[T]) -> usize {
+    let mut tree = FastRBTree::new();
+    let mut inversions = 0;
+    for (index, value) in data.iter().enumerate() {
+        let item = Indexed {
+            value: *value,
+            index,
+        };
+        // the earlier values less than or equal to the value sort before the item
+        inversions += index - tree.rank(item);
+        tree.insert(item);
+    }
+    return inversions;
+}
+
+/// Rearrange `perm` into the next permutation in lexicographic order and return true,
+/// like `std::next_permutation` in C++. If `perm` is already the last one, it becomes
+/// the first one and false is returned.
+///
+/// `perm` holds distinct values taken from `pool`. It can be shorter than the pool,
+/// then the permutations of that many values out of the pool are visited, and the
+/// values in the pool but not in `perm` are used as well. Each step runs in
+/// O(n log n) for n values in the pool.
+///
+/// # Example
+///
+/// ```
+/// use tree_collections::algorithms::next_permutation;
+/// use tree_collections::prelude::*;
+/// let pool = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+/// let mut perm = vec![1, 3, 2];
+/// assert!(next_permutation(&mut perm, &pool));
+/// assert_eq!(vec![2, 1, 3], perm);
+///
+/// let mut perm = vec![3, 2];
+/// assert!(!next_permutation(&mut perm, &pool));
+/// assert_eq!(vec![1, 2], perm);
+/// ```
+pub fn next_permutation<T: Ord + Copy + Debug + Display>(
+    perm: &mut Vec<T>,
+    pool: &FastRBTree<T>,
+) -> bool {
+    let mut unused = pool.clone();
+    perm.iter().for_each(|v| unused.delete(*v));
+    // from the right, find the first position which can take a greater unused value
+    for i in (0..perm.len()).rev() {
+        unused.insert(perm[i]);
+        if let Some(next) = unused.successor(perm[i]) {
+            perm[i] = next;
+            unused.delete(next);
+            // the rest is the smallest arrangement of the unused values
+            let rest: Vec<T> = unused.iter().take(perm.len() - i - 1).collect();
+            perm.splice(i + 1.., rest);
+            return true;
+        }
+    }
+    let first: Vec<T> = unused.iter().take(perm.len()).collect();
+    *perm = first;
+    return false;
+}
+
+/// Rearrange `perm` into the previous permutation in lexicographic order and return
+/// true, like `std::prev_permutation` in C++. If `perm` is already the first one, it
+/// becomes the last one and false is returned. See
+/// [next_permutation](fn.next_permutation.html) for the values `perm` can hold.
+///
+/// # Example
+///
+/// ```
+/// use tree_collections::algorithms::prev_permutation;
+/// use tree_collections::prelude::*;
+/// let pool = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+/// let mut perm = vec![2, 1, 3];
+/// assert!(prev_permutation(&mut perm, &pool));
+/// assert_eq!(vec![1, 3, 2], perm);
+/// ```
+pub fn prev_permutation<T: Ord + Copy + Debug + Display>(
+    perm: &mut Vec<T>,
+    pool: &FastRBTree<T>,
+) -> bool {
+    let mut unused = pool.clone();
+    perm.iter().for_each(|v| unused.delete(*v));
+    // from the right, find the first position which can take a smaller unused value
+    for i in (0..perm.len()).rev() {
+        unused.insert(perm[i]);
+        if let Some(previous) = unused.predecessor(perm[i]) {
+            perm[i] = previous;
+            unused.delete(previous);
+            // the rest is the largest arrangement of the unused values
+            let rest: Vec<T> = unused
+                .to_sorted_vec()
+                .into_iter()
+                .rev()
+                .take(perm.len() - i - 1)
+                .collect();
+            perm.splice(i + 1.., rest);
+            return true;
+        }
+    }
+    let last: Vec<T> = unused
+        .to_sorted_vec()
+        .into_iter()
+        .rev()
+        .take(perm.len())
+        .collect();
+    *perm = last;
+    return false;
+}
+
+/// Sort the values by inserting them into a [FastRBTree](../rb_tree_fast/struct.FastRBTree.html)
+/// and reading them back in order, in O(n log n)
+///
+/// The tree keeps one copy of each value, so duplicates are removed and the result
+/// can be shorter than `data`. Use `Vec::sort` when duplicates must be kept, it is
+/// also faster since it does not allocate a node per value.
+///
+/// # Example
+///
+/// ```
+/// use tree_collections::algorithms::merge_sort;
+/// assert_eq!(vec![1, 2, 3], merge_sort(vec![3, 1, 2, 3, 1]));
+/// ```
+#[must_use = "calling merge_sort without using the result has no effect"]
+pub fn merge_sort<T: Ord + Copy + Debug + Display>(data: Vec<T>) -> Vec<T> {
+    let mut tree = FastRBTree::new();
+    tree.extend(data);
+    return tree.to_sorted_vec();
+}
+
+/// Sort the values with a [FastRBTree](../rb_tree_fast/struct.FastRBTree.html) in
+/// O(n log n), keeping the duplicates which [merge_sort](fn.merge_sort.html) removes
+///
+/// Each value is stored together with its position in `data`, so equal values stay
+/// in the order they were given and the sort is stable.
+///
+/// # Example
+///
+/// ```
+/// use tree_collections::algorithms::tree_sort_stable;
+/// assert_eq!(vec![1, 1, 2, 3, 3], tree_sort_stable(vec![3, 1, 2, 3, 1]));
+/// ```
+#[must_use = "calling tree_sort_stable without using the result has no effect"]
+pub fn tree_sort_stable<T: Ord + Copy + Debug + Display>(data: Vec<T>) -> Vec<T> {
+    let mut tree = FastRBTree::new();
+    tree.extend(
+        data.into_iter()
+            .enumerate()
+            .map(|(index, value)| Indexed { value, index }),
+    );
+    return tree.iter().map(|item| item.value).collect();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_count_inversions() {
+        assert_eq!(count_inversions::<u32>(&[]), 0);
+        assert_eq!(count_inversions(&[3, 1, 2]), 2);
+        assert_eq!(count_inversions(&[1, 2, 3]), 0);
+        assert_eq!(count_inversions(&[5, 4, 3, 2, 1]), 10);
+        assert_eq!(count_inversions(&[2, 1, 2, 1]), 3);
+
+        let data: Vec<u32> = (0..200).map(|v| (v * 37) % 101).collect();
+        let mut expected = 0;
+        for i in 0..data.len() {
+            for j in i + 1..data.len() {
+                if data[i] > data[j] {
+                    expected += 1;
+                }
+            }
+        }
+        assert_eq!(count_inversions(&data), expected);
+    }
+
+    #[test]
+    fn test_next_permutation() {
+        let pool = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+        let mut perm = vec![1, 2, 3];
+        let mut seen = vec![perm.clone()];
+        while next_permutation(&mut perm, &pool) {
+            seen.push(perm.clone());
+        }
+        assert_eq!(
+            seen,
+            vec![
+                vec![1, 2, 3],
+                vec![1, 3, 2],
+                vec![2, 1, 3],
+                vec![2, 3, 1],
+                vec![3, 1, 2],
+                vec![3, 2, 1],
+            ]
+        );
+        // it wrapped around to the first permutation
+        assert_eq!(perm, vec![1, 2, 3]);
+
+        // and prev_permutation walks the same list backwards
+        let mut perm = vec![3, 2, 1];
+        let mut seen_backwards = vec![perm.clone()];
+        while prev_permutation(&mut perm, &pool) {
+            seen_backwards.push(perm.clone());
+        }
+        seen_backwards.reverse();
+        assert_eq!(seen_backwards, seen);
+        assert_eq!(perm, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_next_permutation_of_part_of_the_pool() {
+        let pool = FastRBTree::from_sorted_slice(&[1, 2, 3, 4]);
+        let mut perm = vec![1, 2];
+        let mut count = 1;
+        while next_permutation(&mut perm, &pool) {
+            count += 1;
+        }
+        assert_eq!(count, 12);
+
+        let mut perm = vec![2, 4];
+        assert!(next_permutation(&mut perm, &pool));
+        assert_eq!(perm, vec![3, 1]);
+        assert!(prev_permutation(&mut perm, &pool));
+        assert_eq!(perm, vec![2, 4]);
+
+        let mut empty: Vec<u32> = vec![];
+        assert!(!next_permutation(&mut empty, &pool));
+        assert_eq!(empty, vec![]);
+    }
+
+    #[test]
+    fn test_merge_sort() {
+        assert_eq!(merge_sort::<u32>(vec![]), vec![]);
+
+        let data: Vec<u32> = (0..500).map(|v| (v * 37) % 211).collect();
+        let sorted = merge_sort(data.clone());
+        assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]));
+        let mut unique = data.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(sorted.len(), unique.len());
+        assert_eq!(sorted, unique);
+    }
+
+    // Sorted by key only, so that the order of equal keys can be checked
+    #[derive(Clone, Copy, Debug)]
+    struct Record {
+        key: u32,
+        id: u32,
+    }
+
+    impl PartialEq for Record {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Record {}
+
+    impl PartialOrd for Record {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Record {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.key.cmp(&other.key)
+        }
+    }
+
+    impl Display for Record {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.key)
+        }
+    }
+
+    #[test]
+    fn test_tree_sort_stable() {
+        assert_eq!(tree_sort_stable::<u32>(vec![]), vec![]);
+
+        let data: Vec<u32> = (0..500).map(|v| (v * 37) % 211).collect();
+        let sorted = tree_sort_stable(data.clone());
+        let mut expected = data.clone();
+        expected.sort();
+        assert_eq!(sorted, expected);
+
+        let records: Vec<Record> = (0..300)
+            .map(|id| Record {
+                key: (id * 7) % 10,
+                id,
+            })
+            .collect();
+        let sorted = tree_sort_stable(records.clone());
+        assert_eq!(sorted.len(), records.len());
+        assert!(sorted.windows(2).all(|pair| pair[0].key < pair[1].key
+            || (pair[0].key == pair[1].key && pair[0].id < pair[1].id)));
+        // merge_sort keeps one record per key
+        assert_eq!(merge_sort(records).len(), 10);
+    }
+}
@@ -0,0 +1,1057 @@
+//! AVL tree
+//!
+//! You can generate an AVL tree, and insert or delete nodes.
+//!
+//! Every node stores the height of its subtree. After an insert or a delete the
+//! nodes on the path back to the root are checked, and any node whose subtrees
+//! differ in height by more than one is fixed with a single or a double rotation.
+//! The tree therefore stays strictly balanced, which makes lookups slightly faster
+//! than in a red-black tree at the cost of more rotations on update.
+//!
+//! | Operation                       | Time complexity |
+//! |---------------------------------|-----------------|
+//! | `insert`, `delete`, `contains`  | O(log n)        |
+//! | `min`, `max`                    | O(log n)        |
+//! | `iter` (whole tree)             | O(n)            |
+//! | `height`, `count_leaves`        | O(n)            |
+//!
+//! # Example
+//!
+//! ```
+//! use tree_collections::prelude::*;
+//!
+//! let mut tree = AVLTree::new();
+//! (1..=7).for_each(|v| tree.insert(v));
+//! assert_eq!(3, tree.height());
+//! tree.delete(4);
+//! assert_eq!(false, tree.contains(4));
+//! assert_eq!(vec![1, 2, 3, 5, 6, 7], tree.iter().collect::<Vec<_>>());
+//! ```
+
+use std::fmt::{self, Debug, Display};
+
+use crate::common_trait::{AlreadyExists, DebugSubtree, InOrderIter, DEBUG_DEPTH};
+pub use crate::common_trait::{CommonTreeNodeTrait, CommonTreeTrait};
+use crate::shared_node::{NodeCell, Shared};
+
+type AVLTreeNode<T> = Shared<NodeCell<TreeNode<T>>>;
+type OptionAVLTreeNode<T> = Option<AVLTreeNode<T>>;
+
+/// Node struct for AVLTree
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeNode<T: Ord + Copy + Debug + Display> {
+    pub value: T,
+    left: OptionAVLTreeNode<T>,
+    right: OptionAVLTreeNode<T>,
+    height: usize,
+}
+
+// extend from common tree trait
+impl<T: Ord + Copy + Debug + Display> CommonTreeTrait<T, TreeNode<T>> for AVLTree<T> {
+    fn get_root(&self) -> OptionAVLTreeNode<T> {
+        return self.root.clone();
+    }
+}
+
+/// Iterate over the values of a borrowed tree in ascending order
+impl<'a, T: Ord + Copy + Debug + Display> IntoIterator for &'a AVLTree<T> {
+    type Item = T;
+    type IntoIter = InOrderIter<'a, T, TreeNode<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+/// Copy every node, so that the copy and the original can change independently
+impl<T: Ord + Copy + Debug + Display> Clone for AVLTree<T> {
+    fn clone(&self) -> Self {
+        return AVLTree {
+            root: TreeNode::deep_clone(&self.root),
+        };
+    }
+}
+
+/// Trees are equal when they have the same shape and values,
+/// which is an equivalence relation since `T: Ord` implies `T: Eq`
+impl<T: Ord + Copy + Debug + Display> Eq for AVLTree<T> {}
+
+/// Print the structure of the tree, nodes deeper than five levels are shown as `...`
+impl<T: Ord + Copy + Debug + Display> Debug for AVLTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let root: DebugSubtree<T, TreeNode<T>> = DebugSubtree::new(self.root.clone(), DEBUG_DEPTH);
+        return f.debug_struct("AVLTree").field("root", &root).finish();
+    }
+}
+
+/// Insert every value of an iterator, values already in the tree are kept once
+impl<T: Ord + Copy + Debug + Display> Extend<T> for AVLTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for v in iter {
+            self.insert(v);
+        }
+    }
+}
+
+// extend from common tree node trait
+impl<T: Ord + Copy + Debug + Display> CommonTreeNodeTrait<T> for TreeNode<T> {
+    fn get_left(&self) -> OptionAVLTreeNode<T> {
+        return self.left.clone();
+    }
+
+    fn get_right(&self) -> OptionAVLTreeNode<T> {
+        return self.right.clone();
+    }
+
+    fn get_value(&self) -> T {
+        return self.value;
+    }
+
+    fn get_value_to_print(&self) -> String {
+        return self.value.to_string();
+    }
+}
+
+/// Implementations of AVLTreeNode
+impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
+    // Helper function for Clone, copy every node of the subtree
+    fn deep_clone(node: &OptionAVLTreeNode<T>) -> OptionAVLTreeNode<T> {
+        let node = node.as_ref()?.borrow();
+        return Some(Shared::new(NodeCell::new(TreeNode {
+            value: node.value,
+            left: Self::deep_clone(&node.left),
+            right: Self::deep_clone(&node.right),
+            height: node.height,
+        })));
+    }
+
+    /// Create a new node of type OptionAVLTreeNode , which will be called by [AVLTree](struct.AVLTree.html)
+    fn new(value: T) -> OptionAVLTreeNode<T> {
+        Some(Shared::new(NodeCell::new(Self {
+            value,
+            left: None,
+            right: None,
+            height: 1, // default height of a new node is 1，which is a leave
+        })))
+    }
+
+    /// Build a balanced subtree from sorted data, which will be called by
+    /// [AVLTree.from_sorted_slice](struct.AVLTree.html#method.from_sorted_slice)
+    fn build_from_sorted(data: &[T]) -> OptionAVLTreeNode<T> {
+        if data.is_empty() {
+            return None;
+        }
+        let mid = data.len() / 2;
+        let left = Self::build_from_sorted(&data[..mid]);
+        let right = Self::build_from_sorted(&data[mid + 1..]);
+        let height = left
+            .as_ref()
+            .map_or(0, |n| n.borrow().height)
+            .max(right.as_ref().map_or(0, |n| n.borrow().height))
+            + 1;
+        Some(Shared::new(NodeCell::new(Self {
+            value: data[mid],
+            left,
+            right,
+            height,
+        })))
+    }
+}
+
+#[derive(PartialEq)]
+pub struct AVLTree<T: Ord + Copy + Debug + Display> {
+    root: OptionAVLTreeNode<T>,
+}
+
+impl<T: Ord + Copy + Debug + Display> Default for AVLTree<T> {
+    fn default() -> Self {
+        return AVLTree::new();
+    }
+}
+
+/// Implementations of AVLTree
+impl<T: Ord + Copy + Debug + Display> AVLTree<T> {
+    /// Creates a new AVL tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::avl_tree::AVLTree;
+    /// let mut avl_tree: AVLTree<u32> = AVLTree::new();
+    /// ```
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    /// Build an AVL tree from a sorted slice without duplicates in O(n),
+    /// the input is not checked
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::avl_tree::AVLTree;
+    /// let avl_tree = AVLTree::from_sorted_slice(&[1, 2, 3, 4, 5]);
+    /// ```
+    pub fn from_sorted_slice(data: &[T]) -> Self {
+        Self {
+            root: TreeNode::build_from_sorted(data),
+        }
+    }
+
+    /// Return the balance factor (right height - left height) of the node
+    /// storing the given value, or None if the value is not in the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::avl_tree::AVLTree;
+    /// let mut avl_tree = AVLTree::new();
+    /// avl_tree.insert(2);
+    /// avl_tree.insert(1);
+    /// assert_eq!(Some(-1), avl_tree.get_balance_factor(2));
+    /// assert_eq!(Some(0), avl_tree.get_balance_factor(1));
+    /// assert_eq!(None, avl_tree.get_balance_factor(3));
+    /// ```
+    #[must_use = "calling get_balance_factor without using the result has no effect"]
+    pub fn get_balance_factor(&self, value: T) -> Option<i32> {
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let node_value = node.borrow().value;
+            if value == node_value {
+                return Some(
+                    self.get_right_height(&node) as i32 - self.get_left_height(&node) as i32,
+                );
+            } else if value < node_value {
+                current = node.borrow().left.clone();
+            } else {
+                current = node.borrow().right.clone();
+            }
+        }
+        return None;
+    }
+
+    pub fn preorder_traverse(&self, node: AVLTreeNode<T>, container: &mut Vec<T>) {
+        container.push(node.borrow().value);
+        let left = node.borrow().left.clone();
+        if let Some(left) = left {
+            self.preorder_traverse(left, container);
+        }
+        let right = node.borrow().right.clone();
+        if let Some(right) = right {
+            self.preorder_traverse(right, container);
+        }
+    }
+
+    pub fn in_order_traverse(&self, node: AVLTreeNode<T>, container: &mut Vec<T>) {
+        let left = node.borrow().left.clone();
+        if let Some(left) = left {
+            self.in_order_traverse(left, container);
+        }
+        container.push(node.borrow().value);
+        let right = node.borrow().right.clone();
+        if let Some(right) = right {
+            self.in_order_traverse(right, container);
+        }
+    }
+
+    /// Judge if the AVL tree is empty, replaced by
+    /// [is_empty](../common_trait/trait.CommonTreeTrait.html#method.is_empty)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut avl_tree = AVLTree::<u32>::new();
+    /// // before
+    /// #[allow(deprecated)]
+    /// let empty = avl_tree.is_tree_empty();
+    /// // after
+    /// assert_eq!(empty, avl_tree.is_empty());
+    /// ```
+    #[deprecated(since = "0.1.9", note = "use is_empty instead")]
+    #[must_use = "calling is_tree_empty without using the result has no effect"]
+    pub fn is_tree_empty(&self) -> bool {
+        self.is_empty()
+    }
+
+    /// Insert a new value to the AVL tree
+    ///
+    /// If the tree already holds a value which compares equal to the new one,
+    /// the stored value is replaced by the new one, so for a type whose `Ord`
+    /// only looks at part of its fields the other fields are updated.
+    /// Use [insert_if_absent](struct.AVLTree.html#method.insert_if_absent)
+    /// to keep the stored value instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::avl_tree::AVLTree;
+    /// let mut avl_tree = AVLTree::new();
+    /// avl_tree.insert(1);
+    /// ```
+    pub fn insert(&mut self, insert_value: T) {
+        let root = self.root.take();
+        // TreeNode is type OptionAVLTreeNode, so the code is simplified.
+        match root {
+            None => self.root = TreeNode::new(insert_value),
+            Some(n) => self.root = self.node_insert(Some(n), insert_value),
+        }
+    }
+
+    /// Insert a new value only if no equal value is in the tree,
+    /// return true if the value was inserted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::avl_tree::AVLTree;
+    /// let mut avl_tree = AVLTree::new();
+    /// assert_eq!(true, avl_tree.insert_if_absent(1));
+    /// assert_eq!(false, avl_tree.insert_if_absent(1));
+    /// ```
+    pub fn insert_if_absent(&mut self, insert_value: T) -> bool {
+        if self.contains(insert_value) {
+            return false;
+        }
+        self.insert(insert_value);
+        return true;
+    }
+
+    /// Insert every value which is not in the tree yet, return how many were inserted
+    ///
+    /// Values already in the tree, or repeated in `iter`, are skipped like
+    /// [insert_if_absent](struct.AVLTree.html#method.insert_if_absent) does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::avl_tree::AVLTree;
+    /// let mut avl_tree = AVLTree::new();
+    /// assert_eq!(3, avl_tree.insert_many_unique(vec![1, 2, 3, 2, 1]));
+    /// assert_eq!(1, avl_tree.insert_many_unique(vec![3, 4]));
+    /// ```
+    pub fn insert_many_unique<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        return iter
+            .into_iter()
+            .filter(|value| self.insert_if_absent(*value))
+            .count();
+    }
+
+    /// Inserts a node, return a new root, which will be called by
+    /// [AVLTree.insert](struct.AVLTree.html#method.insert)
+    fn node_insert(&mut self, node: OptionAVLTreeNode<T>, insert_value: T) -> OptionAVLTreeNode<T> {
+        let ret_node = match node {
+            Some(n) => {
+                let node_value = n.borrow().value;
+                if insert_value < node_value {
+                    let left = n.borrow().left.clone();
+                    n.borrow_mut().left = self.node_insert(left, insert_value);
+                } else if insert_value > node_value {
+                    let right = n.borrow().right.clone();
+                    n.borrow_mut().right = self.node_insert(right, insert_value);
+                } else {
+                    n.borrow_mut().value = insert_value; // equal, update value
+                }
+                n
+            }
+            None => TreeNode::new(insert_value).unwrap(),
+        };
+
+        // update height
+        ret_node.borrow_mut().height = self
+            .get_left_height(&ret_node)
+            .max(self.get_right_height(&ret_node))
+            + 1;
+
+        // update balance factor
+        let balance_factor = self.get_node_balance_factor(&ret_node);
+
+        // maintain
+        // case LL: right rotate
+        if balance_factor > 1.0
+            && self.get_node_balance_factor(&ret_node.borrow().left.clone().unwrap()) >= 0.0
+        {
+            return Some(self.right_rotate(ret_node));
+        }
+
+        // case RR: left rotate
+        if balance_factor < -1.0
+            && self.get_node_balance_factor(&ret_node.borrow().right.clone().unwrap()) <= 0.0
+        {
+            return Some(self.left_rotate(ret_node));
+        }
+
+        // case LR: left rotate + right rotate
+        if balance_factor > 1.0
+            && self.get_node_balance_factor(&ret_node.borrow().left.clone().unwrap()) < 0.0
+        {
+            // ret_node.borrow_mut().left = Some(self.left_rotate(ret_node.borrow_mut().left.clone().unwrap())); // 发生移动
+            // return Some(self.right_rotate(ret_node))
+
+            let left = ret_node.borrow().left.clone().unwrap();
+            ret_node.borrow_mut().left = Some(self.left_rotate(left));
+            return Some(self.right_rotate(ret_node));
+        }
+
+        // case RL: right rotate + left rotate
+        if balance_factor < -1.0
+            && self.get_node_balance_factor(&ret_node.borrow().right.clone().unwrap()) > 0.0
+        {
+            // ret_node.borrow_mut().right = Some(self.right_rotate(ret_node.borrow_mut().right.clone().unwrap())); // 发生移动
+            // return Some(self.left_rotate(ret_node))
+
+            let right = ret_node.borrow().right.clone().unwrap();
+            ret_node.borrow_mut().right = Some(self.right_rotate(right));
+            return Some(self.left_rotate(ret_node));
+        }
+        Some(ret_node)
+    }
+
+    /// Insert a new value, or return the stored value in an error
+    /// if an equal value is already in the tree, which is then left unchanged
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// use tree_collections::common_trait::AlreadyExists;
+    /// let mut avl_tree = AVLTree::new();
+    /// assert_eq!(Ok(()), avl_tree.try_insert(1));
+    /// assert_eq!(Err(AlreadyExists(1)), avl_tree.try_insert(1));
+    /// ```
+    pub fn try_insert(&mut self, insert_value: T) -> Result<(), AlreadyExists<T>> {
+        match self.get(insert_value) {
+            Some(existing) => return Err(AlreadyExists(existing)),
+            None => {
+                self.insert(insert_value);
+                return Ok(());
+            }
+        }
+    }
+
+    /// Return the stored value equal to `value`, or insert the value computed by `f`
+    /// and return it if there is none. `f` is only called when the value is missing,
+    /// and the value it returns is expected to compare equal to `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || 1));
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || unreachable!()));
+    /// ```
+    pub fn entry_or_insert_with<F: FnOnce() -> T>(&mut self, value: T, f: F) -> T {
+        if let Some(existing) = self.get(value) {
+            return existing;
+        }
+        let inserted = f();
+        self.insert(inserted);
+        return inserted;
+    }
+
+    /// Delete a value from AVL tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::avl_tree::AVLTree;
+    /// let mut avl_tree = AVLTree::new();
+    /// avl_tree.insert(1);
+    /// avl_tree.delete(1);
+    /// ```
+    pub fn delete(&mut self, delete_value: T) {
+        let root = self.root.take();
+        match root {
+            None => return,
+            Some(n) => self.root = self.node_delete(Some(n), delete_value),
+        }
+    }
+
+    /// Remove all values from the tree, returning them in order as an iterator
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::avl_tree::AVLTree;
+    /// let mut avl_tree = AVLTree::new();
+    /// avl_tree.insert(2);
+    /// avl_tree.insert(1);
+    /// let values: Vec<u32> = avl_tree.drain().collect();
+    /// assert_eq!(values, vec![1, 2]);
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        return Drain { tree: self };
+    }
+
+    // Remove all the nodes, used when a drain is dropped
+    fn clear(&mut self) {
+        match self.root.take() {
+            Some(node) => node.borrow_mut().clear(),
+            None => return,
+        }
+    }
+
+    /// Deletes a node, return a new root, which will be called by
+    /// [AVLTree.delete](struct.AVLTree.html#method.delete)
+    // delete node, return new root
+    fn node_delete(&mut self, node: OptionAVLTreeNode<T>, delete_value: T) -> OptionAVLTreeNode<T> {
+        let ret_node = match node {
+            None => node,
+            Some(n) => {
+                let node_value = n.borrow().value;
+                if delete_value < node_value {
+                    // look left
+                    let left = n.borrow().left.clone();
+                    n.borrow_mut().left = self.node_delete(left, delete_value);
+                    Some(n)
+                } else if delete_value > node_value {
+                    // look right
+                    let right = n.borrow().right.clone();
+                    n.borrow_mut().right = self.node_delete(right, delete_value);
+                    Some(n)
+                } else {
+                    // found the node which should be deleted
+                    let left = n.borrow().left.clone();
+                    let right = n.borrow().right.clone();
+                    let ret = match (left.clone(), right.clone()) {
+                        (None, Some(r)) => Some(r), // The left subtree of the node to be deleted is empty, r is new root
+                        (Some(l), None) => Some(l), // The right subtree of the node to be deleted is empty, l is new root
+
+                        (None, None) => None,
+
+                        // The left and right subtrees of the node to be deleted(node n) are not empty.
+                        // Find the smallest node A that is larger than the node n.
+                        (Some(_), Some(right)) => {
+                            let min_value = right.borrow().get_min_value_in_children(); // Find the value of node A which is the minimum value of the right subtree
+                            n.borrow_mut().value = min_value; // Change the value of node n to the value of node A.
+                            let right = n.borrow().right.clone();
+                            n.borrow_mut().right = self.node_delete(right, min_value); // Delete the node A in the right subtree.
+                            Some(n) // return new root
+                        }
+                    };
+                    ret // 返回option
+                }
+            }
+        };
+
+        // update and maintain
+        match ret_node {
+            None => ret_node,
+            Some(n) => {
+                // update height
+                n.borrow_mut().height = self
+                    .get_left_height(&n) // 借用了发生移动的
+                    .max(self.get_right_height(&n))
+                    + 1; // 把option类型的ret_node都改成了n
+
+                // update balance factor
+                let balance_factor = self.get_node_balance_factor(&n);
+
+                // maintain
+                // case LL: right rotate
+                if balance_factor > 1.0
+                    && self.get_node_balance_factor(&n.borrow().left.clone().unwrap()) >= 0.0
+                {
+                    return Some(self.right_rotate(n));
+                }
+
+                // case RR: left rotate
+                if balance_factor < -1.0
+                    && self.get_node_balance_factor(&n.borrow().right.clone().unwrap()) <= 0.0
+                {
+                    return Some(self.left_rotate(n));
+                }
+
+                // case LR: left rotate + right rotate
+                if balance_factor > 1.0
+                    && self.get_node_balance_factor(&n.borrow().left.clone().unwrap()) < 0.0
+                {
+                    let left = n.borrow().left.clone().unwrap();
+                    n.borrow_mut().left = Some(self.left_rotate(left));
+                    return Some(self.right_rotate(n));
+                }
+
+                // case RL: right rotate + left rotate
+                if balance_factor < -1.0
+                    && self.get_node_balance_factor(&n.borrow().right.clone().unwrap()) > 0.0
+                {
+                    let right = n.borrow().right.clone().unwrap();
+                    n.borrow_mut().right = Some(self.right_rotate(right));
+                    return Some(self.left_rotate(n));
+                }
+                Some(n)
+            }
+        }
+    }
+
+    fn get_height(&self, node: OptionAVLTreeNode<T>) -> usize {
+        // default height of an empty tree is 0
+        node.map_or(0, |n| n.borrow().height)
+    }
+    fn get_left_height(&self, n: &AVLTreeNode<T>) -> usize {
+        self.get_height(n.borrow().left.clone())
+    }
+
+    fn get_right_height(&self, n: &AVLTreeNode<T>) -> usize {
+        self.get_height(n.borrow().right.clone())
+    }
+
+    fn get_node_balance_factor(&self, n: &AVLTreeNode<T>) -> f64 {
+        self.get_left_height(n) as f64 - self.get_right_height(n) as f64
+    }
+
+    //Determine whether the tree is balanced
+    #[allow(dead_code)]
+    fn is_balanced(&self, node: OptionAVLTreeNode<T>) -> bool {
+        match node {
+            Some(node) => {
+                if self.get_node_balance_factor(&node) <= 1.0 {
+                    self.is_balanced(node.borrow().left.clone())
+                        && self.is_balanced(node.borrow().right.clone())
+                } else {
+                    false
+                }
+            }
+            None => true,
+        }
+    }
+
+    //                 y                                     x
+    //               /    \                                 /   \
+    //              x     T4      right rotate (y)         z     y
+    //           /   \            ---------------->       / \   / \
+    //          z     T3             return x            T1 T2 T3 T4
+    //         /   \
+    //        T1   T2
+    fn right_rotate(&self, y: AVLTreeNode<T>) -> AVLTreeNode<T> {
+        let x = y.borrow().left.clone().unwrap();
+        let t_3 = x.borrow().right.clone();
+
+        // right rotate
+        x.borrow_mut().right = Some(y.clone());
+        y.borrow_mut().left = t_3;
+
+        // update height of x and y
+        y.borrow_mut().height = self.get_left_height(&y).max(self.get_right_height(&y)) + 1;
+        x.borrow_mut().height = self.get_left_height(&x).max(self.get_right_height(&x)) + 1;
+
+        return x;
+    }
+
+    //                 y                                        x
+    //               /    \                                   /   \
+    //              T1     x        left rotate (y)          y     z
+    //                   /   \      ---------------->       / \   / \
+    //                 T2     z        return x            T1 T2 T3 T4
+    //                       /  \
+    //                      T3   T4
+    fn left_rotate(&self, y: AVLTreeNode<T>) -> AVLTreeNode<T> {
+        let x = y.borrow().right.clone().unwrap();
+        let t_2 = x.borrow().left.clone();
+
+        // left rotate
+        x.borrow_mut().left = Some(y.clone());
+        y.borrow_mut().right = t_2;
+
+        // update height of x and y
+        y.borrow_mut().height = self.get_left_height(&y).max(self.get_right_height(&y)) + 1;
+        x.borrow_mut().height = self.get_left_height(&x).max(self.get_right_height(&x)) + 1;
+
+        return x;
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Drop for AVLTree<T> {
+    fn drop(&mut self) {
+        match self.root.take() {
+            Some(node) => node.borrow_mut().clear(),
+            None => return,
+        }
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Drop for TreeNode<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
+    fn clear(&mut self) {
+        match self.left.take() {
+            None => {}
+            Some(node) => {
+                node.borrow_mut().clear();
+            }
+        }
+        self.left = None;
+        match self.right.take() {
+            None => {}
+            Some(node) => {
+                node.borrow_mut().clear();
+            }
+        }
+        self.right = None;
+    }
+}
+
+/// A draining iterator over a [AVLTree](struct.AVLTree.html),
+/// created by [AVLTree.drain](struct.AVLTree.html#method.drain)
+///
+/// The values are yielded in order and removed from the tree one by one.
+/// If the iterator is dropped early, the remaining values are removed as well.
+pub struct Drain<'a, T: Ord + Copy + Debug + Display> {
+    tree: &'a mut AVLTree<T>,
+}
+
+impl<'a, T: Ord + Copy + Debug + Display> Iterator for Drain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self.tree.min() {
+            None => return None,
+            Some(value) => {
+                self.tree.delete(value);
+                return Some(value);
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord + Copy + Debug + Display> Drop for Drain<'a, T> {
+    fn drop(&mut self) {
+        self.tree.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tree_traversal() {
+        // Test the three different tree traversal functions.
+        let mut tree = AVLTree::new();
+        tree.insert(0);
+        [16, 16, 8, 24, 20, 22].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let root = tree.root.clone().unwrap();
+        let mut pre_container = vec![];
+        let mut in_container = vec![];
+        tree.preorder_traverse(root.clone(), &mut pre_container);
+        tree.in_order_traverse(root.clone(), &mut in_container);
+        let is_balanced = tree.is_balanced(tree.root.clone());
+        // println!("check {:#?}", in_container);
+        assert_eq!(pre_container, vec![20, 8, 0, 16, 24, 22]);
+        assert_eq!(in_container, vec![0, 8, 16, 20, 22, 24]);
+        assert!(is_balanced);
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut avl_tree = AVLTree::new();
+        avl_tree.insert(1);
+        avl_tree.insert(2);
+        avl_tree.insert(3);
+        avl_tree.insert(4);
+        avl_tree.insert(5);
+
+        let result = avl_tree.is_balanced(avl_tree.root.clone());
+        assert!(result);
+    }
+
+    #[test]
+    fn test_delete() {
+        // Test the three different tree traversal functions.
+        let mut tree = AVLTree::new();
+        tree.insert(0);
+        [16, 8, 24, 20, 22].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+
+        let root = tree.root.clone().unwrap();
+        tree.delete(16);
+        let mut container = vec![];
+        tree.preorder_traverse(root.clone(), &mut container);
+        let result = tree.is_balanced(tree.root.clone());
+        assert!(result);
+
+        assert_eq!(container, vec![20, 8, 0, 24, 22]);
+    }
+
+    #[test]
+    fn test_from_sorted_slice() {
+        for size in 0..100 {
+            let data: Vec<u32> = (0..size).collect();
+            let tree = AVLTree::from_sorted_slice(&data);
+            let mut container = vec![];
+            tree.in_order_traversal_for_test(&mut container);
+            assert_eq!(container, data);
+            let result = tree.is_balanced(tree.root.clone());
+            assert!(result);
+        }
+    }
+
+    #[test]
+    fn test_get_balance_factor() {
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.get_balance_factor(1), None);
+        tree.insert(2);
+        assert_eq!(tree.get_balance_factor(2), Some(0));
+        tree.insert(1);
+        assert_eq!(tree.get_balance_factor(2), Some(-1));
+        assert_eq!(tree.get_balance_factor(1), Some(0));
+        tree.insert(3);
+        assert_eq!(tree.get_balance_factor(2), Some(0));
+
+        let mut tree = AVLTree::new();
+        vec![
+            12, 1, 9, 2, 0, 11, 7, 19, 4, 15, 18, 5, 14, 13, 10, 16, 6, 3, 8, 17,
+        ]
+        .iter()
+        .for_each(|v| {
+            tree.insert(*v);
+        });
+        let mut container = vec![];
+        tree.in_order_traversal_for_test(&mut container);
+        for v in container {
+            let balance_factor = tree.get_balance_factor(v).unwrap();
+            assert!((-1..=1).contains(&balance_factor));
+        }
+    }
+
+    #[test]
+    fn test_insert_if_absent() {
+        let mut tree = AVLTree::new();
+        assert!(tree.insert_if_absent(1));
+        assert!(tree.insert_if_absent(2));
+        assert!(!tree.insert_if_absent(1));
+        let mut container = vec![];
+        tree.in_order_traversal_for_test(&mut container);
+        assert_eq!(container, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_insert_many_unique() {
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.insert_many_unique(vec![1, 2, 3, 2, 1]), 3);
+        assert_eq!(tree.insert_many_unique(vec![2, 3, 4, 5, 5]), 2);
+        assert_eq!(tree.insert_many_unique(Vec::new()), 0);
+        assert_eq!(tree.to_sorted_vec(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut tree = AVLTree::new();
+        [5, 3, 8, 1, 4, 7, 9, 2, 6].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let drained: Vec<u32> = tree.drain().collect();
+        assert_eq!(drained, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert!(tree.is_empty());
+
+        [5, 3, 8, 1, 4].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        {
+            let mut drain = tree.drain();
+            assert_eq!(drain.next(), Some(1));
+            assert_eq!(drain.next(), Some(3));
+            let mut container = vec![];
+            drain.tree.in_order_traversal_for_test(&mut container);
+            assert_eq!(container, vec![4, 5, 8]);
+        }
+        // dropping the drain early removes the remaining values
+        assert!(tree.is_empty());
+        assert!(!tree.contains(5));
+        tree.insert(2);
+        assert!(tree.contains(2));
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let mut tree = AVLTree::new();
+        [5, 3, 8, 1, 4].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let mut container = vec![];
+        for v in &tree {
+            container.push(v);
+        }
+        assert_eq!(container, vec![1, 3, 4, 5, 8]);
+
+        // the tree is still usable after the loop
+        tree.insert(2);
+        assert_eq!(
+            (&tree).into_iter().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 8]
+        );
+    }
+
+    #[test]
+    fn test_try_insert() {
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.try_insert(3), Ok(()));
+        assert_eq!(tree.try_insert(1), Ok(()));
+        assert_eq!(tree.try_insert(3), Err(AlreadyExists(3)));
+        let mut container = vec![];
+        tree.in_order_traversal_for_test(&mut container);
+        assert_eq!(container, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut tree = AVLTree::new();
+        tree.extend(vec![5, 3, 8]);
+        tree.extend(Vec::new());
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![3, 5, 8]);
+
+        // overlapping values are not duplicated
+        tree.extend(vec![8, 1, 3]);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![1, 3, 5, 8]);
+
+        let mut other = AVLTree::new();
+        other.extend(tree.iter());
+        assert_eq!(other.iter().collect::<Vec<_>>(), vec![1, 3, 5, 8]);
+
+        let mut extended = AVLTree::new();
+        let mut inserted = AVLTree::new();
+        extended.extend(0..10);
+        (0..10).for_each(|v| inserted.insert(v));
+        assert_eq!(
+            extended.iter().collect::<Vec<_>>(),
+            inserted.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(extended.height(), inserted.height());
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut tree = AVLTree::new();
+        tree.extend(vec![1, 2]);
+        assert_eq!(
+            format!("{:?}", tree),
+            "AVLTree { root: Some(TreeNode { value: 1, left: None, \
+             right: Some(TreeNode { value: 2, left: None, right: None }) }) }"
+        );
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut tree = AVLTree::new();
+        tree.extend(0..20);
+        let mut copy = tree.clone();
+        assert!(copy == tree);
+        assert_eq!(format!("{:?}", copy), format!("{:?}", tree));
+
+        // every node is only held by its parent, the clone shares none of them
+        for root in [tree.root.clone(), copy.root.clone()] {
+            let mut stack = vec![root.unwrap()];
+            while let Some(node) = stack.pop() {
+                assert_eq!(Shared::strong_count(&node), 2);
+                stack.extend(node.borrow().left.clone());
+                stack.extend(node.borrow().right.clone());
+            }
+        }
+
+        copy.delete(5);
+        copy.insert(100);
+        assert!(tree.contains(5));
+        assert!(!tree.contains(100));
+        drop(copy);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_height_bound() {
+        use rand::seq::SliceRandom;
+
+        let mut rng = rand::thread_rng();
+        for n in [100, 1000, 10000] {
+            let sorted: Vec<u32> = (0..n).collect();
+            let reverse: Vec<u32> = (0..n).rev().collect();
+            let mut random = sorted.clone();
+            random.shuffle(&mut rng);
+            let bound = 1.44 * (n as f64 + 2.0).log2();
+            for data in [sorted, reverse, random] {
+                let mut tree = AVLTree::new();
+                tree.extend(data);
+                assert!(
+                    tree.height() as f64 <= bound,
+                    "height {} above {} for {} values",
+                    tree.height(),
+                    bound,
+                    n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_delete_all_elements() {
+        use rand::seq::SliceRandom;
+
+        let mut inserted: Vec<u32> = (0..=99).collect();
+        inserted.shuffle(&mut rand::thread_rng());
+        let sorted: Vec<u32> = (0..=99).collect();
+        let reverse: Vec<u32> = (0..=99).rev().collect();
+        for deleted in [inserted.clone(), sorted, reverse] {
+            let mut tree = AVLTree::new();
+            inserted.iter().for_each(|v| {
+                tree.insert(*v);
+            });
+            for (i, v) in deleted.iter().enumerate() {
+                tree.delete(*v);
+                assert!(!tree.contains(*v));
+                assert_eq!(tree.count_nodes(), 99 - i);
+                assert!(tree.is_valid_bst());
+                assert!(tree.is_height_balanced());
+            }
+            assert!(tree.is_empty());
+            assert_eq!(tree.height(), 0);
+        }
+    }
+
+    #[test]
+    fn test_entry_or_insert_with() {
+        let mut tree = AVLTree::new();
+        let mut calls = 0;
+        [3, 1, 3, 2, 1, 3].iter().for_each(|v| {
+            let entry = tree.entry_or_insert_with(*v, || {
+                calls += 1;
+                *v
+            });
+            assert_eq!(entry, *v);
+        });
+        assert_eq!(calls, 3);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_insert_equal_value() {
+        use crate::utils::test_item::Item;
+
+        let mut tree = AVLTree::new();
+        [
+            Item {
+                key: 1,
+                payload: 10,
+            },
+            Item {
+                key: 2,
+                payload: 20,
+            },
+            Item {
+                key: 1,
+                payload: 11,
+            },
+        ]
+        .iter()
+        .for_each(|v| tree.insert(*v));
+        let values = tree.to_sorted_vec();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].payload, 11);
+    }
+}
@@ -2,11 +2,12 @@
 //!
 //! You can generate a binary search tree, and insert or delete nodes.
 
-use std::cell::RefCell;
-use std::fmt::{Debug, Display};
-use std::rc::Rc;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Display};
 
-pub use crate::commonTrait::{CommonTreeNodeTrait, CommonTreeTrait};
+use crate::shared_node::{NodeCell, Shared};
+
+pub use crate::common_trait::{CommonTreeNodeTrait, CommonTreeTrait};
 
 /// Structure of BSTree
 #[derive(Clone, Debug, PartialEq)]
@@ -22,9 +23,22 @@ pub struct TreeNode<T: Ord + Copy + Debug + Display> {
     right: OptionBSTreeNode<T>,
 }
 
-type BSTreeNode<T> = Rc<RefCell<TreeNode<T>>>;
+type BSTreeNode<T> = Shared<NodeCell<TreeNode<T>>>;
 type OptionBSTreeNode<T> = Option<BSTreeNode<T>>;
 
+/// Error returned by [BSTree.from_bfs_vec](struct.BSTree.html#method.from_bfs_vec)
+/// when the values do not form a binary search tree in the given shape
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InvalidBSTError;
+
+impl fmt::Display for InvalidBSTError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "values are not in binary search tree order")
+    }
+}
+
+impl std::error::Error for InvalidBSTError {}
+
 // extend from common tree trait
 impl<T: Ord + Copy + Debug + Display> CommonTreeTrait<T, TreeNode<T>> for BSTree<T> {
     fn get_root(&self) -> OptionBSTreeNode<T> {
@@ -51,6 +65,12 @@ impl<T: Ord + Copy + Debug + Display> CommonTreeNodeTrait<T> for TreeNode<T> {
     }
 }
 
+impl<T: Ord + Copy + Debug + Display> Default for BSTree<T> {
+    fn default() -> Self {
+        return BSTree::new();
+    }
+}
+
 /// Implementations of BSTree
 // BSTree
 impl<T: Ord + Copy + Debug + Display> BSTree<T> {
@@ -59,36 +79,105 @@ impl<T: Ord + Copy + Debug + Display> BSTree<T> {
     /// # Example
     ///
     /// ```
-    /// use tree_collections::bsTree::BSTree;
+    /// use tree_collections::bs_tree::BSTree;
     /// let mut bst = BSTree::<u32>::new();
     /// ```
     pub fn new() -> Self {
         BSTree { root: None }
     }
 
+    /// Build a tree from the level order format of
+    /// [to_bfs_vec](../common_trait/trait.CommonTreeTrait.html#method.to_bfs_vec),
+    /// keeping the shape of the tree it was taken from. The values are placed as
+    /// given, and an [InvalidBSTError](struct.InvalidBSTError.html) is returned if
+    /// they do not form a binary search tree in that shape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let data = vec![Some(2), Some(1), Some(3), None, None, None, Some(4)];
+    /// let tree = BSTree::from_bfs_vec(data).unwrap();
+    /// assert_eq!(vec![2, 1, 3, 4], tree.to_preorder_vec());
+    /// assert!(BSTree::from_bfs_vec(vec![Some(1), Some(2)]).is_err());
+    /// ```
+    pub fn from_bfs_vec(data: Vec<Option<T>>) -> Result<Self, InvalidBSTError> {
+        let mut values = data.into_iter();
+        let root = match values.next() {
+            Some(Some(value)) => Shared::new(NodeCell::new(TreeNode::new(value))),
+            _ => return Ok(BSTree::new()),
+        };
+        let mut queue = VecDeque::new();
+        queue.push_back(root.clone());
+        while let Some(node) = queue.pop_front() {
+            let left = values
+                .next()
+                .flatten()
+                .map(|value| Shared::new(NodeCell::new(TreeNode::new(value))));
+            let right = values
+                .next()
+                .flatten()
+                .map(|value| Shared::new(NodeCell::new(TreeNode::new(value))));
+            queue.extend(left.clone());
+            queue.extend(right.clone());
+            node.borrow_mut().left = left;
+            node.borrow_mut().right = right;
+        }
+        let tree = BSTree { root: Some(root) };
+        if !tree.is_valid_bst() {
+            return Err(InvalidBSTError);
+        }
+        return Ok(tree);
+    }
+
     /// Insert a new value to the BSTree
     ///
+    /// If the tree already holds a value which compares equal to the new one,
+    /// the stored value is replaced by the new one, as
+    /// [FastRBTree.insert](../rb_tree_fast/struct.FastRBTree.html#method.insert) does.
+    ///
     /// # Example
     ///
     /// ```
-    /// use tree_collections::bsTree::BSTree;
+    /// use tree_collections::bs_tree::BSTree;
     /// let mut bst = BSTree::new();
     /// bst.insert(1);
     /// ```
     pub fn insert(&mut self, insert_value: T) {
         let root = self.get_root();
         match root {
-            None => self.root = Some(Rc::new(RefCell::new(TreeNode::new(insert_value)))),
+            None => self.root = Some(Shared::new(NodeCell::new(TreeNode::new(insert_value)))),
             Some(root) => TreeNode::node_insert(root, insert_value),
         }
     }
 
+    /// Return the stored value equal to `value`, or insert the value computed by `f`
+    /// and return it if there is none. `f` is only called when the value is missing,
+    /// and the value it returns is expected to compare equal to `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = BSTree::new();
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || 1));
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || unreachable!()));
+    /// ```
+    pub fn entry_or_insert_with<F: FnOnce() -> T>(&mut self, value: T, f: F) -> T {
+        if let Some(existing) = self.get(value) {
+            return existing;
+        }
+        let inserted = f();
+        self.insert(inserted);
+        return inserted;
+    }
+
     /// Delete a value from the tree
     ///
     /// # Example
     ///
     /// ```
-    /// use tree_collections::bsTree::BSTree;
+    /// use tree_collections::bs_tree::BSTree;
     /// let mut bst = BSTree::new();
     /// bst.insert(1);
     /// bst.delete(1);
@@ -137,7 +226,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
     /// which will be called by [BSTree](struct.BSTree.html)
     fn new(value: T) -> Self {
         TreeNode {
-            value: value,
+            value,
             left: None,
             right: None,
         }
@@ -154,7 +243,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
                 }
                 None => {
                     node.borrow_mut().left =
-                        Some(Rc::new(RefCell::new(TreeNode::new(insert_value))));
+                        Some(Shared::new(NodeCell::new(TreeNode::new(insert_value))));
                 }
             }
         } else if node.borrow().get_value() < insert_value {
@@ -165,12 +254,12 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
                 }
                 None => {
                     node.borrow_mut().right =
-                        Some(Rc::new(RefCell::new(TreeNode::new(insert_value))));
+                        Some(Shared::new(NodeCell::new(TreeNode::new(insert_value))));
                     let _right = node.borrow().get_right();
                 }
             }
         } else {
-            return;
+            node.borrow_mut().value = insert_value; // equal, update value
         }
     }
 
@@ -300,7 +389,7 @@ mod test {
     fn test_insert() {
         let mut tree = BSTree::new();
         tree.insert(0);
-        vec![16, 16, 8, 24, 20, 22].iter().for_each(|v| {
+        [16, 16, 8, 24, 20, 22].iter().for_each(|v| {
             tree.insert(*v);
         });
         let mut in_container = vec![];
@@ -315,7 +404,7 @@ mod test {
     fn test_delete() {
         let mut tree = BSTree::new();
         tree.insert(0);
-        vec![16, 16, 8, 24, 20, 22].iter().for_each(|v| {
+        [16, 16, 8, 24, 20, 22].iter().for_each(|v| {
             tree.insert(*v);
         });
         tree.delete(16);
@@ -326,4 +415,83 @@ mod test {
         assert_eq!(in_container, vec![0, 8, 20, 22, 24]);
         assert_eq!(pre_container, vec![0, 20, 8, 24, 22]);
     }
+
+    #[test]
+    fn test_entry_or_insert_with() {
+        let mut tree = BSTree::new();
+        let mut calls = 0;
+        [3, 1, 3, 2, 1, 3].iter().for_each(|v| {
+            let entry = tree.entry_or_insert_with(*v, || {
+                calls += 1;
+                *v
+            });
+            assert_eq!(entry, *v);
+        });
+        assert_eq!(calls, 3);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_bfs_vec() {
+        let empty = BSTree::<u32>::new();
+        assert_eq!(empty.to_bfs_vec(), vec![]);
+        assert_eq!(BSTree::<u32>::from_bfs_vec(vec![]), Ok(empty.clone()));
+        assert_eq!(BSTree::<u32>::from_bfs_vec(vec![None]), Ok(empty));
+
+        let mut tree = BSTree::new();
+        [8, 4, 12, 2, 6, 10, 14, 1, 7, 13]
+            .iter()
+            .for_each(|v| tree.insert(*v));
+        let data = tree.to_bfs_vec();
+        let copy = BSTree::from_bfs_vec(data.clone()).unwrap();
+        assert_eq!(copy.to_preorder_vec(), tree.to_preorder_vec());
+        assert_eq!(copy.to_bfs_vec(), data);
+        assert_eq!(copy, tree);
+
+        // a left chain only lists the left children
+        let mut chain = BSTree::new();
+        [4, 3, 2, 1].iter().for_each(|v| chain.insert(*v));
+        let data = chain.to_bfs_vec();
+        assert_eq!(data, vec![Some(4), Some(3), None, Some(2), None, Some(1)]);
+        let copy = BSTree::from_bfs_vec(data).unwrap();
+        assert_eq!(copy.to_preorder_vec(), vec![4, 3, 2, 1]);
+        assert_eq!(copy.height(), 4);
+
+        // values out of order for the shape are rejected
+        assert_eq!(
+            BSTree::from_bfs_vec(vec![Some(2), Some(3), Some(1)]),
+            Err(InvalidBSTError)
+        );
+        // 5 is the right child of 1 but lies in the left subtree of the root 3
+        assert_eq!(
+            BSTree::from_bfs_vec(vec![Some(3), Some(1), None, None, Some(5)]),
+            Err(InvalidBSTError)
+        );
+    }
+
+    #[test]
+    fn test_insert_equal_value() {
+        use crate::utils::test_item::Item;
+
+        let mut tree = BSTree::new();
+        [
+            Item {
+                key: 1,
+                payload: 10,
+            },
+            Item {
+                key: 2,
+                payload: 20,
+            },
+            Item {
+                key: 1,
+                payload: 11,
+            },
+        ]
+        .iter()
+        .for_each(|v| tree.insert(*v));
+        let values = tree.to_sorted_vec();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].payload, 11);
+    }
 }
@@ -0,0 +1,2734 @@
+//! An automatically-implemented extension trait for nodes and trees
+//!
+//! Provides common functions for trees and nodes.
+//!
+//! The query methods are marked `#[must_use]`, so ignoring their result is a warning:
+//!
+//! ```compile_fail
+//! #![deny(unused_must_use)]
+//! use tree_collections::prelude::*;
+//! let tree = FastRBTree::<u32>::new();
+//! tree.contains(1);
+//! ```
+
+use std::cmp::{max, min};
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Display};
+use std::iter::{Enumerate, Map, Skip, TakeWhile, Zip};
+use std::marker::PhantomData;
+
+use crate::shared_node::{NodeCell, Shared};
+
+/// The middle value(s) of a tree, returned by
+/// [CommonTreeTrait.median](trait.CommonTreeTrait.html#method.median)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MedianResult<T> {
+    /// The tree has an odd number of values, with a single middle value
+    Odd(T),
+    /// The tree has an even number of values, with two middle values in sorted order
+    Even(T, T),
+}
+
+/// Error returned by `try_insert` when an equal value is already in the tree,
+/// holding the stored value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlreadyExists<T>(pub T);
+
+impl<T: Display> Display for AlreadyExists<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "value {} already exists in the tree", self.0)
+    }
+}
+
+impl<T: Debug + Display> std::error::Error for AlreadyExists<T> {}
+
+/// A visitor called by [CommonTreeTrait.walk](trait.CommonTreeTrait.html#method.walk)
+///
+/// Each method is called once per node, in preorder, inorder and postorder
+/// respectively. They do nothing by default, so only the needed ones are implemented.
+///
+/// # Example
+///
+/// ```
+/// use tree_collections::prelude::*;
+/// use tree_collections::common_trait::TreeVisitor;
+///
+/// struct Sum(u32);
+///
+/// impl TreeVisitor<u32> for Sum {
+///     fn visit_in(&mut self, value: u32) {
+///         self.0 += value;
+///     }
+/// }
+///
+/// let tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+/// let mut sum = Sum(0);
+/// tree.walk(&mut sum);
+/// assert_eq!(6, sum.0);
+/// ```
+pub trait TreeVisitor<T> {
+    /// Called before the children of the node are visited
+    fn visit_pre(&mut self, _value: T) {}
+
+    /// Called after the left child and before the right child of the node are visited
+    fn visit_in(&mut self, _value: T) {}
+
+    /// Called after the children of the node are visited
+    fn visit_post(&mut self, _value: T) {}
+}
+
+/// A lazy in-order iterator over the values of a tree, created by
+/// [CommonTreeTrait.iter](trait.CommonTreeTrait.html#method.iter)
+///
+/// Only the path to the next value is kept, so the iterator uses O(log n) space
+/// and stopping early does not visit the rest of the tree.
+pub struct InOrderIter<'a, T, TreeNode> {
+    root: Option<Shared<NodeCell<TreeNode>>>,
+    stack: Vec<Shared<NodeCell<TreeNode>>>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: Ord + Copy + Debug + Display, TreeNode: CommonTreeNodeTrait<T>>
+    InOrderIter<'a, T, TreeNode>
+{
+    // Start at the smallest value of the tree
+    fn new(root: Option<Shared<NodeCell<TreeNode>>>) -> Self {
+        let mut iter = InOrderIter {
+            root: root.clone(),
+            stack: vec![],
+            marker: PhantomData,
+        };
+        iter.push_left_spine(root);
+        return iter;
+    }
+
+    // Start at the first value which is not less than the lower bound
+    pub(crate) fn with_lower_bound(root: Option<Shared<NodeCell<TreeNode>>>, lower: T) -> Self {
+        let mut iter = InOrderIter {
+            root,
+            stack: vec![],
+            marker: PhantomData,
+        };
+        iter.skip_to_lower_bound(lower);
+        return iter;
+    }
+
+    /// Move the iterator to the first value which is not less than `lower`,
+    /// in O(log n). The position does not depend on the values already yielded,
+    /// so this can also move the iterator backwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// (1..=100).for_each(|v| tree.insert(v));
+    /// let mut iter = tree.iter();
+    /// let page: Vec<u32> = iter.by_ref().take(10).collect();
+    /// iter.skip_to_lower_bound(page[9] + 50);
+    /// assert_eq!(Some(60), iter.next());
+    /// ```
+    pub fn skip_to_lower_bound(&mut self, lower: T) {
+        self.stack.clear();
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            if node.borrow().get_value() < lower {
+                current = node.borrow().get_right();
+            } else {
+                current = node.borrow().get_left();
+                self.stack.push(node);
+            }
+        }
+    }
+
+    fn push_left_spine(&mut self, node: Option<Shared<NodeCell<TreeNode>>>) {
+        let mut current = node;
+        while let Some(node) = current {
+            current = node.borrow().get_left();
+            self.stack.push(node);
+        }
+    }
+}
+
+impl<'a, T: Ord + Copy + Debug + Display, TreeNode: CommonTreeNodeTrait<T>> Iterator
+    for InOrderIter<'a, T, TreeNode>
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let node = self.stack.pop()?;
+        let value = node.borrow().get_value();
+        self.push_left_spine(node.borrow().get_right());
+        return Some(value);
+    }
+}
+
+/// The iterator returned by [CommonTreeTrait.zip_with_rank](trait.CommonTreeTrait.html#method.zip_with_rank),
+/// yielding `(value, rank)` pairs in ascending order
+pub type RankedIter<'a, T, TreeNode> =
+    Map<Enumerate<InOrderIter<'a, T, TreeNode>>, fn((usize, T)) -> (T, usize)>;
+
+/// The iterator returned by [CommonTreeTrait.consecutive_pairs](trait.CommonTreeTrait.html#method.consecutive_pairs),
+/// yielding `(prev, curr)` pairs in ascending order
+pub type PairsIter<'a, T, TreeNode> =
+    Zip<InOrderIter<'a, T, TreeNode>, Skip<InOrderIter<'a, T, TreeNode>>>;
+
+/// Called on the branches where a searched value is not in the tree, so that the
+/// compiler lays out the code of the usual case, the value being found, contiguously
+#[cold]
+#[inline(never)]
+pub(crate) fn handle_not_found() {}
+
+/// Number of levels printed by the `Debug` output of the trees
+pub(crate) const DEBUG_DEPTH: u32 = 5;
+
+/// Number of values `batch_contains` steps over towards the next query before
+/// it looks the query up from the root instead
+const BATCH_CONTAINS_MAX_STEPS: usize = 8;
+
+/// `Debug` output of a subtree, used by the `Debug` implementations of the trees
+///
+/// Nodes print as `TreeNode { value, left, right }` with the value taken from
+/// `get_value_to_print`. Only `depth` levels are printed and deeper subtrees are
+/// replaced by `...`, so the output stays short and a cycle cannot loop forever.
+pub(crate) struct DebugSubtree<T, TreeNode> {
+    node: Option<Shared<NodeCell<TreeNode>>>,
+    depth: u32,
+    marker: PhantomData<T>,
+}
+
+impl<T, TreeNode> DebugSubtree<T, TreeNode> {
+    pub(crate) fn new(node: Option<Shared<NodeCell<TreeNode>>>, depth: u32) -> Self {
+        return DebugSubtree {
+            node,
+            depth,
+            marker: PhantomData,
+        };
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display, TreeNode: CommonTreeNodeTrait<T>> Debug
+    for DebugSubtree<T, TreeNode>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let node = match &self.node {
+            None => return f.write_str("None"),
+            Some(node) => node,
+        };
+        if self.depth == 0 {
+            return f.write_str("Some(...)");
+        }
+        let node = node.borrow();
+        let fields = DebugNodeFields {
+            value: node.get_value_to_print(),
+            left: DebugSubtree::new(node.get_left(), self.depth - 1),
+            right: DebugSubtree::new(node.get_right(), self.depth - 1),
+        };
+        return f.debug_tuple("Some").field(&fields).finish();
+    }
+}
+
+// Helper struct for printing one node of DebugSubtree
+struct DebugNodeFields<T, TreeNode> {
+    value: String,
+    left: DebugSubtree<T, TreeNode>,
+    right: DebugSubtree<T, TreeNode>,
+}
+
+impl<T: Ord + Copy + Debug + Display, TreeNode: CommonTreeNodeTrait<T>> Debug
+    for DebugNodeFields<T, TreeNode>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f
+            .debug_struct("TreeNode")
+            .field("value", &format_args!("{}", self.value))
+            .field("left", &self.left)
+            .field("right", &self.right)
+            .finish();
+    }
+}
+
+/// Provide common functions for trees
+// Common trait for Tree
+pub trait CommonTreeTrait<T: Ord + Copy + Debug + Display, TreeNode: CommonTreeNodeTrait<T>> {
+    fn get_root(&self) -> Option<Shared<NodeCell<TreeNode>>>;
+
+    /// Return the value stored at the root, or None for an empty tree.
+    /// Rotations may move another value to the root after an insert or delete.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// assert_eq!(None, tree.get_root_value());
+    /// (1..=3).for_each(|v| tree.insert(v));
+    /// assert_eq!(Some(2), tree.get_root_value());
+    /// ```
+    #[must_use = "calling get_root_value without using the result has no effect"]
+    fn get_root_value(&self) -> Option<T> {
+        return self.get_root().map(|node| node.borrow().get_value());
+    }
+
+    /// Counts leaves(None nodes) of the Tree
+    ///
+    /// # Example
+    ///
+    /// ```compile_fail
+    /// use tree_collections::common_trait::CommonTreeTrait;
+    /// let mut tree = CommonTreeTrait::new();
+    /// tree.insert(1);
+    /// println!("{}", tree.count_leaves());  // 2
+    /// tree.insert(2);
+    /// println!("{}", tree.count_leaves());  // 3
+    /// tree.insert(3);
+    /// println!("{}", tree.count_leaves());  // 4
+    ///
+    /// let mut leaf_number = tree.count_leaves();
+    /// assert_eq!(4, leaf_number);
+    /// ```
+    // count the leaves (None nodes)
+    #[must_use = "calling count_leaves without using the result has no effect"]
+    fn count_leaves(&self) -> u32 {
+        match self.get_root() {
+            None => 0,
+            Some(node) => node.borrow().count_leaves(),
+        }
+    }
+
+    /// Counts the nodes of the Tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// assert_eq!(0, tree.count_nodes());
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(2, tree.count_nodes());
+    /// ```
+    #[must_use = "calling count_nodes without using the result has no effect"]
+    fn count_nodes(&self) -> usize {
+        match self.get_root() {
+            None => 0,
+            Some(node) => node.borrow().count_nodes(),
+        }
+    }
+
+    /// Counts the nodes which have both a left and a right child. A non-empty
+    /// tree has one more leaf than full nodes, so at most half of its nodes are full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(1);
+    /// assert_eq!(0, tree.count_full_nodes());
+    /// tree.insert(2);
+    /// tree.insert(3);
+    /// assert_eq!(1, tree.count_full_nodes());
+    /// ```
+    #[must_use = "calling count_full_nodes without using the result has no effect"]
+    fn count_full_nodes(&self) -> usize {
+        match self.get_root() {
+            None => 0,
+            Some(node) => node.borrow().count_full_nodes(),
+        }
+    }
+
+    /// Gets height of the Tree (from root to leaves)
+    ///
+    /// # Example
+    ///
+    /// ```compile_fail
+    /// use tree_collections::common_trait::CommonTreeTrait;
+    /// let mut tree = CommonTreeTrait::new();
+    /// assert_eq!(0, tree.height());
+    /// tree.insert(1);
+    /// assert_eq!(2, tree.height());
+    ///tree.insert(2);
+    /// assert_eq!(3, tree.height());
+    /// ```
+    // from root to leaves
+    #[must_use = "calling height without using the result has no effect"]
+    fn height(&self) -> u32 {
+        match self.get_root() {
+            None => 0,
+            Some(node) => node.borrow().get_height(),
+        }
+    }
+
+    /// Return the length of the shortest path from the root to an empty child,
+    /// counted like [height](trait.CommonTreeTrait.html#method.height), 0 for an empty tree
+    ///
+    /// The levels are visited breadth first and the search stops at the first node
+    /// with an empty child, so only the top of a deep tree is visited.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = BSTree::new();
+    /// vec![2, 1, 3, 4].iter().for_each(|v| tree.insert(*v));
+    /// assert_eq!(2, tree.min_height());
+    /// assert_eq!(3, tree.height());
+    /// ```
+    #[must_use = "calling min_height without using the result has no effect"]
+    fn min_height(&self) -> u32 {
+        let mut queue = VecDeque::new();
+        if let Some(root) = self.get_root() {
+            queue.push_back((root, 1));
+        }
+        while let Some((node, depth)) = queue.pop_front() {
+            let node = node.borrow();
+            match (node.get_left(), node.get_right()) {
+                (Some(left), Some(right)) => {
+                    queue.push_back((left, depth + 1));
+                    queue.push_back((right, depth + 1));
+                }
+                _ => return depth + node.get_nil_height(),
+            }
+        }
+        return 0;
+    }
+
+    /// Return the height of the right subtree of the root minus the height
+    /// of its left subtree, 0 for an empty tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = BSTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// tree.insert(3);
+    /// assert_eq!(2, tree.height_difference());
+    /// ```
+    #[must_use = "calling height_difference without using the result has no effect"]
+    fn height_difference(&self) -> i32 {
+        match self.get_root() {
+            None => 0,
+            Some(node) => {
+                let node = node.borrow();
+                let subtree_height = |child: Option<Shared<NodeCell<TreeNode>>>| match child {
+                    None => node.get_nil_height() as i32,
+                    Some(child) => child.borrow().get_height() as i32,
+                };
+                return subtree_height(node.get_right()) - subtree_height(node.get_left());
+            }
+        }
+    }
+
+    /// Return the number of left children on the path from the root which always
+    /// goes left, 0 for an empty tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = BSTree::new();
+    /// vec![3, 2, 1, 4].iter().for_each(|v| tree.insert(*v));
+    /// assert_eq!(2, tree.left_spine_length());
+    /// ```
+    #[must_use = "calling left_spine_length without using the result has no effect"]
+    fn left_spine_length(&self) -> usize {
+        let mut length = 0;
+        let mut current = self.get_root().and_then(|node| node.borrow().get_left());
+        while let Some(node) = current {
+            length += 1;
+            current = node.borrow().get_left();
+        }
+        return length;
+    }
+
+    /// Return the number of right children on the path from the root which always
+    /// goes right, 0 for an empty tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = BSTree::new();
+    /// vec![3, 2, 1, 4].iter().for_each(|v| tree.insert(*v));
+    /// assert_eq!(1, tree.right_spine_length());
+    /// ```
+    #[must_use = "calling right_spine_length without using the result has no effect"]
+    fn right_spine_length(&self) -> usize {
+        let mut length = 0;
+        let mut current = self.get_root().and_then(|node| node.borrow().get_right());
+        while let Some(node) = current {
+            length += 1;
+            current = node.borrow().get_right();
+        }
+        return length;
+    }
+
+    /// Determine whether the heights of the two subtrees of the root differ by at most 1
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// (1..=10).for_each(|v| tree.insert(v));
+    /// assert_eq!(true, tree.is_height_balanced());
+    /// ```
+    #[must_use = "calling is_height_balanced without using the result has no effect"]
+    fn is_height_balanced(&self) -> bool {
+        return self.height_difference().abs() <= 1;
+    }
+
+    /// Prints Tree inorder
+    ///
+    /// # Example
+    ///
+    /// ```compile_fail
+    /// use tree_collections::common_trait::CommonTreeTrait;
+    /// let mut tree = CommonTreeTrait::new();
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// tree.insert(5);
+    /// tree.insert(2);
+    /// tree.insert(4);
+    /// tree.in_order_traversal(); // Inorder traversal: 1 2 3 4 5
+    ///
+    fn in_order_traversal(&self) {
+        match self.get_root() {
+            None => println!("There is no node in the tree!"),
+            Some(node) => {
+                node.borrow().in_order_traversal();
+                println!();
+            }
+        }
+    }
+
+    fn in_order_traversal_for_test(&self, container: &mut Vec<T>) {
+        match self.get_root() {
+            None => println!("There is no node in the tree!"),
+            Some(node) => {
+                node.borrow().in_order_traversal_for_test(container);
+            }
+        }
+    }
+
+    /// Return the values of the tree in ascending order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(3);
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// let sorted: Vec<u32> = tree.to_sorted_vec();
+    /// assert_eq!(vec![1, 2, 3], sorted);
+    /// ```
+    #[must_use = "calling to_sorted_vec without using the result has no effect"]
+    fn to_sorted_vec(&self) -> Vec<T> {
+        let mut container = Vec::new();
+        // an empty tree gives an empty Vec, without the message of in_order_traversal_for_test
+        if let Some(node) = self.get_root() {
+            node.borrow().in_order_traversal_for_test(&mut container);
+        }
+        return container;
+    }
+
+    /// Return a lazy iterator over the values of the tree in ascending order
+    ///
+    /// The iterator works with the standard adapters, and since the values come
+    /// in order, adapters such as `take_while` stop without visiting the rest of the tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(3);
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(vec![1, 2, 3], tree.iter().collect::<Vec<_>>());
+    /// ```
+    #[must_use = "calling iter without using the result has no effect"]
+    fn iter(&self) -> InOrderIter<'_, T, TreeNode> {
+        return InOrderIter::new(self.get_root());
+    }
+
+    /// Return the values `v` with `low <= v <= high` in ascending order,
+    /// in O(log n + k) for k values in the range
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// (1..=10).for_each(|v| tree.insert(v));
+    /// assert_eq!(vec![3, 4, 5], tree.range(3, 5));
+    /// ```
+    #[must_use = "calling range without using the result has no effect"]
+    fn range(&self, low: T, high: T) -> Vec<T> {
+        return InOrderIter::<T, TreeNode>::with_lower_bound(self.get_root(), low)
+            .take_while(|value| *value <= high)
+            .collect();
+    }
+
+    /// Return the values in ascending order while the predicate holds.
+    /// For a predicate which is monotone with the ordering, such as `v < 50`,
+    /// this yields a prefix of the tree in O(log n + k).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// (1..=100).for_each(|v| tree.insert(v));
+    /// let small: Vec<u32> = tree.take_while_sorted(|v| *v < 4).collect();
+    /// assert_eq!(vec![1, 2, 3], small);
+    /// ```
+    #[must_use = "calling take_while_sorted without using the result has no effect"]
+    fn take_while_sorted<F: FnMut(&T) -> bool>(
+        &self,
+        predicate: F,
+    ) -> TakeWhile<InOrderIter<'_, T, TreeNode>, F> {
+        return self.iter().take_while(predicate);
+    }
+
+    /// Return the values in ascending order, each paired with its rank,
+    /// the number of smaller values in the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// vec![30, 10, 20].iter().for_each(|v| tree.insert(*v));
+    /// let ranked: Vec<(u32, usize)> = tree.zip_with_rank().collect();
+    /// assert_eq!(vec![(10, 0), (20, 1), (30, 2)], ranked);
+    /// ```
+    #[must_use = "calling zip_with_rank without using the result has no effect"]
+    fn zip_with_rank(&self) -> RankedIter<'_, T, TreeNode> {
+        return self.iter().enumerate().map(|(rank, value)| (value, rank));
+    }
+
+    /// Prints red-black tree preorder
+    ///
+    /// # Example
+    ///
+    /// ```compile_fail
+    /// use tree_collections::common_trait::CommonTreeTrait;
+    /// let mut tree = CommonTreeTrait::new();
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// tree.insert(5);
+    /// tree.insert(2);
+    /// tree.insert(4);
+    /// tree.preorder_traversal(); // Preorder traversal: 3 2 1 5 4
+    ///
+    fn pre_order_traversal(&self) {
+        match self.get_root() {
+            None => println!("There is no node in the tree!"),
+            Some(node) => {
+                node.borrow().pre_order_traversal();
+                println!();
+            }
+        }
+    }
+
+    fn pre_order_traversal_for_test(&self, container: &mut Vec<T>) {
+        match self.get_root() {
+            None => println!("There is no node in the tree!"),
+            Some(node) => {
+                node.borrow().pre_order_traversal_for_test(container);
+            }
+        }
+    }
+
+    /// Return the values of the tree in preorder, each node before its children
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// (1..=3).for_each(|v| tree.insert(v));
+    /// assert_eq!(vec![2, 1, 3], tree.to_preorder_vec());
+    /// ```
+    #[must_use = "calling to_preorder_vec without using the result has no effect"]
+    fn to_preorder_vec(&self) -> Vec<T> {
+        let mut container = Vec::new();
+        if let Some(node) = self.get_root() {
+            node.borrow().pre_order_traversal_for_test(&mut container);
+        }
+        return container;
+    }
+
+    /// Return the values of the tree in postorder, each node after its children
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// (1..=3).for_each(|v| tree.insert(v));
+    /// assert_eq!(vec![1, 3, 2], tree.to_postorder_vec());
+    /// ```
+    #[must_use = "calling to_postorder_vec without using the result has no effect"]
+    fn to_postorder_vec(&self) -> Vec<T> {
+        let mut container = Vec::new();
+        if let Some(node) = self.get_root() {
+            node.borrow().post_order_traversal_for_test(&mut container);
+        }
+        return container;
+    }
+
+    /// Return the values of the tree level by level, from left to right
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// (1..=5).for_each(|v| tree.insert(v));
+    /// assert_eq!(vec![2, 1, 4, 3, 5], tree.to_levelorder_vec());
+    /// ```
+    #[must_use = "calling to_levelorder_vec without using the result has no effect"]
+    fn to_levelorder_vec(&self) -> Vec<T> {
+        let mut container = Vec::new();
+        let mut queue = VecDeque::new();
+        if let Some(root) = self.get_root() {
+            queue.push_back(root);
+        }
+        while let Some(node) = queue.pop_front() {
+            let node = node.borrow();
+            container.push(node.get_value());
+            if let Some(left) = node.get_left() {
+                queue.push_back(left);
+            }
+            if let Some(right) = node.get_right() {
+                queue.push_back(right);
+            }
+        }
+        return container;
+    }
+
+    /// Return the tree level by level with `None` for a missing child, the format
+    /// used by LeetCode. The children of a missing node are not listed and the
+    /// trailing `None`s are dropped, so the result has O(n) entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = BSTree::new();
+    /// vec![2, 1, 3, 4].iter().for_each(|v| tree.insert(*v));
+    /// assert_eq!(vec![Some(2), Some(1), Some(3), None, None, None, Some(4)], tree.to_bfs_vec());
+    /// ```
+    #[must_use = "calling to_bfs_vec without using the result has no effect"]
+    fn to_bfs_vec(&self) -> Vec<Option<T>> {
+        let mut container = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(self.get_root());
+        while let Some(node) = queue.pop_front() {
+            match node {
+                None => container.push(None),
+                Some(node) => {
+                    let node = node.borrow();
+                    container.push(Some(node.get_value()));
+                    queue.push_back(node.get_left());
+                    queue.push_back(node.get_right());
+                }
+            }
+        }
+        while container.last() == Some(&None) {
+            container.pop();
+        }
+        return container;
+    }
+
+    /// Return the number of nodes at each depth, starting with the root at depth 0.
+    /// The counts add up to [count_nodes](trait.CommonTreeTrait.html#method.count_nodes),
+    /// and there is one per level of nodes, so the nil leaves which red-black trees
+    /// count in [height](trait.CommonTreeTrait.html#method.height) are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// (1..=10).for_each(|v| tree.insert(v));
+    /// assert_eq!(vec![1, 2, 4, 3], tree.height_histogram());
+    /// ```
+    #[must_use = "calling height_histogram without using the result has no effect"]
+    fn height_histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+        let mut level: Vec<Shared<NodeCell<TreeNode>>> = self.get_root().into_iter().collect();
+        while !level.is_empty() {
+            histogram.push(level.len());
+            let mut next_level = Vec::new();
+            for node in level {
+                let node = node.borrow();
+                next_level.extend(node.get_left());
+                next_level.extend(node.get_right());
+            }
+            level = next_level;
+        }
+        return histogram;
+    }
+
+    /// Walk the tree depth first and call the visitor on every node,
+    /// see [TreeVisitor](trait.TreeVisitor.html)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// use tree_collections::common_trait::TreeVisitor;
+    ///
+    /// struct Count(usize);
+    ///
+    /// impl TreeVisitor<u32> for Count {
+    ///     fn visit_pre(&mut self, _value: u32) {
+    ///         self.0 += 1;
+    ///     }
+    /// }
+    ///
+    /// let tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+    /// let mut count = Count(0);
+    /// tree.walk(&mut count);
+    /// assert_eq!(3, count.0);
+    /// ```
+    fn walk<V: TreeVisitor<T>>(&self, visitor: &mut V) {
+        if let Some(node) = self.get_root() {
+            node.borrow().walk(visitor);
+        }
+    }
+
+    /// Determine whether the tree contains given value
+    ///
+    /// # Example
+    ///
+    /// ```compile_fail
+    /// use tree_collections::common_trait::CommonTreeTrait;
+    /// let mut tree = CommonTreeTrait::new();
+    /// tree.insert(1);
+    /// assert_eq!(true, tree.contains(1));
+    /// assert_eq!(false, tree.contains(0));
+    ///
+    #[inline]
+    #[must_use = "calling contains without using the result has no effect"]
+    fn contains(&self, value: T) -> bool {
+        match self.get_root() {
+            None => {
+                handle_not_found();
+                false
+            }
+            Some(node) => node.borrow().contains(value),
+        }
+    }
+
+    /// Return the stored value which is equal to the given value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(1);
+    /// assert_eq!(Some(1), tree.get(1));
+    /// assert_eq!(None, tree.get(2));
+    /// ```
+    #[must_use = "calling get without using the result has no effect"]
+    fn get(&self, value: T) -> Option<T> {
+        let mut current = self.get_root();
+        while let Some(node) = current {
+            let node_value = node.borrow().get_value();
+            if value == node_value {
+                return Some(node_value);
+            }
+            if value < node_value {
+                current = node.borrow().get_left();
+            } else {
+                current = node.borrow().get_right();
+            }
+        }
+        return None;
+    }
+
+    /// Determine whether the tree contains every given value,
+    /// stops at the first missing value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(true, tree.contains_all(vec![1, 2]));
+    /// assert_eq!(false, tree.contains_all(vec![1, 3]));
+    /// ```
+    #[must_use = "calling contains_all without using the result has no effect"]
+    fn contains_all<I: IntoIterator<Item = T>>(&self, values: I) -> bool {
+        values.into_iter().all(|value| self.contains(value))
+    }
+
+    /// Determine whether the tree contains none of the given values,
+    /// stops at the first value found
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// assert_eq!(true, tree.contains_none(vec![2, 3]));
+    /// assert_eq!(false, tree.contains_none(vec![2, 1]));
+    /// ```
+    #[must_use = "calling contains_none without using the result has no effect"]
+    fn contains_none<I: IntoIterator<Item = T>>(&self, values: I) -> bool {
+        !values.into_iter().any(|value| self.contains(value))
+    }
+
+    /// Return for each given value whether the tree contains it
+    ///
+    /// If the values are in ascending order they are matched against the in-order
+    /// traversal of the tree, stepping a few values towards each query and looking
+    /// it up in O(log n) when it is further away. So dense queries take O(n + m)
+    /// and sparse ones O(m log n), without walking the values in between.
+    /// Otherwise each value is looked up on its own.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// (1..=5).for_each(|v| tree.insert(v * 2));
+    /// assert_eq!(vec![false, true, true, false], tree.batch_contains(vec![1, 2, 4, 11]));
+    /// assert_eq!(vec![true, false], tree.batch_contains(vec![10, 3]));
+    /// ```
+    #[must_use = "calling batch_contains without using the result has no effect"]
+    fn batch_contains<I: IntoIterator<Item = T>>(&self, values: I) -> Vec<bool> {
+        let values: Vec<T> = values.into_iter().collect();
+        if !values.windows(2).all(|pair| pair[0] <= pair[1]) {
+            return values.iter().map(|value| self.contains(*value)).collect();
+        }
+        let mut iter = self.iter();
+        let mut current = iter.next();
+        let mut result = Vec::with_capacity(values.len());
+        for value in values {
+            let mut steps = 0;
+            while matches!(current, Some(current) if current < value) {
+                if steps == BATCH_CONTAINS_MAX_STEPS {
+                    iter.skip_to_lower_bound(value);
+                    current = iter.next();
+                    break;
+                }
+                current = iter.next();
+                steps += 1;
+            }
+            result.push(current == Some(value));
+        }
+        return result;
+    }
+
+    /// Determine whether the tree has no values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// assert_eq!(true, tree.is_empty());
+    /// tree.insert(1);
+    /// assert_eq!(false, tree.is_empty());
+    /// ```
+    #[must_use = "calling is_empty without using the result has no effect"]
+    fn is_empty(&self) -> bool {
+        self.get_root().is_none()
+    }
+
+    /// Determine whether the tree has no values, replaced by
+    /// [is_empty](trait.CommonTreeTrait.html#method.is_empty)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = RBTree::<u32>::new();
+    /// // before
+    /// #[allow(deprecated)]
+    /// let empty = tree.is_tree_empty();
+    /// // after
+    /// assert_eq!(empty, tree.is_empty());
+    /// ```
+    #[deprecated(since = "0.1.9", note = "use is_empty instead")]
+    #[must_use = "calling is_tree_empty without using the result has no effect"]
+    fn is_tree_empty(&self) -> bool {
+        self.is_empty()
+    }
+
+    #[must_use = "calling min without using the result has no effect"]
+    fn min(&self) -> Option<T> {
+        self.get_root()
+            .map(|node| node.borrow().get_min_value_in_children())
+    }
+
+    #[must_use = "calling max without using the result has no effect"]
+    fn max(&self) -> Option<T> {
+        self.get_root()
+            .map(|node| node.borrow().get_max_value_in_children())
+    }
+
+    /// Return both the minimum and the maximum value of the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// assert_eq!(None, tree.min_max());
+    /// tree.insert(3);
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(Some((1, 3)), tree.min_max());
+    /// ```
+    #[must_use = "calling min_max without using the result has no effect"]
+    fn min_max(&self) -> Option<(T, T)> {
+        match self.get_root() {
+            None => None,
+            Some(node) => {
+                let node = node.borrow();
+                Some((
+                    node.get_min_value_in_children(),
+                    node.get_max_value_in_children(),
+                ))
+            }
+        }
+    }
+
+    /// Return the number of values of the tree which are smaller than the given value,
+    /// so that [select](trait.CommonTreeTrait.html#method.select) of the rank of a
+    /// value in the tree returns that value
+    ///
+    /// The nodes do not store the size of their subtree, so the values before the
+    /// given one are walked in order, in O(rank + log n).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(5);
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// assert_eq!(0, tree.rank(1));
+    /// assert_eq!(2, tree.rank(4));
+    /// assert_eq!(3, tree.rank(6));
+    /// ```
+    #[must_use = "calling rank without using the result has no effect"]
+    fn rank(&self, value: T) -> usize {
+        return self.iter().take_while(|v| *v < value).count();
+    }
+
+    /// Return the k-th smallest value of the tree, starting from 0
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(5);
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// assert_eq!(Some(1), tree.select(0));
+    /// assert_eq!(Some(5), tree.select(2));
+    /// assert_eq!(None, tree.select(3));
+    /// ```
+    #[must_use = "calling select without using the result has no effect"]
+    fn select(&self, k: usize) -> Option<T> {
+        // in-order walk with an explicit stack, stops at the k-th value
+        let mut stack = vec![];
+        let mut current = self.get_root();
+        let mut index = 0;
+        loop {
+            while let Some(node) = current {
+                current = node.borrow().get_left();
+                stack.push(node);
+            }
+            match stack.pop() {
+                None => return None,
+                Some(node) => {
+                    if index == k {
+                        return Some(node.borrow().get_value());
+                    }
+                    index += 1;
+                    current = node.borrow().get_right();
+                }
+            }
+        }
+    }
+
+    /// Return a value of the tree chosen uniformly at random,
+    /// only available with the `rand` feature
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// let value = tree.sample(&mut rand::thread_rng()).unwrap();
+    /// assert_eq!(true, tree.contains(value));
+    /// ```
+    #[cfg(feature = "rand")]
+    #[must_use = "calling sample without using the result has no effect"]
+    fn sample<R: rand::Rng>(&self, rng: &mut R) -> Option<T> {
+        let count = self.count_nodes();
+        if count == 0 {
+            return None;
+        }
+        return self.select(rng.gen_range(0, count));
+    }
+
+    /// Return the middle value of the tree, or the two middle values
+    /// if the tree has an even number of values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// use tree_collections::common_trait::MedianResult;
+    /// let mut tree = AVLTree::new();
+    /// assert_eq!(None, tree.median());
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(Some(MedianResult::Even(1, 2)), tree.median());
+    /// tree.insert(3);
+    /// assert_eq!(Some(MedianResult::Odd(2)), tree.median());
+    /// ```
+    #[must_use = "calling median without using the result has no effect"]
+    fn median(&self) -> Option<MedianResult<T>> {
+        let count = self.count_nodes();
+        if count == 0 {
+            return None;
+        }
+        if count % 2 == 1 {
+            return Some(MedianResult::Odd(self.select(count / 2)?));
+        }
+        return Some(MedianResult::Even(
+            self.select(count / 2 - 1)?,
+            self.select(count / 2)?,
+        ));
+    }
+
+    /// Return the smallest value in the tree which is greater than the given value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// assert_eq!(Some(3), tree.successor(1));
+    /// assert_eq!(Some(3), tree.successor(2));
+    /// assert_eq!(None, tree.successor(3));
+    /// ```
+    #[must_use = "calling successor without using the result has no effect"]
+    fn successor(&self, value: T) -> Option<T> {
+        let mut current = self.get_root();
+        let mut result = None;
+        while let Some(node) = current {
+            let node_value = node.borrow().get_value();
+            if node_value > value {
+                result = Some(node_value);
+                current = node.borrow().get_left();
+            } else {
+                current = node.borrow().get_right();
+            }
+        }
+        return result;
+    }
+
+    /// Return the smallest value in the tree which is greater than or equal to the given value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// assert_eq!(Some(1), tree.ceiling(1));
+    /// assert_eq!(Some(3), tree.ceiling(2));
+    /// assert_eq!(None, tree.ceiling(4));
+    /// ```
+    #[must_use = "calling ceiling without using the result has no effect"]
+    fn ceiling(&self, value: T) -> Option<T> {
+        let mut current = self.get_root();
+        let mut result = None;
+        while let Some(node) = current {
+            let node_value = node.borrow().get_value();
+            if node_value >= value {
+                result = Some(node_value);
+                current = node.borrow().get_left();
+            } else {
+                current = node.borrow().get_right();
+            }
+        }
+        return result;
+    }
+
+    /// Return the first value which is not less than the given value, named after
+    /// `std::lower_bound` in C++. The same as [ceiling](trait.CommonTreeTrait.html#method.ceiling),
+    /// so an equal value is returned, unlike [upper_bound](trait.CommonTreeTrait.html#method.upper_bound).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// assert_eq!(Some(3), tree.lower_bound(3));
+    /// assert_eq!(Some(3), tree.lower_bound(2));
+    /// ```
+    #[must_use = "calling lower_bound without using the result has no effect"]
+    fn lower_bound(&self, value: T) -> Option<T> {
+        return self.ceiling(value);
+    }
+
+    /// Return the first value which is greater than the given value, named after
+    /// `std::upper_bound` in C++. The same as [successor](trait.CommonTreeTrait.html#method.successor),
+    /// so an equal value is skipped, unlike [lower_bound](trait.CommonTreeTrait.html#method.lower_bound).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// assert_eq!(None, tree.upper_bound(3));
+    /// assert_eq!(Some(3), tree.upper_bound(2));
+    /// ```
+    #[must_use = "calling upper_bound without using the result has no effect"]
+    fn upper_bound(&self, value: T) -> Option<T> {
+        return self.successor(value);
+    }
+
+    /// Return the largest value in the tree which is less than the given value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// assert_eq!(Some(1), tree.predecessor(3));
+    /// assert_eq!(Some(1), tree.predecessor(2));
+    /// assert_eq!(None, tree.predecessor(1));
+    /// ```
+    #[must_use = "calling predecessor without using the result has no effect"]
+    fn predecessor(&self, value: T) -> Option<T> {
+        let mut current = self.get_root();
+        let mut result = None;
+        while let Some(node) = current {
+            let node_value = node.borrow().get_value();
+            if node_value < value {
+                result = Some(node_value);
+                current = node.borrow().get_right();
+            } else {
+                current = node.borrow().get_left();
+            }
+        }
+        return result;
+    }
+
+    /// Return every pair of consecutive values in sorted order as `(prev, curr)`,
+    /// trees with less than two values yield nothing. The pairs are produced lazily
+    /// by two in-order iterators, one a step ahead of the other, so only O(height)
+    /// extra memory is used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(4);
+    /// tree.insert(6);
+    /// let max_gap = tree.consecutive_pairs().map(|(a, b)| b - a).max();
+    /// assert_eq!(Some(3), max_gap);
+    /// ```
+    #[must_use = "calling consecutive_pairs without using the result has no effect"]
+    fn consecutive_pairs(&self) -> PairsIter<'_, T, TreeNode> {
+        return self.iter().zip(self.iter().skip(1));
+    }
+
+    /// Return the minimum value of the tree, skipping the given value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(Some(2), tree.min_excluding(1));
+    /// assert_eq!(Some(1), tree.min_excluding(2));
+    /// ```
+    #[must_use = "calling min_excluding without using the result has no effect"]
+    fn min_excluding(&self, exclude: T) -> Option<T> {
+        match self.min() {
+            Some(min) if min == exclude => self.successor(min),
+            min => min,
+        }
+    }
+
+    /// Return the maximum value of the tree, skipping the given value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(Some(1), tree.max_excluding(2));
+    /// assert_eq!(Some(2), tree.max_excluding(1));
+    /// ```
+    #[must_use = "calling max_excluding without using the result has no effect"]
+    fn max_excluding(&self, exclude: T) -> Option<T> {
+        match self.max() {
+            Some(max) if max == exclude => self.predecessor(max),
+            max => max,
+        }
+    }
+
+    /// Determine whether every value in the left subtree of each node is smaller
+    /// and every value in the right subtree is greater than the node's value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// assert_eq!(true, tree.is_valid_bst());
+    /// ```
+    #[must_use = "calling is_valid_bst without using the result has no effect"]
+    fn is_valid_bst(&self) -> bool {
+        match self.get_root() {
+            None => true,
+            Some(node) => node.borrow().is_valid_bst(None, None),
+        }
+    }
+
+    /// Determine whether the tree keeps the red-black tree properties.
+    /// Trees without colors only check the binary search tree property,
+    /// red-black trees override it with the full check.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = RBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(true, tree.is_valid_red_black_tree());
+    /// ```
+    #[must_use = "calling is_valid_red_black_tree without using the result has no effect"]
+    fn is_valid_red_black_tree(&self) -> bool {
+        self.is_valid_bst()
+    }
+
+    /// Serialize the tree in Newick format, e.g. `((1,3)2,(5,7)6)4`,
+    /// with the node values as labels. A missing child of a node with
+    /// one child is written as an empty label, so `(,2)1` has only a right child.
+    /// An empty tree gives an empty string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AVLTree::new();
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// tree.insert(3);
+    /// assert_eq!("(1,3)2", tree.to_newick());
+    /// ```
+    #[must_use = "calling to_newick without using the result has no effect"]
+    fn to_newick(&self) -> String {
+        let mut newick = String::new();
+        if let Some(node) = self.get_root() {
+            node.borrow().to_newick(&mut newick);
+        }
+        return newick;
+    }
+
+    /// Count the values in both trees, in O(m + n) without building a new tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut a = AVLTree::new();
+    /// let mut b = AVLTree::new();
+    /// (1..=5).for_each(|v| a.insert(v));
+    /// (4..=8).for_each(|v| b.insert(v));
+    /// assert_eq!(2, a.intersection_size(&b));
+    /// ```
+    #[must_use = "calling intersection_size without using the result has no effect"]
+    fn intersection_size(&self, other: &Self) -> usize {
+        let (common, _, _) = merge_counts(self.iter(), other.iter());
+        return common;
+    }
+
+    /// Count the values in either tree, in O(m + n) without building a new tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut a = AVLTree::new();
+    /// let mut b = AVLTree::new();
+    /// (1..=5).for_each(|v| a.insert(v));
+    /// (4..=8).for_each(|v| b.insert(v));
+    /// assert_eq!(8, a.union_size(&b));
+    /// ```
+    #[must_use = "calling union_size without using the result has no effect"]
+    fn union_size(&self, other: &Self) -> usize {
+        let (common, only_self, only_other) = merge_counts(self.iter(), other.iter());
+        return common + only_self + only_other;
+    }
+
+    /// Count the values in this tree but not in the other one,
+    /// in O(m + n) without building a new tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut a = AVLTree::new();
+    /// let mut b = AVLTree::new();
+    /// (1..=5).for_each(|v| a.insert(v));
+    /// (4..=8).for_each(|v| b.insert(v));
+    /// assert_eq!(3, a.difference_size(&b));
+    /// ```
+    #[must_use = "calling difference_size without using the result has no effect"]
+    fn difference_size(&self, other: &Self) -> usize {
+        let (_, only_self, _) = merge_counts(self.iter(), other.iter());
+        return only_self;
+    }
+
+    /// Determine whether the two trees have no value in common,
+    /// stops at the first common value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut a = AVLTree::new();
+    /// let mut b = AVLTree::new();
+    /// a.insert(1);
+    /// b.insert(2);
+    /// assert_eq!(true, a.is_disjoint(&b));
+    /// b.insert(1);
+    /// assert_eq!(false, a.is_disjoint(&b));
+    /// ```
+    #[must_use = "calling is_disjoint without using the result has no effect"]
+    fn is_disjoint(&self, other: &Self) -> bool {
+        let mut first = self.iter().peekable();
+        let mut second = other.iter().peekable();
+        while let (Some(a), Some(b)) = (first.peek(), second.peek()) {
+            if a < b {
+                first.next();
+            } else if a > b {
+                second.next();
+            } else {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    fn print(&self) {
+        match self.get_root() {
+            None => println!("This is an empty tree."),
+            Some(node) => node.borrow().print(),
+        }
+    }
+
+    /// Print the tree like `print`, but only down to `max_depth`, the root being
+    /// at depth 0. The cut subtrees are drawn as `...`, so large trees stay readable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = FastRBTree::from_sorted_slice(&(0..1000).collect::<Vec<u32>>());
+    /// tree.print_bounded(3);
+    /// ```
+    fn print_bounded(&self, max_depth: usize) {
+        match self.get_root() {
+            None => println!("This is an empty tree."),
+            Some(node) => node.borrow().print_bounded(max_depth),
+        }
+    }
+}
+
+// Walk two sorted iterators together and count the values in both,
+// the values only in the first one and the values only in the second one
+fn merge_counts<T: Ord, A: Iterator<Item = T>, B: Iterator<Item = T>>(
+    first: A,
+    second: B,
+) -> (usize, usize, usize) {
+    let (mut common, mut only_first, mut only_second) = (0, 0, 0);
+    let mut first = first.peekable();
+    let mut second = second.peekable();
+    loop {
+        match (first.peek(), second.peek()) {
+            (None, None) => break,
+            (Some(_), None) => {
+                only_first += 1;
+                first.next();
+            }
+            (None, Some(_)) => {
+                only_second += 1;
+                second.next();
+            }
+            (Some(a), Some(b)) => {
+                if a < b {
+                    only_first += 1;
+                    first.next();
+                } else if a > b {
+                    only_second += 1;
+                    second.next();
+                } else {
+                    common += 1;
+                    first.next();
+                    second.next();
+                }
+            }
+        }
+    }
+    return (common, only_first, only_second);
+}
+
+/// Provide common functions for nodes
+// Common trait for TreeNode
+pub trait CommonTreeNodeTrait<T: Ord + Copy + Debug + Display> {
+    /// Get left child node
+    fn get_left(&self) -> Option<Shared<NodeCell<Self>>>;
+
+    /// Get right child node
+    fn get_right(&self) -> Option<Shared<NodeCell<Self>>>;
+
+    /// Get value from current node
+    fn get_value(&self) -> T;
+
+    /// Get value string from current node
+    fn get_value_to_print(&self) -> String;
+
+    /// Return the leaves number of current node, which will be called by
+    /// [CommonTreeTrait.count_leaves](trait.CommonTreeTrait.html#method.count_leaves)
+    fn count_leaves(&self) -> u32 {
+        let left = self.get_left();
+        let right = self.get_right();
+        match (left, right) {
+            (None, None) => return 1,
+            (None, Some(right)) => return right.borrow().count_leaves(),
+            (Some(left), None) => return left.borrow().count_leaves(),
+            (Some(left), Some(right)) => {
+                return right.borrow().count_leaves() + left.borrow().count_leaves();
+            }
+        }
+    }
+
+    /// Return the nodes number of current node, which will be called by
+    /// [CommonTreeTrait.count_nodes](trait.CommonTreeTrait.html#method.count_nodes)
+    fn count_nodes(&self) -> usize {
+        let left = match self.get_left() {
+            None => 0,
+            Some(l) => l.borrow().count_nodes(),
+        };
+        let right = match self.get_right() {
+            None => 0,
+            Some(r) => r.borrow().count_nodes(),
+        };
+        return left + right + 1;
+    }
+
+    /// Return the number of nodes with two children in the subtree, which will be called by
+    /// [CommonTreeTrait.count_full_nodes](trait.CommonTreeTrait.html#method.count_full_nodes)
+    fn count_full_nodes(&self) -> usize {
+        match (self.get_left(), self.get_right()) {
+            (Some(left), Some(right)) => {
+                left.borrow().count_full_nodes() + right.borrow().count_full_nodes() + 1
+            }
+            (Some(child), None) | (None, Some(child)) => child.borrow().count_full_nodes(),
+            (None, None) => 0,
+        }
+    }
+
+    /// Return the height of current node, which will be called by
+    /// [CommonTreeTrait.height](trait.CommonTreeTrait.html#method.height)
+    fn get_height(&self) -> u32 {
+        let left = self.get_left();
+        let right = self.get_right();
+        let left_height = match left {
+            None => 0,
+            Some(l) => l.borrow().get_height(),
+        };
+        let right_height = match right {
+            None => 0,
+            Some(r) => r.borrow().get_height(),
+        };
+        return max(left_height, right_height) + 1;
+    }
+
+    /// Return the height of an empty child, which is 0 unless
+    /// the tree counts its nil leaves in the height
+    fn get_nil_height(&self) -> u32 {
+        return 0;
+    }
+
+    /// Print nodes inorder, which will be called by
+    /// [CommonTreeTrait.in_order_traversal](trait.CommonTreeTrait.html#method.in_order_traversal)
+    fn in_order_traversal(&self) {
+        let left = self.get_left();
+        if let Some(left) = left {
+            left.borrow().in_order_traversal();
+        }
+        print!("{} ", self.get_value_to_print());
+        let right = self.get_right();
+        if let Some(right) = right {
+            right.borrow().in_order_traversal();
+        }
+    }
+
+    fn in_order_traversal_for_test(&self, container: &mut Vec<T>) {
+        let left = self.get_left();
+        if let Some(left) = left {
+            left.borrow().in_order_traversal_for_test(container);
+        }
+        container.push(self.get_value());
+        let right = self.get_right();
+        if let Some(right) = right {
+            right.borrow().in_order_traversal_for_test(container);
+        }
+    }
+
+    /// Print nodes preorder, which will be called by
+    /// [CommonTreeTrait.pre_order_traversal](trait.CommonTreeTrait.html#method.pre_order_traversal)
+    fn pre_order_traversal(&self) {
+        print!("{} ", self.get_value_to_print());
+        let left = self.get_left();
+        if let Some(left) = left {
+            left.borrow().pre_order_traversal();
+        }
+        let right = self.get_right();
+        if let Some(right) = right {
+            right.borrow().pre_order_traversal();
+        }
+    }
+
+    fn pre_order_traversal_for_test(&self, container: &mut Vec<T>) {
+        container.push(self.get_value());
+        let left = self.get_left();
+        if let Some(left) = left {
+            left.borrow().pre_order_traversal_for_test(container);
+        }
+        let right = self.get_right();
+        if let Some(right) = right {
+            right.borrow().pre_order_traversal_for_test(container);
+        }
+    }
+
+    /// Push the values of the node and its successors in postorder, which will be called by
+    /// [CommonTreeTrait.to_postorder_vec](trait.CommonTreeTrait.html#method.to_postorder_vec)
+    fn post_order_traversal_for_test(&self, container: &mut Vec<T>) {
+        if let Some(left) = self.get_left() {
+            left.borrow().post_order_traversal_for_test(container);
+        }
+        if let Some(right) = self.get_right() {
+            right.borrow().post_order_traversal_for_test(container);
+        }
+        container.push(self.get_value());
+    }
+
+    /// Visit the node and its successors, which will be called by
+    /// [CommonTreeTrait.walk](trait.CommonTreeTrait.html#method.walk)
+    fn walk<V: TreeVisitor<T>>(&self, visitor: &mut V) {
+        let value = self.get_value();
+        visitor.visit_pre(value);
+        if let Some(left) = self.get_left() {
+            left.borrow().walk(visitor);
+        }
+        visitor.visit_in(value);
+        if let Some(right) = self.get_right() {
+            right.borrow().walk(visitor);
+        }
+        visitor.visit_post(value);
+    }
+
+    /// Determine whether the node and its successors contains given value,
+    /// which will be called by
+    /// [CommonTreeTrait.contains](trait.CommonTreeTrait.html#method.contains)
+    #[inline]
+    fn contains(&self, value: T) -> bool {
+        let current_value = self.get_value();
+        return if current_value == value {
+            true
+        } else if current_value > value {
+            match self.get_left() {
+                None => {
+                    handle_not_found();
+                    false
+                }
+                Some(node) => node.borrow().contains(value),
+            }
+        } else {
+            match self.get_right() {
+                None => {
+                    handle_not_found();
+                    false
+                }
+                Some(node) => node.borrow().contains(value),
+            }
+        };
+    }
+
+    /// Determine whether the node and its successors are inside the bounds and keep
+    /// the binary search tree property, which will be called by
+    /// [CommonTreeTrait.is_valid_bst](trait.CommonTreeTrait.html#method.is_valid_bst)
+    fn is_valid_bst(&self, lower: Option<T>, upper: Option<T>) -> bool {
+        let value = self.get_value();
+        if matches!(lower, Some(lower) if value <= lower)
+            || matches!(upper, Some(upper) if value >= upper)
+        {
+            return false;
+        }
+        let left_valid = match self.get_left() {
+            None => true,
+            Some(node) => node.borrow().is_valid_bst(lower, Some(value)),
+        };
+        let right_valid = match self.get_right() {
+            None => true,
+            Some(node) => node.borrow().is_valid_bst(Some(value), upper),
+        };
+        return left_valid && right_valid;
+    }
+
+    /// Append the Newick string of the node and its successors, which will be called by
+    /// [CommonTreeTrait.to_newick](trait.CommonTreeTrait.html#method.to_newick)
+    fn to_newick(&self, newick: &mut String) {
+        let left = self.get_left();
+        let right = self.get_right();
+        if left.is_some() || right.is_some() {
+            newick.push('(');
+            if let Some(node) = left {
+                node.borrow().to_newick(newick);
+            }
+            newick.push(',');
+            if let Some(node) = right {
+                node.borrow().to_newick(newick);
+            }
+            newick.push(')');
+        }
+        newick.push_str(&self.get_value().to_string());
+    }
+
+    // find the min value in its children
+    fn get_min_value_in_children(&self) -> T {
+        match self.get_left() {
+            Some(left) => left.borrow().get_min_value_in_children(),
+            None => self.get_value(),
+        }
+    }
+
+    // find the max value in its children
+    fn get_max_value_in_children(&self) -> T {
+        match self.get_right() {
+            Some(right) => right.borrow().get_max_value_in_children(),
+            None => self.get_value(),
+        }
+    }
+
+    // print the tree with structure
+    fn print(&self) {
+        self.print_bounded(usize::MAX);
+    }
+
+    /// Print the tree down to `max_depth`, which will be called by
+    /// [CommonTreeTrait.print_bounded](trait.CommonTreeTrait.html#method.print_bounded)
+    fn print_bounded(&self, max_depth: usize) {
+        for line in self.print_lines(max_depth) {
+            println!("{}", line);
+        }
+    }
+
+    /// Return the lines printed by [print_bounded](trait.CommonTreeNodeTrait.html#method.print_bounded),
+    /// the children below `max_depth` are drawn as `...`
+    fn print_lines(&self, max_depth: usize) -> Vec<String> {
+        // get height, one more level is kept for the "..." of the cut subtrees
+        let height = min(self.get_height() as usize, max_depth.saturating_add(2));
+
+        // the last row's width is 2^(n-1) * 3 + 1
+        // to be the array's width
+        let array_height = height * 2 - 1;
+        let array_width = if height < 2 {
+            1
+        } else {
+            (2 << (height - 2)) * 3 + 1_usize
+        };
+        // use an array to store all the elements
+        let mut container_raw = vec![String::from(" "); array_width * array_height];
+        let mut container_base: Vec<_> = container_raw
+            .as_mut_slice()
+            .chunks_mut(array_width)
+            .collect();
+        let container: &mut [&mut [String]] = container_base.as_mut_slice();
+
+        // use print_helper to recursive traverse the tree
+        self.print_helper(0, array_width / 2, container, height, max_depth);
+
+        // concatenate the structure of the tree
+        let mut lines = vec![];
+        for row in container.iter() {
+            let mut line = String::new();
+            for cell in row.iter() {
+                line += cell;
+            }
+            lines.push(line);
+        }
+        return lines;
+    }
+
+    fn print_helper(
+        &self,
+        row_index: usize,
+        column_index: usize,
+        container: &mut [&mut [String]],
+        height: usize,
+        max_depth: usize,
+    ) {
+        // get current height
+        let curr_height = row_index.div_ceil(2);
+
+        // below the max depth, only mark that the subtree is cut
+        if curr_height > max_depth {
+            container[row_index][column_index] = String::from("...");
+            return;
+        }
+        // save current node into the container
+        container[row_index][column_index] = self.get_value_to_print();
+
+        // if it is the last level, then return
+        if curr_height == height {
+            return;
+        }
+        // compute the gap between curr height and the next level
+        let gap = height - curr_height - 1;
+
+        // if it has left child, then record "/" and the left node's value
+        match self.get_left() {
+            None => (),
+            Some(node) => {
+                container[row_index + 1][column_index - gap] = String::from("/");
+                let left_child = node.borrow();
+                left_child.print_helper(
+                    row_index + 2,
+                    column_index - gap * 2,
+                    container,
+                    height,
+                    max_depth,
+                );
+            }
+        }
+
+        // if it has right child, then record "\" and the right node's value
+        match self.get_right() {
+            None => (),
+            Some(node) => {
+                container[row_index + 1][column_index + gap] = String::from("\\");
+                let right_child = node.borrow();
+                right_child.print_helper(
+                    row_index + 2,
+                    column_index + gap * 2,
+                    container,
+                    height,
+                    max_depth,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MedianResult, TreeVisitor};
+    use crate::prelude::*;
+    use proptest::prelude::*;
+    use static_assertions::assert_impl_all;
+
+    #[test]
+    fn test_contains_all_and_none() {
+        let mut tree = FastRBTree::new();
+        [1, 3, 5, 7, 9].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        for queries in [vec![], vec![1, 9], vec![1, 2], vec![2, 4], vec![3, 5, 7]] {
+            let mut expected_all = true;
+            let mut expected_none = true;
+            for v in &queries {
+                if tree.contains(*v) {
+                    expected_none = false;
+                } else {
+                    expected_all = false;
+                }
+            }
+            assert_eq!(tree.contains_all(queries.clone()), expected_all);
+            assert_eq!(tree.contains_none(queries.clone()), expected_none);
+        }
+    }
+
+    #[test]
+    fn test_contains_all_and_none_short_circuit() {
+        let mut tree = FastRBTree::new();
+        [1, 3, 5].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+
+        let mut count = 0;
+        let result = tree.contains_all(vec![1, 2, 3, 5].into_iter().inspect(|_| count += 1));
+        assert!(!result);
+        assert_eq!(count, 2);
+
+        let mut count = 0;
+        let result = tree.contains_none(vec![0, 3, 4, 5].into_iter().inspect(|_| count += 1));
+        assert!(!result);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_batch_contains() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let mut tree = FastRBTree::new();
+        let mut avl_tree = AVLTree::new();
+        (0..300).for_each(|_| {
+            let v = rng.gen_range(0, 1000u32);
+            tree.insert(v);
+            avl_tree.insert(v);
+        });
+        assert_eq!(tree.batch_contains(Vec::new()), vec![]);
+        for _ in 0..20 {
+            let mut queries: Vec<u32> = (0..100).map(|_| rng.gen_range(0, 1100)).collect();
+            let expected: Vec<bool> = queries.iter().map(|v| tree.contains(*v)).collect();
+            assert_eq!(tree.batch_contains(queries.clone()), expected);
+            assert_eq!(avl_tree.batch_contains(queries.clone()), expected);
+
+            // sorted, with repeated values, takes the merge-scan
+            queries.sort();
+            let expected: Vec<bool> = queries.iter().map(|v| tree.contains(*v)).collect();
+            assert_eq!(tree.batch_contains(queries.clone()), expected);
+            assert_eq!(avl_tree.batch_contains(queries), expected);
+        }
+
+        // sparse sorted queries are looked up instead of stepped to
+        let mut tree = FastRBTree::new();
+        tree.extend((0..10_000).map(|v| v * 2));
+        let queries = vec![0, 1, 2, 9_999, 10_000, 10_002, 19_998, 19_999, 30_000];
+        let expected = vec![true, false, true, false, true, true, true, false, false];
+        assert_eq!(tree.batch_contains(queries), expected);
+    }
+
+    #[test]
+    fn test_successor_and_predecessor() {
+        let mut tree = AVLTree::new();
+        [10, 20, 30, 40].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        assert_eq!(tree.successor(5), Some(10));
+        assert_eq!(tree.successor(20), Some(30));
+        assert_eq!(tree.successor(25), Some(30));
+        assert_eq!(tree.successor(40), None);
+        assert_eq!(tree.predecessor(45), Some(40));
+        assert_eq!(tree.predecessor(20), Some(10));
+        assert_eq!(tree.predecessor(25), Some(20));
+        assert_eq!(tree.predecessor(10), None);
+    }
+
+    #[test]
+    fn test_min_max_excluding() {
+        let mut tree = FastRBTree::new();
+        assert_eq!(tree.min_excluding(1), None);
+        assert_eq!(tree.max_excluding(1), None);
+        [1, 2, 3, 4, 5].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        // excluded value is the minimum
+        assert_eq!(tree.min_excluding(1), Some(2));
+        assert_eq!(tree.max_excluding(1), Some(5));
+        // excluded value is the maximum
+        assert_eq!(tree.min_excluding(5), Some(1));
+        assert_eq!(tree.max_excluding(5), Some(4));
+        // excluded value is an interior value
+        assert_eq!(tree.min_excluding(3), Some(1));
+        assert_eq!(tree.max_excluding(3), Some(5));
+        // excluded value is not in the tree
+        assert_eq!(tree.min_excluding(9), Some(1));
+        assert_eq!(tree.max_excluding(9), Some(5));
+
+        let mut single = FastRBTree::new();
+        single.insert(7);
+        assert_eq!(single.min_excluding(7), None);
+        assert_eq!(single.max_excluding(7), None);
+    }
+
+    #[test]
+    fn test_is_valid_bst() {
+        let mut avl_tree = AVLTree::new();
+        let mut bs_tree = BSTree::new();
+        assert!(avl_tree.is_valid_bst());
+        [12, 1, 9, 2, 0, 11, 7, 19, 4, 15].iter().for_each(|v| {
+            avl_tree.insert(*v);
+            bs_tree.insert(*v);
+        });
+        assert!(avl_tree.is_valid_bst());
+        assert!(bs_tree.is_valid_bst());
+        // trees without colors fall back to the binary search tree check
+        assert!(avl_tree.is_valid_red_black_tree());
+        assert!(bs_tree.is_valid_red_black_tree());
+    }
+
+    #[test]
+    fn test_consecutive_pairs() {
+        let mut tree = AVLTree::new();
+        [5, 1, 9, 3, 4].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let pairs: Vec<(u32, u32)> = tree.consecutive_pairs().collect();
+        assert_eq!(pairs, vec![(1, 3), (3, 4), (4, 5), (5, 9)]);
+        assert_eq!(tree.consecutive_pairs().map(|(a, b)| b - a).max(), Some(4));
+
+        let mut tree = FastRBTree::<u32>::new();
+        assert_eq!(tree.consecutive_pairs().next(), None);
+        tree.insert(1);
+        assert_eq!(tree.consecutive_pairs().next(), None);
+
+        // the pairs are produced one at a time
+        tree.extend(2..10_000);
+        let first: Vec<(u32, u32)> = tree.consecutive_pairs().take(2).collect();
+        assert_eq!(first, vec![(1, 2), (2, 3)]);
+        assert_eq!(tree.consecutive_pairs().count(), 9998);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let mut avl_tree = AVLTree::new();
+        let mut rb_tree = RBTree::new();
+        let mut fast_rb_tree = FastRBTree::new();
+        assert_eq!(avl_tree.min_max(), None);
+        assert_eq!(rb_tree.min_max(), None);
+        assert_eq!(fast_rb_tree.min_max(), None);
+
+        avl_tree.insert(7);
+        rb_tree.insert(7);
+        fast_rb_tree.insert(7);
+        assert_eq!(avl_tree.min_max(), Some((7, 7)));
+        assert_eq!(rb_tree.min_max(), Some((7, 7)));
+        assert_eq!(fast_rb_tree.min_max(), Some((7, 7)));
+
+        [5, 3, 8, 1, 4, 9].iter().for_each(|v| {
+            avl_tree.insert(*v);
+            rb_tree.insert(*v);
+            fast_rb_tree.insert(*v);
+        });
+        assert_eq!(
+            avl_tree.min_max(),
+            Some((avl_tree.min().unwrap(), avl_tree.max().unwrap()))
+        );
+        assert_eq!(
+            rb_tree.min_max(),
+            Some((rb_tree.min().unwrap(), rb_tree.max().unwrap()))
+        );
+        assert_eq!(
+            fast_rb_tree.min_max(),
+            Some((fast_rb_tree.min().unwrap(), fast_rb_tree.max().unwrap()))
+        );
+        assert_eq!(fast_rb_tree.min_max(), Some((1, 9)));
+    }
+
+    #[test]
+    fn test_select_and_median() {
+        let mut tree = FastRBTree::new();
+        assert_eq!(tree.count_nodes(), 0);
+        assert_eq!(tree.select(0), None);
+        assert_eq!(tree.median(), None);
+
+        tree.insert(4);
+        assert_eq!(tree.median(), Some(MedianResult::Odd(4)));
+
+        [9, 1, 7, 3].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        assert_eq!(tree.count_nodes(), 5);
+        assert_eq!(tree.select(0), Some(1));
+        assert_eq!(tree.select(4), Some(9));
+        assert_eq!(tree.select(5), None);
+        assert_eq!(tree.median(), Some(MedianResult::Odd(4)));
+        tree.insert(8);
+        assert_eq!(tree.median(), Some(MedianResult::Even(4, 7)));
+
+        let mut avl_tree = AVLTree::new();
+        let mut rb_tree = RBTree::new();
+        (1..=10).for_each(|v| {
+            avl_tree.insert(v);
+            rb_tree.insert(v);
+        });
+        assert_eq!(avl_tree.median(), Some(MedianResult::Even(5, 6)));
+        assert_eq!(rb_tree.median(), Some(MedianResult::Even(5, 6)));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_sample() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.sample(&mut rng), None);
+        (0..10).for_each(|v| {
+            tree.insert(v);
+        });
+
+        let mut counts = [0; 10];
+        for _ in 0..10000 {
+            let value = tree.sample(&mut rng).unwrap();
+            assert!(tree.contains(value));
+            counts[value as usize] += 1;
+        }
+        // each value is expected 1000 times
+        counts.iter().for_each(|count| {
+            assert!(
+                *count > 850 && *count < 1150,
+                "count {} is not uniform",
+                count
+            );
+        });
+    }
+
+    #[test]
+    fn test_iter_and_range() {
+        let mut tree = FastRBTree::new();
+        assert_eq!(tree.iter().next(), None);
+        assert_eq!(tree.range(1, 10), vec![]);
+
+        (1..=100).rev().for_each(|v| {
+            tree.insert(v);
+        });
+        assert_eq!(
+            tree.iter().collect::<Vec<u32>>(),
+            (1..=100).collect::<Vec<u32>>()
+        );
+        assert_eq!(tree.range(10, 13), vec![10, 11, 12, 13]);
+        assert_eq!(tree.range(99, 200), vec![99, 100]);
+        assert_eq!(tree.range(5, 4), vec![]);
+
+        let mut avl_tree = AVLTree::new();
+        [10, 20, 30, 40].iter().for_each(|v| {
+            avl_tree.insert(*v);
+        });
+        assert_eq!(avl_tree.range(15, 35), vec![20, 30]);
+        assert_eq!(avl_tree.range(0, 10), vec![10]);
+    }
+
+    #[test]
+    fn test_take_while_sorted() {
+        let mut tree = AVLTree::new();
+        (0..1000).for_each(|v| {
+            tree.insert(v);
+        });
+        let below: Vec<u32> = tree.iter().take_while(|&v| v < 50).collect();
+        assert_eq!(below, tree.range(u32::MIN, 49));
+
+        // the predicate is only called until it first fails
+        let mut calls = 0;
+        let below: Vec<u32> = tree
+            .take_while_sorted(|v| {
+                calls += 1;
+                *v < 50
+            })
+            .collect();
+        assert_eq!(below, tree.range(u32::MIN, 49));
+        assert_eq!(calls, 51);
+    }
+
+    #[test]
+    fn test_zip_with_rank() {
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.zip_with_rank().next(), None);
+
+        (0..100).map(|v| (v * 37) % 100 * 3).for_each(|v| {
+            tree.insert(v);
+        });
+        let sorted = tree.to_sorted_vec();
+        let mut count = 0;
+        for (value, rank) in tree.zip_with_rank() {
+            assert_eq!(sorted[rank], value);
+            assert_eq!(tree.rank(value), rank);
+            assert_eq!(rank, count);
+            count += 1;
+        }
+        assert_eq!(count, 100);
+    }
+
+    #[test]
+    fn test_to_newick() {
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.to_newick(), "");
+        tree.insert(4);
+        assert_eq!(tree.to_newick(), "4");
+        [2, 6, 1, 3, 5, 7].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        assert_eq!(tree.to_newick(), "((1,3)2,(5,7)6)4");
+
+        let fast_rb_tree = FastRBTree::from_sorted_slice(&[1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(fast_rb_tree.to_newick(), "((1,3)2,(5,7)6)4");
+
+        let mut rb_tree = RBTree::new();
+        [1, 2].iter().for_each(|v| {
+            rb_tree.insert(*v);
+        });
+        assert_eq!(rb_tree.to_newick(), "(,2)1");
+    }
+
+    #[test]
+    fn test_height_difference() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut avl_tree = AVLTree::new();
+        let mut fast_rb_tree = FastRBTree::new();
+        assert_eq!(avl_tree.height_difference(), 0);
+        assert_eq!(fast_rb_tree.height_difference(), 0);
+        for _ in 0..500 {
+            let value: u32 = rng.gen_range(0, 1000);
+            avl_tree.insert(value);
+            fast_rb_tree.insert(value);
+            assert!(avl_tree.is_height_balanced());
+            assert!(fast_rb_tree.is_valid_red_black_tree());
+        }
+
+        // a left leaning red-black tree may lean by more than one level
+        let mut fast_rb_tree = FastRBTree::new();
+        (1..=6).rev().for_each(|v| {
+            fast_rb_tree.insert(v);
+        });
+        assert_eq!(fast_rb_tree.height_difference(), -2);
+        assert!(!fast_rb_tree.is_height_balanced());
+        assert!(fast_rb_tree.is_valid_red_black_tree());
+
+        let mut rb_tree = RBTree::new();
+        rb_tree.insert(1);
+        assert_eq!(rb_tree.height_difference(), 0);
+        rb_tree.insert(2);
+        assert_eq!(rb_tree.height_difference(), 1);
+    }
+
+    #[test]
+    fn test_skip_to_lower_bound() {
+        let mut tree = AVLTree::new();
+        (0..100).for_each(|v| {
+            tree.insert(v * 2);
+        });
+
+        // pages of 5 values, each starting after the last value seen
+        let mut iter = tree.iter();
+        let first_page: Vec<u32> = iter.by_ref().take(5).collect();
+        assert_eq!(first_page, vec![0, 2, 4, 6, 8]);
+        iter.skip_to_lower_bound(first_page[4] + 1);
+        let second_page: Vec<u32> = iter.by_ref().take(5).collect();
+        assert_eq!(second_page, vec![10, 12, 14, 16, 18]);
+
+        // values between and beyond the stored ones
+        iter.skip_to_lower_bound(151);
+        assert_eq!(iter.next(), Some(152));
+        iter.skip_to_lower_bound(3);
+        assert_eq!(iter.next(), Some(4));
+        iter.skip_to_lower_bound(199);
+        assert_eq!(iter.next(), None);
+
+        let empty_tree = FastRBTree::<u32>::new();
+        let mut iter = empty_tree.iter();
+        iter.skip_to_lower_bound(1);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_lower_and_upper_bound() {
+        let values = [10, 20, 20, 30, 40];
+        let mut tree = RBTree::new();
+        values.iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let sorted: Vec<u32> = tree.iter().collect();
+        for x in 0..45 {
+            // the same as partition_point on the sorted values
+            let lower = sorted.get(sorted.partition_point(|v| *v < x)).copied();
+            let upper = sorted.get(sorted.partition_point(|v| *v <= x)).copied();
+            assert_eq!(tree.lower_bound(x), lower);
+            assert_eq!(tree.ceiling(x), lower);
+            assert_eq!(tree.upper_bound(x), upper);
+        }
+        assert_eq!(tree.lower_bound(20), Some(20));
+        assert_eq!(tree.upper_bound(20), Some(30));
+        assert_eq!(tree.lower_bound(41), None);
+        assert_eq!(BSTree::<u32>::new().lower_bound(1), None);
+    }
+
+    #[test]
+    fn test_set_sizes() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        use std::collections::BTreeSet;
+
+        let mut rng = StdRng::seed_from_u64(11);
+        for size in [0, 1, 10, 100, 1000] {
+            let mut a = FastRBTree::new();
+            let mut b = FastRBTree::new();
+            let mut avl_a = AVLTree::new();
+            let mut avl_b = AVLTree::new();
+            let mut set_a = BTreeSet::new();
+            let mut set_b = BTreeSet::new();
+            for _ in 0..size {
+                let (x, y) = (rng.gen_range(0, 2 * size), rng.gen_range(0, 2 * size));
+                a.insert(x);
+                avl_a.insert(x);
+                set_a.insert(x);
+                b.insert(y);
+                avl_b.insert(y);
+                set_b.insert(y);
+            }
+            let intersection = set_a.intersection(&set_b).count();
+            let union = set_a.union(&set_b).count();
+            let difference = set_a.difference(&set_b).count();
+            assert_eq!(a.intersection_size(&b), intersection);
+            assert_eq!(a.union_size(&b), union);
+            assert_eq!(a.difference_size(&b), difference);
+            assert_eq!(avl_a.intersection_size(&avl_b), intersection);
+            assert_eq!(avl_a.union_size(&avl_b), union);
+            assert_eq!(avl_a.difference_size(&avl_b), difference);
+            assert_eq!(b.difference_size(&a), set_b.difference(&set_a).count());
+        }
+    }
+
+    #[test]
+    fn test_is_disjoint() {
+        let mut a = FastRBTree::new();
+        let mut b = FastRBTree::new();
+        let empty = FastRBTree::new();
+        assert!(empty.is_disjoint(&empty));
+        [1, 3, 5, 7].iter().for_each(|v| {
+            a.insert(*v);
+        });
+        [2, 4, 6, 8].iter().for_each(|v| {
+            b.insert(*v);
+        });
+        assert!(a.is_disjoint(&b));
+        assert!(b.is_disjoint(&a));
+        assert!(a.is_disjoint(&empty));
+        assert!(empty.is_disjoint(&a));
+        assert!(!a.is_disjoint(&a));
+
+        b.insert(7);
+        assert!(!a.is_disjoint(&b));
+        assert!(!b.is_disjoint(&a));
+
+        let mut avl_a = AVLTree::new();
+        let mut avl_b = AVLTree::new();
+        avl_a.insert(1);
+        avl_b.insert(2);
+        assert!(avl_a.is_disjoint(&avl_b));
+        avl_b.insert(1);
+        assert!(!avl_a.is_disjoint(&avl_b));
+    }
+
+    #[test]
+    #[deny(unused_must_use)]
+    fn test_must_use_results() {
+        let mut tree = FastRBTree::new();
+        tree.insert(1);
+        let found = tree.contains(1);
+        let min = tree.min();
+        let _ = tree.height();
+        assert!(found);
+        assert_eq!(min, Some(1));
+    }
+
+    #[test]
+    fn test_spine_length() {
+        let empty = FastRBTree::<u32>::new();
+        assert_eq!(empty.left_spine_length(), 0);
+        assert_eq!(empty.right_spine_length(), 0);
+
+        // a root with a red left child
+        let mut tree = FastRBTree::new();
+        tree.insert(1);
+        tree.insert(2);
+        assert_eq!(tree.left_spine_length(), 1);
+        assert_eq!(tree.right_spine_length(), 0);
+
+        let mut fast_rb_tree = FastRBTree::new();
+        let mut rb_tree = RBTree::new();
+        let mut avl_tree = AVLTree::new();
+        fast_rb_tree.extend(0..1000);
+        rb_tree.extend(0..1000);
+        avl_tree.extend(0..1000);
+        let spines = vec![
+            (
+                fast_rb_tree.left_spine_length(),
+                fast_rb_tree.right_spine_length(),
+            ),
+            (rb_tree.left_spine_length(), rb_tree.right_spine_length()),
+            (avl_tree.left_spine_length(), avl_tree.right_spine_length()),
+        ];
+        for (left, right) in spines {
+            assert!(left > 0 && right > 0);
+            assert!(left <= 2 * right && right <= 2 * left);
+        }
+    }
+
+    #[test]
+    fn test_min_height() {
+        use rand::Rng;
+
+        assert_eq!(FastRBTree::<u32>::new().min_height(), 0);
+        assert_eq!(AVLTree::<u32>::new().min_height(), 0);
+
+        // perfect trees, the nil leaves count for the red-black tree only
+        let data: Vec<u32> = (0..15).collect();
+        let avl_tree = AVLTree::from_sorted_slice(&data);
+        let fast_rb_tree = FastRBTree::from_sorted_slice(&data);
+        assert_eq!(avl_tree.min_height(), 4);
+        assert_eq!(avl_tree.min_height(), avl_tree.height());
+        assert_eq!(fast_rb_tree.min_height(), 5);
+        assert_eq!(fast_rb_tree.min_height(), fast_rb_tree.height());
+
+        let mut rng = rand::thread_rng();
+        let mut fast_rb_tree = FastRBTree::new();
+        let mut rb_tree = RBTree::new();
+        let mut avl_tree = AVLTree::new();
+        let mut bs_tree = BSTree::new();
+        let mut previous = [0; 4];
+        for _ in 0..500 {
+            let v = rng.gen_range(0, 1000);
+            fast_rb_tree.insert(v);
+            rb_tree.insert(v);
+            avl_tree.insert(v);
+            bs_tree.insert(v);
+            let heights = vec![
+                (fast_rb_tree.min_height(), fast_rb_tree.height()),
+                (rb_tree.min_height(), rb_tree.height()),
+                (avl_tree.min_height(), avl_tree.height()),
+                (bs_tree.min_height(), bs_tree.height()),
+            ];
+            for (i, (min_height, height)) in heights.into_iter().enumerate() {
+                assert!(min_height <= height);
+                assert!(min_height + 1 >= previous[i]);
+                previous[i] = min_height;
+            }
+        }
+    }
+
+    struct CountVisitor(usize);
+
+    impl TreeVisitor<u32> for CountVisitor {
+        fn visit_in(&mut self, _value: u32) {
+            self.0 += 1;
+        }
+    }
+
+    #[derive(Default)]
+    struct OrderVisitor {
+        pre: Vec<u32>,
+        in_order: Vec<u32>,
+        post: Vec<u32>,
+    }
+
+    impl TreeVisitor<u32> for OrderVisitor {
+        fn visit_pre(&mut self, value: u32) {
+            self.pre.push(value);
+        }
+
+        fn visit_in(&mut self, value: u32) {
+            self.in_order.push(value);
+        }
+
+        fn visit_post(&mut self, value: u32) {
+            self.post.push(value);
+        }
+    }
+
+    assert_impl_all!(FastRBTree<u32>: Eq);
+    assert_impl_all!(RBTree<u32>: Eq);
+    assert_impl_all!(AVLTree<u32>: Eq);
+
+    #[test]
+    fn test_eq() {
+        let mut fast_rb_tree = FastRBTree::new();
+        let mut avl_tree = AVLTree::new();
+        (1..=10).for_each(|v| {
+            fast_rb_tree.insert(v);
+            avl_tree.insert(v);
+        });
+        assert_eq!(fast_rb_tree, fast_rb_tree.clone());
+        assert_eq!(avl_tree, avl_tree.clone());
+        let mut other = fast_rb_tree.clone();
+        other.delete(10);
+        assert_ne!(fast_rb_tree, other);
+    }
+
+    #[test]
+    fn test_get_root_value() {
+        let mut fast_rb_tree = FastRBTree::new();
+        let mut rb_tree = RBTree::new();
+        let mut avl_tree = AVLTree::new();
+        assert_eq!(fast_rb_tree.get_root_value(), None);
+        assert_eq!(rb_tree.get_root_value(), None);
+        assert_eq!(avl_tree.get_root_value(), None);
+        for v in vec![5, 3, 8, 1, 4, 7, 9, 2, 6] {
+            fast_rb_tree.insert(v);
+            rb_tree.insert(v);
+            avl_tree.insert(v);
+            assert!(fast_rb_tree.contains(fast_rb_tree.get_root_value().unwrap()));
+            assert!(rb_tree.contains(rb_tree.get_root_value().unwrap()));
+            assert!(avl_tree.contains(avl_tree.get_root_value().unwrap()));
+            assert_eq!(
+                fast_rb_tree.get_root_value(),
+                fast_rb_tree.to_preorder_vec().first().copied()
+            );
+        }
+    }
+
+    #[test]
+    fn test_traversal_vecs() {
+        let mut tree = FastRBTree::new();
+        assert!(tree.to_preorder_vec().is_empty());
+        assert!(tree.to_postorder_vec().is_empty());
+        assert!(tree.to_levelorder_vec().is_empty());
+
+        (1..=7).for_each(|v| tree.insert(v));
+        let mut preorder = Vec::new();
+        tree.pre_order_traversal_for_test(&mut preorder);
+        assert_eq!(tree.to_preorder_vec(), preorder);
+        assert_eq!(preorder[0], tree.to_levelorder_vec()[0]);
+        assert_eq!(preorder[0], *tree.to_postorder_vec().last().unwrap());
+
+        let mut avl_tree = AVLTree::new();
+        (1..=7).for_each(|v| avl_tree.insert(v));
+        assert_eq!(avl_tree.to_preorder_vec(), vec![4, 2, 1, 3, 6, 5, 7]);
+        assert_eq!(avl_tree.to_postorder_vec(), vec![1, 3, 2, 5, 7, 6, 4]);
+        assert_eq!(avl_tree.to_levelorder_vec(), vec![4, 2, 6, 1, 3, 5, 7]);
+    }
+
+    #[test]
+    fn test_print_lines() {
+        let mut tree = AVLTree::new();
+        (1..=3).for_each(|v| tree.insert(v));
+        let root = tree.get_root().unwrap();
+        let full = root.borrow().print_lines(usize::MAX);
+        assert_eq!(full, vec!["   2   ", "  / \\  ", " 1   3 "]);
+
+        // only the root is printed, its children are cut
+        let lines = root.borrow().print_lines(0);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].trim(), "2");
+        assert_eq!(lines[2].matches("...").count(), 2);
+        assert!(!lines.iter().any(|line| line.contains('1')));
+
+        let leaf = tree.get_root().unwrap().borrow().get_left().unwrap();
+        assert_eq!(leaf.borrow().print_lines(0), vec!["1"]);
+
+        // the size of the output depends on max_depth, not on the size of the tree,
+        // 6 levels of nodes take 3 * 2^4 + 1 cells of at most 4 characters
+        let big = FastRBTree::from_sorted_slice(&(0..10000).collect::<Vec<u32>>());
+        let lines = big.get_root().unwrap().borrow().print_lines(4);
+        assert_eq!(lines.len(), 2 * 6 - 1);
+        assert!(lines.iter().all(|line| line.chars().count() <= 49 * 4));
+        assert!(lines[10].contains("..."));
+    }
+
+    #[test]
+    fn test_count_full_nodes() {
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.count_full_nodes(), 0);
+        tree.insert(1);
+        assert_eq!(tree.count_full_nodes(), 0);
+        tree.insert(2);
+        assert_eq!(tree.count_full_nodes(), 0);
+        tree.insert(3);
+        assert_eq!(tree.count_full_nodes(), 1);
+        // a perfect tree of n nodes has (n - 1) / 2 full nodes
+        (4..=15).for_each(|v| tree.insert(v));
+        assert_eq!(tree.count_full_nodes(), 7);
+
+        let mut bs_tree = BSTree::new();
+        (1..=10).for_each(|v| bs_tree.insert(v));
+        assert_eq!(bs_tree.count_full_nodes(), 0);
+
+        let mut avl_tree = AVLTree::new();
+        let mut rb_tree = RBTree::new();
+        let mut fast_rb_tree = FastRBTree::new();
+        for v in (0..500).map(|v| (v * 37) % 500) {
+            avl_tree.insert(v);
+            rb_tree.insert(v);
+            fast_rb_tree.insert(v);
+            for (full, nodes) in [
+                (avl_tree.count_full_nodes(), avl_tree.count_nodes()),
+                (rb_tree.count_full_nodes(), rb_tree.count_nodes()),
+                (fast_rb_tree.count_full_nodes(), fast_rb_tree.count_nodes()),
+            ] {
+                assert!(full * 2 < nodes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_height_histogram() {
+        let mut tree = AVLTree::new();
+        assert_eq!(tree.height_histogram(), vec![]);
+        (1..=15).for_each(|v| tree.insert(v));
+        assert_eq!(tree.height_histogram(), vec![1, 2, 4, 8]);
+
+        let mut avl_tree = AVLTree::new();
+        let mut fast_rb_tree = FastRBTree::new();
+        (0..1000).map(|v| (v * 7919) % 1000).for_each(|v| {
+            avl_tree.insert(v);
+            fast_rb_tree.insert(v);
+        });
+        let histogram = avl_tree.height_histogram();
+        assert_eq!(histogram.iter().sum::<usize>(), avl_tree.count_nodes());
+        assert_eq!(histogram.len() as u32, avl_tree.height());
+        // the levels above the shortest path are full
+        for (depth, count) in histogram
+            .iter()
+            .take(avl_tree.min_height() as usize)
+            .enumerate()
+        {
+            assert_eq!(*count, 1 << depth);
+        }
+
+        // red-black trees count the nil leaves in the height
+        let histogram = fast_rb_tree.height_histogram();
+        assert_eq!(histogram.iter().sum::<usize>(), 1000);
+        assert_eq!(histogram.len() as u32, fast_rb_tree.height() - 1);
+        let full_levels = (fast_rb_tree.min_height() - 1) as usize;
+        for (depth, count) in histogram.iter().take(full_levels).enumerate() {
+            assert_eq!(*count, 1 << depth);
+        }
+    }
+
+    #[test]
+    fn test_walk() {
+        let mut count = CountVisitor(0);
+        FastRBTree::<u32>::new().walk(&mut count);
+        assert_eq!(count.0, 0);
+
+        //       4
+        //     /   \
+        //    2     6
+        //   / \   / \
+        //  1   3 5   7
+        let mut tree = AVLTree::new();
+        [4, 2, 6, 1, 3, 5, 7].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let mut count = CountVisitor(0);
+        tree.walk(&mut count);
+        assert_eq!(count.0, 7);
+        let mut orders = OrderVisitor::default();
+        tree.walk(&mut orders);
+        assert_eq!(orders.pre, vec![4, 2, 1, 3, 6, 5, 7]);
+        assert_eq!(orders.in_order, vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(orders.post, vec![1, 3, 2, 5, 7, 6, 4]);
+
+        let mut fast_rb_tree = FastRBTree::new();
+        fast_rb_tree.extend(0..100);
+        let mut orders = OrderVisitor::default();
+        fast_rb_tree.walk(&mut orders);
+        let mut pre = vec![];
+        fast_rb_tree.pre_order_traversal_for_test(&mut pre);
+        assert_eq!(orders.pre, pre);
+        assert_eq!(orders.in_order, fast_rb_tree.iter().collect::<Vec<_>>());
+        assert_eq!(orders.post.len(), 100);
+        assert_eq!(
+            orders.post.last(),
+            fast_rb_tree
+                .get_root()
+                .map(|root| root.borrow().get_value())
+                .as_ref()
+        );
+    }
+
+    // check both directions of the inverse and the rank of a probe, in or out of the tree
+    fn check_rank_select<TreeNode, Tree>(tree: &Tree, probe: u32) -> Result<(), TestCaseError>
+    where
+        TreeNode: CommonTreeNodeTrait<u32>,
+        Tree: CommonTreeTrait<u32, TreeNode>,
+    {
+        let values: Vec<u32> = tree.iter().collect();
+        for (k, v) in values.iter().enumerate() {
+            prop_assert_eq!(tree.rank(*v), k);
+            prop_assert_eq!(tree.select(tree.rank(*v)), Some(*v));
+            prop_assert_eq!(tree.rank(tree.select(k).unwrap()), k);
+        }
+        prop_assert_eq!(tree.select(values.len()), None);
+
+        let rank = tree.rank(probe);
+        prop_assert_eq!(tree.select(rank), tree.ceiling(probe));
+        match rank {
+            0 => prop_assert_eq!(tree.predecessor(probe), None),
+            _ => prop_assert_eq!(tree.select(rank - 1), tree.predecessor(probe)),
+        }
+        return Ok(());
+    }
+
+    proptest! {
+        #[test]
+        fn test_rank_select_inverse(
+            values in prop::collection::vec(0u32..1000, 0..200),
+            probe in 0u32..1000,
+        ) {
+            let mut fast_rb_tree = FastRBTree::new();
+            let mut avl_tree = AVLTree::new();
+            fast_rb_tree.extend(values.iter().copied());
+            avl_tree.extend(values.iter().copied());
+            check_rank_select(&fast_rb_tree, probe)?;
+            check_rank_select(&avl_tree, probe)?;
+        }
+
+        #[test]
+        fn test_to_sorted_vec(values in prop::collection::vec(0u32..1000, 0..200)) {
+            let mut fast_rb_tree = FastRBTree::new();
+            let mut avl_tree = AVLTree::new();
+            fast_rb_tree.extend(values.iter().copied());
+            avl_tree.extend(values.iter().copied());
+            let mut expected = values.clone();
+            expected.sort_unstable();
+            expected.dedup();
+            prop_assert_eq!(fast_rb_tree.to_sorted_vec(), expected.clone());
+            prop_assert_eq!(avl_tree.to_sorted_vec(), expected);
+        }
+    }
+}
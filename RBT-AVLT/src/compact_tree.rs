@@ -0,0 +1,332 @@
+//! Array-backed search tree
+//!
+//! [CompactTree](struct.CompactTree.html) keeps its values in a single `Vec` laid out in
+//! breadth-first order: the root is at index 0 and the children of index `i` are at
+//! `2 * i + 1` and `2 * i + 2`. There are no pointers and no per-node allocation, and
+//! the first levels of every search share the same few cache lines, which makes
+//! lookups fast for read-heavy workloads. The price is that `insert` and `delete`
+//! rebuild the whole array in O(n).
+
+use std::fmt::{Debug, Display};
+
+/// A balanced search tree stored in a flat `Vec` in breadth-first order
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompactTree<T: Ord + Copy + Debug + Display> {
+    data: Vec<T>,
+}
+
+impl<T: Ord + Copy + Debug + Display> Default for CompactTree<T> {
+    fn default() -> Self {
+        return CompactTree::new();
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> CompactTree<T> {
+    /// Create an empty tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let tree: CompactTree<u32> = CompactTree::new();
+    /// ```
+    pub fn new() -> Self {
+        return CompactTree { data: vec![] };
+    }
+
+    /// Build a tree from a sorted slice in O(n), duplicated values are kept once
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let tree = CompactTree::from_sorted_slice(&[1, 2, 2, 3]);
+    /// assert_eq!(3, tree.len());
+    /// ```
+    pub fn from_sorted_slice(data: &[T]) -> Self {
+        let mut sorted = data.to_vec();
+        sorted.dedup();
+        let mut tree = CompactTree {
+            data: sorted.clone(),
+        };
+        let mut next = 0;
+        tree.fill(&sorted, 0, &mut next);
+        return tree;
+    }
+
+    // Helper function for from_sorted_slice, visit the positions in order
+    // and give each the next sorted value
+    fn fill(&mut self, sorted: &[T], index: usize, next: &mut usize) {
+        if index >= sorted.len() {
+            return;
+        }
+        self.fill(sorted, 2 * index + 1, next);
+        self.data[index] = sorted[*next];
+        *next += 1;
+        self.fill(sorted, 2 * index + 2, next);
+    }
+
+    /// Return the number of values in the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let tree = CompactTree::from_sorted_slice(&[1, 2, 3]);
+    /// assert_eq!(3, tree.len());
+    /// ```
+    #[must_use = "calling len without using the result has no effect"]
+    pub fn len(&self) -> usize {
+        return self.data.len();
+    }
+
+    /// Determine whether the tree has no values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let tree: CompactTree<u32> = CompactTree::new();
+    /// assert_eq!(true, tree.is_empty());
+    /// ```
+    #[must_use = "calling is_empty without using the result has no effect"]
+    pub fn is_empty(&self) -> bool {
+        return self.data.is_empty();
+    }
+
+    /// Determine whether the tree contains the value, in O(log n)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let tree = CompactTree::from_sorted_slice(&[1, 2, 3]);
+    /// assert_eq!(true, tree.contains(2));
+    /// assert_eq!(false, tree.contains(4));
+    /// ```
+    #[must_use = "calling contains without using the result has no effect"]
+    pub fn contains(&self, value: T) -> bool {
+        let mut index = 0;
+        while index < self.data.len() {
+            if value == self.data[index] {
+                return true;
+            } else if value < self.data[index] {
+                index = 2 * index + 1;
+            } else {
+                index = 2 * index + 2;
+            }
+        }
+        return false;
+    }
+
+    /// Return the smallest value of the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let tree = CompactTree::from_sorted_slice(&[1, 2, 3]);
+    /// assert_eq!(Some(1), tree.min());
+    /// ```
+    #[must_use = "calling min without using the result has no effect"]
+    pub fn min(&self) -> Option<T> {
+        let mut index = 0;
+        while 2 * index + 1 < self.data.len() {
+            index = 2 * index + 1;
+        }
+        return self.data.get(index).copied();
+    }
+
+    /// Return the largest value of the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let tree = CompactTree::from_sorted_slice(&[1, 2, 3]);
+    /// assert_eq!(Some(3), tree.max());
+    /// ```
+    #[must_use = "calling max without using the result has no effect"]
+    pub fn max(&self) -> Option<T> {
+        let mut index = 0;
+        while 2 * index + 2 < self.data.len() {
+            index = 2 * index + 2;
+        }
+        return self.data.get(index).copied();
+    }
+
+    /// Return the values between `low` and `high` inclusive in ascending order,
+    /// only the subtrees which may hold such values are visited
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let tree = CompactTree::from_sorted_slice(&[1, 2, 3, 4, 5]);
+    /// assert_eq!(vec![2, 3, 4], tree.range(2, 4));
+    /// ```
+    #[must_use = "calling range without using the result has no effect"]
+    pub fn range(&self, low: T, high: T) -> Vec<T> {
+        let mut container = vec![];
+        self.range_from(0, low, high, &mut container);
+        return container;
+    }
+
+    // Helper function for range
+    fn range_from(&self, index: usize, low: T, high: T, container: &mut Vec<T>) {
+        if index >= self.data.len() {
+            return;
+        }
+        let value = self.data[index];
+        if low < value {
+            self.range_from(2 * index + 1, low, high, container);
+        }
+        if low <= value && value <= high {
+            container.push(value);
+        }
+        if value < high {
+            self.range_from(2 * index + 2, low, high, container);
+        }
+    }
+
+    /// Return all values in ascending order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let tree = CompactTree::from_sorted_slice(&[1, 2, 3]);
+    /// assert_eq!(vec![1, 2, 3], tree.to_vec());
+    /// ```
+    #[must_use = "calling to_vec without using the result has no effect"]
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut container = Vec::with_capacity(self.data.len());
+        self.in_order(0, &mut container);
+        return container;
+    }
+
+    // Helper function for to_vec
+    fn in_order(&self, index: usize, container: &mut Vec<T>) {
+        if index >= self.data.len() {
+            return;
+        }
+        self.in_order(2 * index + 1, container);
+        container.push(self.data[index]);
+        self.in_order(2 * index + 2, container);
+    }
+
+    /// Insert a value and rebuild the tree in O(n), an equal value already in the tree
+    /// is replaced by the new one, as [FastRBTree.insert](../rb_tree_fast/struct.FastRBTree.html#method.insert) does
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let mut tree = CompactTree::new();
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// assert_eq!(vec![1, 2], tree.to_vec());
+    /// ```
+    pub fn insert(&mut self, value: T) {
+        let mut sorted = self.to_vec();
+        match sorted.binary_search(&value) {
+            Ok(position) => sorted[position] = value,
+            Err(position) => sorted.insert(position, value),
+        }
+        *self = CompactTree::from_sorted_slice(&sorted);
+    }
+
+    /// Delete a value and rebuild the tree in O(n)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::compact_tree::CompactTree;
+    /// let mut tree = CompactTree::from_sorted_slice(&[1, 2, 3]);
+    /// tree.delete(2);
+    /// assert_eq!(vec![1, 3], tree.to_vec());
+    /// ```
+    pub fn delete(&mut self, value: T) {
+        let mut sorted = self.to_vec();
+        if let Ok(position) = sorted.binary_search(&value) {
+            sorted.remove(position);
+            *self = CompactTree::from_sorted_slice(&sorted);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_compact_tree() {
+        let empty: CompactTree<u32> = CompactTree::new();
+        assert!(empty.is_empty());
+        assert!(!empty.contains(1));
+        assert_eq!(empty.min(), None);
+        assert_eq!(empty.max(), None);
+        assert_eq!(empty.range(0, 10), vec![]);
+
+        // every size up to a few full levels gives the same answers as FastRBTree
+        for size in 1..40 {
+            let data: Vec<u32> = (0..size).map(|v| v * 2).collect();
+            let tree = CompactTree::from_sorted_slice(&data);
+            let fast_rb_tree = FastRBTree::from_sorted_slice(&data);
+            assert_eq!(tree.len(), data.len());
+            assert_eq!(tree.to_vec(), data);
+            assert_eq!(tree.min(), fast_rb_tree.min());
+            assert_eq!(tree.max(), fast_rb_tree.max());
+            for v in 0..size * 2 + 1 {
+                assert_eq!(tree.contains(v), fast_rb_tree.contains(v));
+            }
+            assert_eq!(tree.range(3, 11), fast_rb_tree.range(3, 11));
+        }
+    }
+
+    #[test]
+    fn test_insert_and_delete() {
+        let mut tree = CompactTree::new();
+        [5, 3, 8, 1, 4, 3].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        assert_eq!(tree.to_vec(), vec![1, 3, 4, 5, 8]);
+        tree.delete(3);
+        tree.delete(7);
+        assert_eq!(tree.to_vec(), vec![1, 4, 5, 8]);
+        assert!(!tree.contains(3));
+        assert!(tree.contains(4));
+        assert_eq!(
+            CompactTree::from_sorted_slice(&[1, 1, 2]).to_vec(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_insert_equal_value() {
+        use crate::utils::test_item::Item;
+
+        let mut tree = CompactTree::new();
+        [
+            Item {
+                key: 1,
+                payload: 10,
+            },
+            Item {
+                key: 2,
+                payload: 20,
+            },
+            Item {
+                key: 1,
+                payload: 11,
+            },
+        ]
+        .iter()
+        .for_each(|v| tree.insert(*v));
+        let values = tree.to_vec();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].payload, 11);
+        assert_eq!(tree.len(), 2);
+    }
+}
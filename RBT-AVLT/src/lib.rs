@@ -1,6 +1,93 @@
-pub mod avlTree;
-pub mod bsTree;
-pub mod commonTrait;
+//! Binary search trees, red-black trees and AVL trees
+//!
+//! Every tree implements [CommonTreeTrait](common_trait/trait.CommonTreeTrait.html),
+//! and its `iter` yields the values lazily in ascending order, so it composes with
+//! the standard iterator adapters. Because the values are sorted, adapters such as
+//! `take_while` stop as soon as the predicate fails.
+//!
+//! # Example
+//!
+//! ```
+//! use tree_collections::prelude::*;
+//!
+//! let mut tree = FastRBTree::new();
+//! (1..=100).for_each(|v| tree.insert(v));
+//! let below_five: Vec<u32> = tree.iter().take_while(|&v| v < 5).collect();
+//! assert_eq!(vec![1, 2, 3, 4], below_five);
+//! assert_eq!(below_five, tree.range(u32::MIN, 4));
+//! let even_sum: u32 = tree.iter().filter(|v| v % 2 == 0).sum();
+//! assert_eq!(2550, even_sum);
+//! ```
+
+// The crate keeps the explicit `return` style used throughout its modules.
+#![allow(clippy::needless_return)]
+
+pub mod algorithms;
+pub mod avl_tree;
+pub mod bs_tree;
+pub mod common_trait;
+pub mod compact_tree;
 pub mod prelude;
-pub mod rbTree;
-pub mod rbTreeFast;
+pub mod rb_tree;
+pub mod rb_tree_arena;
+pub mod rb_tree_augmented;
+pub mod rb_tree_box;
+pub mod rb_tree_fast;
+pub mod rb_tree_set;
+pub mod shared_node;
+pub mod tree_enum;
+pub mod utils;
+
+// The released modules used to have camelCase names, the old paths still work for one
+// release but warn so that users can move to the snake_case ones.
+
+/// Former name of [avl_tree](avl_tree/index.html)
+#[allow(non_snake_case)]
+#[deprecated(since = "0.1.9", note = "renamed to `avl_tree`")]
+pub mod avlTree {
+    pub use crate::avl_tree::*;
+}
+
+/// Former name of [bs_tree](bs_tree/index.html)
+#[allow(non_snake_case)]
+#[deprecated(since = "0.1.9", note = "renamed to `bs_tree`")]
+pub mod bsTree {
+    pub use crate::bs_tree::*;
+}
+
+/// Former name of [common_trait](common_trait/index.html)
+#[allow(non_snake_case)]
+#[deprecated(since = "0.1.9", note = "renamed to `common_trait`")]
+pub mod commonTrait {
+    pub use crate::common_trait::*;
+}
+
+/// Former name of [rb_tree](rb_tree/index.html)
+#[allow(non_snake_case)]
+#[deprecated(since = "0.1.9", note = "renamed to `rb_tree`")]
+pub mod rbTree {
+    pub use crate::rb_tree::*;
+}
+
+/// Former name of [rb_tree_fast](rb_tree_fast/index.html)
+#[allow(non_snake_case)]
+#[deprecated(since = "0.1.9", note = "renamed to `rb_tree_fast`")]
+pub mod rbTreeFast {
+    pub use crate::rb_tree_fast::*;
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_module_names() {
+        use crate::commonTrait::CommonTreeTrait;
+
+        let mut tree: crate::rbTreeFast::FastRBTree<u32> = crate::rb_tree_fast::FastRBTree::new();
+        tree.insert(1);
+        assert!(tree.contains(1));
+        let avl_tree: crate::avl_tree::AVLTree<u32> =
+            crate::avlTree::AVLTree::from_sorted_slice(&[1]);
+        assert_eq!(avl_tree.min(), tree.min());
+    }
+}
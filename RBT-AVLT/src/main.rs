@@ -67,7 +67,7 @@ pub fn avl_promote() {
                 tree.pre_order_traversal();
             }
 
-            "empty" => println!("Is the tree empty?: {:?}", tree.is_tree_empty()),
+            "empty" => println!("Is the tree empty?: {:?}", tree.is_empty()),
 
             "search" => {
                 let val = get_val("search");
@@ -123,7 +123,7 @@ fn rbt_promote() {
                 tree.pre_order_traversal();
             }
 
-            "empty" => println!("Is the tree empty?: {:?}", tree.is_tree_empty()),
+            "empty" => println!("Is the tree empty?: {:?}", tree.is_empty()),
 
             "search" => {
                 let val = get_val("search");
@@ -179,7 +179,7 @@ fn rbt_fast_promote() {
                 tree.pre_order_traversal();
             }
 
-            "empty" => println!("Is the tree empty?: {:?}", tree.is_tree_empty()),
+            "empty" => println!("Is the tree empty?: {:?}", tree.is_empty()),
 
             "search" => {
                 let val = get_val("search");
@@ -214,7 +214,7 @@ fn main() {
             rbt_fast_promote();
         }
         _ => {
-            eprint!("Command not recognized. \n");
+            eprintln!("Command not recognized. ");
         }
     }
 }
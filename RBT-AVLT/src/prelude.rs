@@ -1,5 +1,85 @@
-pub use crate::avlTree::AVLTree;
-pub use crate::bsTree::BSTree;
-pub use crate::commonTrait::{CommonTreeNodeTrait, CommonTreeTrait};
-pub use crate::rbTree::RBTree;
-pub use crate::rbTreeFast::FastRBTree;
+//! Commonly used types
+//!
+//! The prelude re-exports the tree structures and the traits needed to call
+//! their shared methods, so a single glob import is enough to get started:
+//!
+//! - [AVLTree](../avl_tree/struct.AVLTree.html), [RBTree](../rb_tree/struct.RBTree.html),
+//!   [FastRBTree](../rb_tree_fast/struct.FastRBTree.html) and
+//!   [BSTree](../bs_tree/struct.BSTree.html), the tree structures themselves.
+//! - [ArenaRBTree](../rb_tree_arena/struct.ArenaRBTree.html), a red-black tree whose nodes
+//!   are stored in a `Vec` and linked by index.
+//! - [AugmentedRBTree](../rb_tree_augmented/struct.AugmentedRBTree.html), a red-black tree
+//!   whose nodes also point to their parents.
+//! - [BoxRBTree](../rb_tree_box/struct.BoxRBTree.html), a red-black tree whose nodes are
+//!   owned through `Box`.
+//! - [RBTreeSet](../rb_tree_set/struct.RBTreeSet.html), a set with the method names of `BTreeSet`.
+//! - [CompactTree](../compact_tree/struct.CompactTree.html), a read-mostly tree stored in a flat `Vec`.
+//! - [Tree](../tree_enum/enum.Tree.html), one of the balanced trees chosen at runtime.
+//! - [NodeColor](../rb_tree_fast/enum.NodeColor.html), the node color of
+//!   [FastRBTree](../rb_tree_fast/struct.FastRBTree.html).
+//! - [CommonTreeTrait](../common_trait/trait.CommonTreeTrait.html) and
+//!   [CommonTreeNodeTrait](../common_trait/trait.CommonTreeNodeTrait.html), which provide
+//!   `contains`, `height`, `min`, `max`, the traversals and the other shared methods.
+//!
+//! Node structs, error types, iterators and other helper types are not part of the
+//! prelude; import them from their modules.
+//!
+//! # Example
+//!
+//! ```
+//! use tree_collections::prelude::*;
+//!
+//! let mut avl_tree = AVLTree::new();
+//! let mut rb_tree = RBTree::new();
+//! let mut fast_rb_tree = FastRBTree::new();
+//! for v in 1..=5 {
+//!     avl_tree.insert(v);
+//!     rb_tree.insert(v);
+//!     fast_rb_tree.insert(v);
+//! }
+//! assert!(avl_tree.contains(3));
+//! assert!(rb_tree.contains(3));
+//! assert!(fast_rb_tree.contains(3));
+//! ```
+
+pub use crate::avl_tree::AVLTree;
+pub use crate::bs_tree::BSTree;
+pub use crate::common_trait::{CommonTreeNodeTrait, CommonTreeTrait};
+pub use crate::compact_tree::CompactTree;
+pub use crate::rb_tree::RBTree;
+pub use crate::rb_tree_arena::ArenaRBTree;
+pub use crate::rb_tree_augmented::AugmentedRBTree;
+pub use crate::rb_tree_box::BoxRBTree;
+pub use crate::rb_tree_fast::{FastRBTree, NodeColor};
+pub use crate::rb_tree_set::RBTreeSet;
+pub use crate::tree_enum::Tree;
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_prelude_trees() {
+        let mut avl_tree = AVLTree::new();
+        let mut rb_tree = RBTree::new();
+        let mut fast_rb_tree = FastRBTree::new();
+        [5, 3, 8, 1, 4].iter().for_each(|v| {
+            avl_tree.insert(*v);
+            rb_tree.insert(*v);
+            fast_rb_tree.insert(*v);
+        });
+        avl_tree.delete(3);
+        rb_tree.delete(3);
+        fast_rb_tree.delete(8);
+
+        let mut avl_container = vec![];
+        let mut rb_container = vec![];
+        let mut fast_rb_container = vec![];
+        avl_tree.in_order_traversal_for_test(&mut avl_container);
+        rb_tree.in_order_traversal_for_test(&mut rb_container);
+        fast_rb_tree.in_order_traversal_for_test(&mut fast_rb_container);
+        assert_eq!(avl_container, vec![1, 4, 5, 8]);
+        assert_eq!(rb_container, vec![1, 4, 5, 8]);
+        assert_eq!(fast_rb_container, vec![1, 3, 4, 5]);
+    }
+}
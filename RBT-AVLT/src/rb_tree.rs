@@ -1,13 +1,42 @@
 //! Red-black tree
 //!
 //! You can generate a red-black tree, and insert or delete nodes.
+//!
+//! This is the classic red-black tree, where every node keeps a pointer to its
+//! parent. New nodes are red, and an insert or a delete is followed by recoloring
+//! and at most three rotations, so that no red node has a red child and every path
+//! from the root to a leaf has the same number of black nodes. The longest path is
+//! then at most twice as long as the shortest one. See
+//! [FastRBTree](../rb_tree_fast/struct.FastRBTree.html) for a left-leaning variant
+//! with simpler code.
+//!
+//! | Operation                       | Time complexity |
+//! |---------------------------------|-----------------|
+//! | `insert`, `delete`, `contains`  | O(log n)        |
+//! | `min`, `max`                    | O(log n)        |
+//! | `iter` (whole tree)             | O(n)            |
+//! | `height`, `count_leaves`        | O(n)            |
+//!
+//! # Example
+//!
+//! ```
+//! use tree_collections::prelude::*;
+//!
+//! let mut tree = RBTree::new();
+//! (1..=7).for_each(|v| tree.insert(v));
+//! assert_eq!(true, tree.is_valid_red_black_tree());
+//! tree.delete(4);
+//! assert_eq!(false, tree.contains(4));
+//! assert_eq!(Some(1), tree.min());
+//! assert_eq!(vec![1, 2, 3, 5, 6, 7], tree.iter().collect::<Vec<_>>());
+//! ```
 
-use std::cell::RefCell;
 use std::cmp::max;
-use std::fmt::{Debug, Display};
-use std::rc::Rc;
+use std::fmt::{self, Debug, Display};
 
-pub use crate::commonTrait::{CommonTreeNodeTrait, CommonTreeTrait};
+pub use crate::common_trait::{CommonTreeNodeTrait, CommonTreeTrait};
+use crate::common_trait::{DebugSubtree, InOrderIter, DEBUG_DEPTH};
+use crate::shared_node::{NodeCell, Shared};
 
 /// Color representation for the [TreeNode](struct.TreeNode.html)
 /// of [RBTree](struct.RBTree.html) struct
@@ -26,7 +55,7 @@ enum NodeDirection {
 }
 
 /// Structure of RBTree
-#[derive(Clone, Debug, PartialEq)]
+#[derive(PartialEq)]
 pub struct RBTree<T: Ord + Copy + Debug + Display> {
     root: OptionRBTreeNode<T>,
 }
@@ -43,12 +72,12 @@ pub struct TreeNode<T: Ord + Copy + Debug + Display> {
     right: OptionRBTreeNode<T>,
 }
 
-type RBTreeNode<T> = Rc<RefCell<TreeNode<T>>>;
+type RBTreeNode<T> = Shared<NodeCell<TreeNode<T>>>;
 type OptionRBTreeNode<T> = Option<RBTreeNode<T>>;
 
 /// Implementations of NodeColor to print
 impl NodeColor {
-    fn to_string(&self) -> &str {
+    fn to_short_string(&self) -> &str {
         if self == &NodeColor::Red {
             "r"
         } else {
@@ -57,12 +86,33 @@ impl NodeColor {
     }
 }
 
+impl fmt::Display for NodeColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeColor::Red => write!(f, "Red"),
+            NodeColor::Black => write!(f, "Black"),
+        }
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> fmt::Display for TreeNode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({})", self.value, self.color)
+    }
+}
+
 // extend from common tree trait
 impl<T: Ord + Copy + Debug + Display> CommonTreeTrait<T, TreeNode<T>> for RBTree<T> {
     fn get_root(&self) -> OptionRBTreeNode<T> {
         return self.root.clone();
     }
 
+    // @Override
+    // check the binary search tree property and the red-black tree properties
+    fn is_valid_red_black_tree(&self) -> bool {
+        return RBTree::is_valid_subtree(self.root.clone());
+    }
+
     // @Override
     fn height(&self) -> u32 {
         match self.get_root() {
@@ -72,6 +122,46 @@ impl<T: Ord + Copy + Debug + Display> CommonTreeTrait<T, TreeNode<T>> for RBTree
     }
 }
 
+/// Iterate over the values of a borrowed tree in ascending order
+impl<'a, T: Ord + Copy + Debug + Display> IntoIterator for &'a RBTree<T> {
+    type Item = T;
+    type IntoIter = InOrderIter<'a, T, TreeNode<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+/// Copy every node, so that the copy and the original can change independently
+impl<T: Ord + Copy + Debug + Display> Clone for RBTree<T> {
+    fn clone(&self) -> Self {
+        return RBTree {
+            root: TreeNode::deep_clone(&self.root, None),
+        };
+    }
+}
+
+/// Trees are equal when they have the same shape and values,
+/// which is an equivalence relation since `T: Ord` implies `T: Eq`
+impl<T: Ord + Copy + Debug + Display> Eq for RBTree<T> {}
+
+/// Print the structure of the tree, nodes deeper than five levels are shown as `...`
+impl<T: Ord + Copy + Debug + Display> Debug for RBTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let root: DebugSubtree<T, TreeNode<T>> = DebugSubtree::new(self.root.clone(), DEBUG_DEPTH);
+        return f.debug_struct("RBTree").field("root", &root).finish();
+    }
+}
+
+/// Insert every value of an iterator, values already in the tree are kept once
+impl<T: Ord + Copy + Debug + Display> Extend<T> for RBTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for v in iter {
+            self.insert(v);
+        }
+    }
+}
+
 // extend from common tree node trait
 impl<T: Ord + Copy + Debug + Display> CommonTreeNodeTrait<T> for TreeNode<T> {
     fn get_left(&self) -> OptionRBTreeNode<T> {
@@ -88,8 +178,8 @@ impl<T: Ord + Copy + Debug + Display> CommonTreeNodeTrait<T> for TreeNode<T> {
 
     fn get_value_to_print(&self) -> String {
         let value = self.value.to_string();
-        let color = self.color.to_string();
-        return value + &color;
+        let color = self.color.to_short_string();
+        return value + color;
     }
 
     // @Override
@@ -107,22 +197,32 @@ impl<T: Ord + Copy + Debug + Display> CommonTreeNodeTrait<T> for TreeNode<T> {
         return max(left_height, right_height) + 1;
     }
 
+    // @override
+    fn get_nil_height(&self) -> u32 {
+        return 1;
+    }
+
     // @override
     fn count_leaves(&self) -> u32 {
         let left = self.get_left();
         let right = self.get_right();
-        if left.is_none() && right.is_none() {
-            return 2;
-        } else if left.is_none() && right.is_some() {
-            return 1 + right.unwrap().borrow().count_leaves();
-        } else if left.is_some() && right.is_none() {
-            return 1 + left.unwrap().borrow().count_leaves();
-        } else {
-            return right.unwrap().borrow().count_leaves() + left.unwrap().borrow().count_leaves();
+        match (left, right) {
+            (None, None) => return 2,
+            (None, Some(right)) => return 1 + right.borrow().count_leaves(),
+            (Some(left), None) => return 1 + left.borrow().count_leaves(),
+            (Some(left), Some(right)) => {
+                return right.borrow().count_leaves() + left.borrow().count_leaves();
+            }
         }
     }
 }
 
+impl<T: Ord + Copy + Debug + Display> Default for RBTree<T> {
+    fn default() -> Self {
+        return RBTree::new();
+    }
+}
+
 /// Implementations of RBTree
 // RBTree
 impl<T: Ord + Copy + Debug + Display> RBTree<T> {
@@ -131,7 +231,7 @@ impl<T: Ord + Copy + Debug + Display> RBTree<T> {
     /// # Example
     ///
     /// ```
-    /// use tree_collections::rbTree::RBTree;
+    /// use tree_collections::rb_tree::RBTree;
     /// let mut tree = RBTree::<u32>::new();
     /// ```
     pub fn new() -> Self {
@@ -140,21 +240,87 @@ impl<T: Ord + Copy + Debug + Display> RBTree<T> {
 
     /// Insert a new value to the red-black Tree
     ///
+    /// If the tree already holds a value which compares equal to the new one,
+    /// the stored value is replaced by the new one, so for a type whose `Ord`
+    /// only looks at part of its fields the other fields are updated.
+    /// Use [insert_if_absent](struct.RBTree.html#method.insert_if_absent)
+    /// to keep the stored value instead.
+    ///
     /// # Example
     ///
     /// ```
-    /// use tree_collections::rbTree::RBTree;
+    /// use tree_collections::rb_tree::RBTree;
     /// let mut tree = RBTree::new();
     /// tree.insert(1);
     /// ```
     pub fn insert(&mut self, insert_value: T) {
         let root = self.root.clone();
         self.root = match root {
-            None => Some(TreeNode::set_black(Rc::new(RefCell::new(TreeNode::new(
-                insert_value,
-            ))))),
+            None => Some(TreeNode::set_black(Shared::new(NodeCell::new(
+                TreeNode::new(insert_value),
+            )))),
             Some(root) => TreeNode::node_insert(root, insert_value),
+        };
+    }
+
+    /// Insert a new value only if no equal value is in the tree,
+    /// return true if the value was inserted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// assert_eq!(true, tree.insert_if_absent(1));
+    /// assert_eq!(false, tree.insert_if_absent(1));
+    /// ```
+    pub fn insert_if_absent(&mut self, insert_value: T) -> bool {
+        if self.contains(insert_value) {
+            return false;
         }
+        self.insert(insert_value);
+        return true;
+    }
+
+    /// Insert every value which is not in the tree yet, return how many were inserted
+    ///
+    /// Values already in the tree, or repeated in `iter`, are skipped like
+    /// [insert_if_absent](struct.RBTree.html#method.insert_if_absent) does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree::RBTree;
+    /// let mut tree = RBTree::new();
+    /// assert_eq!(3, tree.insert_many_unique(vec![1, 2, 3, 2, 1]));
+    /// assert_eq!(1, tree.insert_many_unique(vec![3, 4]));
+    /// ```
+    pub fn insert_many_unique<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        return iter
+            .into_iter()
+            .filter(|value| self.insert_if_absent(*value))
+            .count();
+    }
+
+    /// Return the stored value equal to `value`, or insert the value computed by `f`
+    /// and return it if there is none. `f` is only called when the value is missing,
+    /// and the value it returns is expected to compare equal to `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = RBTree::new();
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || 1));
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || unreachable!()));
+    /// ```
+    pub fn entry_or_insert_with<F: FnOnce() -> T>(&mut self, value: T, f: F) -> T {
+        if let Some(existing) = self.get(value) {
+            return existing;
+        }
+        let inserted = f();
+        self.insert(inserted);
+        return inserted;
     }
 
     /// Delete a value from the red-black Tree
@@ -162,7 +328,7 @@ impl<T: Ord + Copy + Debug + Display> RBTree<T> {
     /// # Example
     ///
     /// ```
-    /// use tree_collections::rbTree::RBTree;
+    /// use tree_collections::rb_tree::RBTree;
     /// let mut tree = RBTree::new();
     /// tree.delete(1);
     /// ```
@@ -177,39 +343,110 @@ impl<T: Ord + Copy + Debug + Display> RBTree<T> {
         }
     }
 
+    // Helper function for the tests, check after insert and delete that the
+    // values are ordered and that every child points back to its parent
+    #[cfg(test)]
+    fn verify_parent_child_consistency(&self) {
+        if let Some(root) = &self.root {
+            assert!(
+                root.borrow().parent.is_none(),
+                "root {} has a parent",
+                root.borrow().value
+            );
+            TreeNode::verify_parent_child_consistency(root);
+        }
+    }
+
     pub fn pre_order_traverse(&self, node: RBTreeNode<T>, container: &mut Vec<T>) {
         container.push(node.borrow().value);
         let left = node.borrow().left.clone();
-        if left.is_some() {
-            self.pre_order_traverse(left.unwrap(), container);
+        if let Some(left) = left {
+            self.pre_order_traverse(left, container);
         }
         let right = node.borrow().right.clone();
-        if right.is_some() {
-            self.pre_order_traverse(right.unwrap(), container);
+        if let Some(right) = right {
+            self.pre_order_traverse(right, container);
         }
     }
 
-    /// Return true if the tree match propertity 5
-    /// Propertity 5. Every path from any node to all of its descendent Nil nodes
-    /// has the same number of black nodes.
+    /// Judge if the tree under `root` is a valid red-black tree, replaced by
+    /// [is_valid_subtree](struct.RBTree.html#method.is_valid_subtree) so that it no longer
+    /// shares its name with the trait method
+    #[deprecated(since = "0.1.9", note = "use is_valid_subtree instead")]
+    #[must_use = "calling is_valid_red_black_tree without using the result has no effect"]
     pub fn is_valid_red_black_tree(root: OptionRBTreeNode<T>) -> bool {
-        let result = TreeNode::calculate_black_height(root);
-        match result {
-            Some(_) => true,
-            None => false,
-        }
+        return RBTree::is_valid_subtree(root);
+    }
+
+    /// Return true if the tree under `root` keeps the binary search tree property
+    /// and the red-black tree properties, the same check as
+    /// [is_valid_red_black_tree](../common_trait/trait.CommonTreeTrait.html#method.is_valid_red_black_tree)
+    /// on a whole RBTree
+    #[must_use = "calling is_valid_subtree without using the result has no effect"]
+    pub fn is_valid_subtree(root: OptionRBTreeNode<T>) -> bool {
+        let is_bst = match &root {
+            None => true,
+            Some(node) => node.borrow().is_valid_bst(None, None),
+        };
+        return is_bst
+            && TreeNode::get_color(root.clone()) == NodeColor::Black
+            && !TreeNode::has_red_violation(root.clone())
+            && TreeNode::calculate_black_height(root).is_some();
     }
 }
 
 /// Implementations of TreeNode
 // TreeNode
 impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
+    // Helper function for RBTree::verify_parent_child_consistency
+    #[cfg(test)]
+    fn verify_parent_child_consistency(node: &RBTreeNode<T>) {
+        let current = node.borrow();
+        for (child, is_left) in [(&current.left, true), (&current.right, false)] {
+            if let Some(child) = child {
+                let child_value = child.borrow().value;
+                assert!(
+                    (child_value < current.value) == is_left && child_value != current.value,
+                    "child {} is on the wrong side of {}",
+                    child_value,
+                    current.value
+                );
+                let parent_matches = match &child.borrow().parent {
+                    Some(parent) => Shared::ptr_eq(parent, node),
+                    None => false,
+                };
+                assert!(
+                    parent_matches,
+                    "child {} does not point back to {}",
+                    child_value, current.value
+                );
+                Self::verify_parent_child_consistency(child);
+            }
+        }
+    }
+
+    // Helper function for Clone, copy every node of the subtree and
+    // point the copied root to the given parent
+    fn deep_clone(node: &OptionRBTreeNode<T>, parent: OptionRBTreeNode<T>) -> OptionRBTreeNode<T> {
+        let node = node.as_ref()?.borrow();
+        let copy = Shared::new(NodeCell::new(TreeNode {
+            color: node.color.clone(),
+            value: node.value,
+            parent,
+            left: None,
+            right: None,
+        }));
+        copy.borrow_mut().left = Self::deep_clone(&node.left, Some(copy.clone()));
+        copy.borrow_mut().right = Self::deep_clone(&node.right, Some(copy.clone()));
+        return Some(copy);
+    }
+
     /// Create a new node
     /// ,which will be called by [RBTree](struct.RBTree.html)
     fn new(value: T) -> Self {
         TreeNode {
             color: NodeColor::Red,
-            value: value,
+            value,
             parent: None,
             left: None,
             right: None,
@@ -220,19 +457,20 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
     fn new_with_parent(value: T, parent: OptionRBTreeNode<T>) -> Self {
         TreeNode {
             color: NodeColor::Red,
-            value: value,
-            parent: parent,
+            value,
+            parent,
             left: None,
             right: None,
         }
     }
 
     // For testing
+    #[allow(dead_code)]
     fn new_black_with_parent(value: T, parent: OptionRBTreeNode<T>) -> Self {
         TreeNode {
             color: NodeColor::Black,
-            value: value,
-            parent: parent,
+            value,
+            parent,
             left: None,
             right: None,
         }
@@ -244,6 +482,8 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
     /// [RBTree.insert](struct.RBTree.html#method.insert)
     fn node_insert(node: RBTreeNode<T>, insert_value: T) -> OptionRBTreeNode<T> {
         if node.borrow().value == insert_value {
+            // equal, update value
+            node.borrow_mut().value = insert_value;
             return Some(node);
         } else if node.borrow().value > insert_value {
             let left = node.borrow().left.clone();
@@ -252,7 +492,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
                     Self::node_insert(left, insert_value);
                 }
                 None => {
-                    node.borrow_mut().left = Some(Rc::new(RefCell::new(
+                    node.borrow_mut().left = Some(Shared::new(NodeCell::new(
                         TreeNode::new_with_parent(insert_value, Some(node.clone())),
                     )));
                     let left = node.borrow().left.clone();
@@ -266,7 +506,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
                     Self::node_insert(right, insert_value);
                 }
                 None => {
-                    node.borrow_mut().right = Some(Rc::new(RefCell::new(
+                    node.borrow_mut().right = Some(Shared::new(NodeCell::new(
                         TreeNode::new_with_parent(insert_value, Some(node.clone())),
                     )));
                     let right = node.borrow().right.clone();
@@ -290,12 +530,9 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
             }
             // Check parent color
             Some(parent) => {
-                if parent.borrow().color == NodeColor::Black {
-                    // insert case 1: parent is black, do not need maintain
-                    ();
-                }
+                // insert case 1: parent is black, do not need maintain
                 // parent is red, need maintain
-                else {
+                if parent.borrow().color == NodeColor::Red {
                     let grand_parent = parent.borrow().parent.clone();
                     match grand_parent {
                         None => {
@@ -391,13 +628,13 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
     fn node_delete(node: RBTreeNode<T>, delete_value: T) -> OptionRBTreeNode<T> {
         if node.borrow().value > delete_value {
             let left_child = node.borrow().left.clone();
-            if left_child.is_some() {
-                Self::node_delete(left_child.unwrap(), delete_value);
+            if let Some(left_child) = left_child {
+                Self::node_delete(left_child, delete_value);
             }
         } else if node.borrow().value < delete_value {
             let right_child = node.borrow_mut().right.clone();
-            if right_child.is_some() {
-                Self::node_delete(right_child.unwrap(), delete_value);
+            if let Some(right_child) = right_child {
+                Self::node_delete(right_child, delete_value);
             }
         } else {
             let left = node.borrow_mut().left.clone();
@@ -406,10 +643,10 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
             // if current node has two children, then recursively replace it with the min value of right
             // delete the min value of right in the right tree
             // the goal is to make the problem to be the case where current node has only one child
-            if left.is_some() && right.is_some() {
-                let min_of_right = right.clone().unwrap().borrow().get_min_value_in_children();
+            if let (Some(_), Some(right)) = (&left, &right) {
+                let min_of_right = right.borrow().get_min_value_in_children();
                 node.borrow_mut().value = min_of_right;
-                Self::node_delete(right.unwrap(), min_of_right);
+                Self::node_delete(right.clone(), min_of_right);
             }
             // current node has one child or no child
             else {
@@ -427,8 +664,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
                 else {
                     // 3.1 Black + left red case: current node is black and left child is red, right child is None
                     // delete the current black node and move the left child to the current node place
-                    if left.is_some() && right.is_none() {
-                        let left = left.unwrap();
+                    if let (Some(left), None) = (left.clone(), &right) {
                         let parent = node.borrow().parent.clone();
                         match parent {
                             None => {
@@ -451,8 +687,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
                     }
                     // 3.2 Black + right red case: current node is black and right child is red, left child is None
                     // delete the current black node and move the right child to the current node place
-                    else if left.is_none() && right.is_some() {
-                        let right = right.unwrap();
+                    else if let (None, Some(right)) = (&left, right.clone()) {
                         let parent = node.borrow().parent.clone();
                         match parent {
                             None => {
@@ -508,12 +743,11 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
             None => return,
             Some(parent) => {
                 let sibling = Self::get_sibling(node.clone());
-                let direction;
-                if Self::is_left(node.clone()) {
-                    direction = NodeDirection::Left;
+                let direction = if Self::is_left(node.clone()) {
+                    NodeDirection::Left
                 } else {
-                    direction = NodeDirection::Right;
-                }
+                    NodeDirection::Right
+                };
                 match sibling {
                     None => return,
                     Some(sibling) => {
@@ -532,15 +766,18 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
                         }
                         // sibling is black
                         else {
-                            let option_close;
-                            let option_distant;
-                            if direction == NodeDirection::Left {
-                                option_close = sibling.borrow().left.clone();
-                                option_distant = sibling.borrow().right.clone()
+                            let (option_close, option_distant) = if direction == NodeDirection::Left
+                            {
+                                (
+                                    sibling.borrow().left.clone(),
+                                    sibling.borrow().right.clone(),
+                                )
                             } else {
-                                option_close = sibling.borrow().right.clone();
-                                option_distant = sibling.borrow().left.clone()
-                            }
+                                (
+                                    sibling.borrow().right.clone(),
+                                    sibling.borrow().left.clone(),
+                                )
+                            };
                             // close and distant are black
                             if Self::get_color(option_close.clone()) == NodeColor::Black
                                 && Self::get_color(option_distant.clone()) == NodeColor::Black
@@ -608,7 +845,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
         if parent.is_some() {
             let left = parent.clone().unwrap().borrow().left.clone();
             match left {
-                Some(left) if Rc::ptr_eq(&left, &node) => {
+                Some(left) if Shared::ptr_eq(&left, &node) => {
                     parent.clone().unwrap().borrow_mut().left = right.clone();
                 }
                 _ => parent.clone().unwrap().borrow_mut().right = right.clone(),
@@ -634,7 +871,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
         if parent.is_some() {
             let right = parent.clone().unwrap().borrow().right.clone();
             match right {
-                Some(right) if Rc::ptr_eq(&right, &node) => {
+                Some(right) if Shared::ptr_eq(&right, &node) => {
                     parent.clone().unwrap().borrow_mut().right = left.clone();
                 }
                 _ => parent.clone().unwrap().borrow_mut().left = left.clone(),
@@ -649,7 +886,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
         // Return true if the node is the left child of its parent.
         match node.borrow().parent.clone() {
             Some(parent) => match parent.borrow().left.clone() {
-                Some(left) => Rc::ptr_eq(&left, &node),
+                Some(left) => Shared::ptr_eq(&left, &node),
                 None => false,
             },
             _ => false,
@@ -661,7 +898,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
         // Return true if the node is the right child of its parent.
         match node.borrow().parent.clone() {
             Some(parent) => match parent.borrow().right.clone() {
-                Some(right) => Rc::ptr_eq(&right, &node),
+                Some(right) => Shared::ptr_eq(&right, &node),
                 None => false,
             },
             _ => false,
@@ -682,6 +919,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
     }
 
     // Helper function for maintaining
+    #[allow(dead_code)]
     fn reverse_color(node: RBTreeNode<T>) {
         if node.borrow().color == NodeColor::Red {
             node.borrow_mut().color = NodeColor::Black;
@@ -700,6 +938,7 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
 
     // Helper function for maintaining
     // get uncle
+    #[allow(dead_code)]
     fn get_uncle(node: RBTreeNode<T>) -> OptionRBTreeNode<T> {
         let parent = node.borrow().parent.clone();
         match parent {
@@ -746,7 +985,26 @@ impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
         }
     }
 
-    // Helper function for RBTree::is_valid_red_black_tree
+    // Helper function for RBTree::is_valid_subtree
+    // Return true if a red node has a red child
+    fn has_red_violation(node: OptionRBTreeNode<T>) -> bool {
+        match node {
+            None => false,
+            Some(node) => {
+                let left = node.borrow().left.clone();
+                let right = node.borrow().right.clone();
+                if node.borrow().color == NodeColor::Red
+                    && (Self::get_color(left.clone()) == NodeColor::Red
+                        || Self::get_color(right.clone()) == NodeColor::Red)
+                {
+                    return true;
+                }
+                Self::has_red_violation(left) || Self::has_red_violation(right)
+            }
+        }
+    }
+
+    // Helper function for RBTree::is_valid_subtree
     fn calculate_black_height(node: OptionRBTreeNode<T>) -> Option<usize> {
         match node {
             None => Some(1),
@@ -784,31 +1042,26 @@ mod test {
         tree.insert(30);
         {
             let root = tree.root.clone().unwrap();
-            root.borrow_mut().left = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                20,
-                Some(root.clone()),
-            ))));
-            root.borrow_mut().right = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                40,
-                Some(root.clone()),
-            ))));
+            root.borrow_mut().left = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(20, Some(root.clone())),
+            )));
+            root.borrow_mut().right = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(40, Some(root.clone())),
+            )));
 
             let left = root.borrow().left.clone().unwrap();
-            left.borrow_mut().left = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                10,
-                Some(left.clone()),
-            ))));
-            left.borrow_mut().right = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                25,
-                Some(left.clone()),
-            ))));
+            left.borrow_mut().left = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(10, Some(left.clone())),
+            )));
+            left.borrow_mut().right = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(25, Some(left.clone())),
+            )));
 
             let right = root.borrow().right.clone().unwrap();
-            right.borrow_mut().left = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                35,
-                Some(right.clone()),
-            ))));
-            right.borrow_mut().right = Some(Rc::new(RefCell::new(
+            right.borrow_mut().left = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(35, Some(right.clone())),
+            )));
+            right.borrow_mut().right = Some(Shared::new(NodeCell::new(
                 TreeNode::new_black_with_parent(50, Some(right.clone())),
             )));
         }
@@ -816,35 +1069,29 @@ mod test {
         after_left_rot.insert(40);
         {
             let root = after_left_rot.root.clone().unwrap();
-            root.borrow_mut().left = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                30,
-                Some(root.clone()),
-            ))));
+            root.borrow_mut().left = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(30, Some(root.clone())),
+            )));
 
             let left = root.borrow().left.clone().unwrap();
-            left.borrow_mut().left = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                20,
-                Some(left.clone()),
-            ))));
-            left.borrow_mut().right = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                35,
-                Some(left.clone()),
-            ))));
+            left.borrow_mut().left = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(20, Some(left.clone())),
+            )));
+            left.borrow_mut().right = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(35, Some(left.clone())),
+            )));
 
             let left = left.borrow().left.clone().unwrap();
-            left.borrow_mut().left = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                10,
-                Some(left.clone()),
-            ))));
-            left.borrow_mut().right = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                25,
-                Some(root.clone()),
-            ))));
-
-            root.borrow_mut().right = Some(Rc::new(RefCell::new(TreeNode::new_black_with_parent(
-                50,
-                Some(root.clone()),
-            ))));
+            left.borrow_mut().left = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(10, Some(left.clone())),
+            )));
+            left.borrow_mut().right = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(25, Some(root.clone())),
+            )));
+
+            root.borrow_mut().right = Some(Shared::new(NodeCell::new(
+                TreeNode::new_black_with_parent(50, Some(root.clone())),
+            )));
         }
         {
             let root = tree.root.clone().unwrap();
@@ -864,12 +1111,13 @@ mod test {
         // Test the three different tree traversal functions.
         let mut tree = RBTree::new();
         tree.insert(0);
-        vec![16, 16, 8, 24, 20, 22].iter().for_each(|v| {
+        [16, 16, 8, 24, 20, 22].iter().for_each(|v| {
             tree.insert(*v);
         });
         let mut container = vec![];
         tree.pre_order_traversal_for_test(&mut container);
         assert_eq!(container, vec![8, 0, 20, 16, 24, 22]);
+        assert!(tree.is_valid_red_black_tree());
         // let mut container = vec![];
         // RedBlackTreeNode::debug_preorder_traverse(root.clone(), &mut container);
         // assert_eq!(container, vec![0, -16, 16, 8, 22, 20, 24]);
@@ -903,8 +1151,27 @@ mod test {
         rb_tree.insert(8);
         rb_tree.insert(17);
 
-        let result = RBTree::is_valid_red_black_tree(rb_tree.root);
-        assert_eq!(result, true);
+        assert!(rb_tree.is_valid_red_black_tree());
+        let result = RBTree::is_valid_subtree(rb_tree.root.clone());
+        assert!(result);
+
+        // the same checks as FastRBTree, a red root or two red nodes in a row
+        // break the red-black tree properties
+        rb_tree.root.clone().unwrap().borrow_mut().color = NodeColor::Red;
+        assert!(!rb_tree.is_valid_red_black_tree());
+        assert!(!RBTree::is_valid_subtree(rb_tree.root.clone()));
+        rb_tree.root.clone().unwrap().borrow_mut().color = NodeColor::Black;
+        let left = rb_tree.root.clone().unwrap().borrow().left.clone().unwrap();
+        left.borrow_mut().color = NodeColor::Red;
+        left.borrow().left.clone().unwrap().borrow_mut().color = NodeColor::Red;
+        assert!(!rb_tree.is_valid_red_black_tree());
+        assert!(!RBTree::is_valid_subtree(rb_tree.root.clone()));
+        // a black node added on one path breaks property 5
+        left.borrow().left.clone().unwrap().borrow_mut().color = NodeColor::Black;
+        left.borrow_mut().color = NodeColor::Black;
+        assert!(!rb_tree.is_valid_red_black_tree());
+        let result = RBTree::is_valid_subtree(rb_tree.root.clone());
+        assert!(!result);
     }
 
     #[test]
@@ -912,15 +1179,239 @@ mod test {
         // Test the three different tree traversal functions.
         let mut tree = RBTree::new();
         tree.insert(0);
-        vec![16, 8, 24, 20, 22].iter().for_each(|v| {
+        [16, 8, 24, 20, 22].iter().for_each(|v| {
             tree.insert(*v);
         });
 
         tree.delete(16);
         let mut container = vec![];
         tree.pre_order_traversal_for_test(&mut container);
-        let result = RBTree::is_valid_red_black_tree(tree.root);
-        assert_eq!(result, true);
+        assert!(tree.is_valid_red_black_tree());
+        let result = RBTree::is_valid_subtree(tree.root);
+        assert!(result);
         //  assert_eq!(container, vec![8, 0, 20, 24, 22]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", NodeColor::Red), "Red");
+        assert_eq!(format!("{}", NodeColor::Black), "Black");
+        assert_eq!(NodeColor::Red.to_short_string(), "r");
+        assert_eq!(NodeColor::Black.to_short_string(), "b");
+
+        let mut tree = RBTree::new();
+        tree.insert(5);
+        let root = tree.root.clone().unwrap();
+        assert_eq!(root.borrow().to_string(), "5(Black)");
+        assert_eq!(root.borrow().get_value_to_print(), "5b");
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let mut tree = RBTree::new();
+        [5, 3, 8, 1, 4].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let mut container = vec![];
+        for v in &tree {
+            container.push(v);
+        }
+        assert_eq!(container, vec![1, 3, 4, 5, 8]);
+
+        // the tree is still usable after the loop
+        tree.insert(2);
+        assert_eq!(
+            (&tree).into_iter().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 8]
+        );
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut tree = RBTree::new();
+        tree.extend(vec![5, 3, 8]);
+        tree.extend(Vec::new());
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![3, 5, 8]);
+
+        // overlapping values are not duplicated
+        tree.extend(vec![8, 1, 3]);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![1, 3, 5, 8]);
+
+        let mut other = RBTree::new();
+        other.extend(tree.iter());
+        assert_eq!(other.iter().collect::<Vec<_>>(), vec![1, 3, 5, 8]);
+
+        let mut extended = RBTree::new();
+        let mut inserted = RBTree::new();
+        extended.extend(0..10);
+        (0..10).for_each(|v| inserted.insert(v));
+        assert_eq!(
+            extended.iter().collect::<Vec<_>>(),
+            inserted.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(extended.height(), inserted.height());
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut tree = RBTree::new();
+        tree.extend(vec![2, 1]);
+        assert_eq!(
+            format!("{:?}", tree),
+            "RBTree { root: Some(TreeNode { value: 2b, \
+             left: Some(TreeNode { value: 1r, left: None, right: None }), right: None }) }"
+        );
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut tree = RBTree::new();
+        tree.extend(0..20);
+        let mut copy = tree.clone();
+        assert_eq!(
+            copy.iter().collect::<Vec<_>>(),
+            tree.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(format!("{:?}", copy), format!("{:?}", tree));
+
+        // the clone shares no node with the original, and its parent
+        // pointers point to its own nodes
+        let nodes = |tree: &RBTree<u32>| {
+            let mut pointers = vec![];
+            let mut stack = vec![tree.root.clone().unwrap()];
+            while let Some(node) = stack.pop() {
+                let children = [node.borrow().left.clone(), node.borrow().right.clone()];
+                for child in children.iter().flatten() {
+                    let parent = child.borrow().parent.clone().unwrap();
+                    assert!(Shared::ptr_eq(&parent, &node));
+                    stack.push(child.clone());
+                }
+                pointers.push(Shared::as_ptr(&node));
+            }
+            return pointers;
+        };
+        let original = nodes(&tree);
+        assert_eq!(original.len(), 20);
+        assert!(nodes(&copy).iter().all(|p| !original.contains(p)));
+
+        copy.delete(5);
+        copy.insert(100);
+        assert!(tree.contains(5));
+        assert!(!tree.contains(100));
+        drop(copy);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_delete_all_elements() {
+        use rand::seq::SliceRandom;
+
+        let mut inserted: Vec<u32> = (0..=99).collect();
+        inserted.shuffle(&mut rand::thread_rng());
+        let sorted: Vec<u32> = (0..=99).collect();
+        let reverse: Vec<u32> = (0..=99).rev().collect();
+        for deleted in [inserted.clone(), sorted, reverse] {
+            let mut tree = RBTree::new();
+            inserted.iter().for_each(|v| {
+                tree.insert(*v);
+            });
+            for (i, v) in deleted.iter().enumerate() {
+                tree.delete(*v);
+                assert!(!tree.contains(*v));
+                assert_eq!(tree.count_nodes(), 99 - i);
+                assert!(tree.is_valid_bst());
+                assert!(tree.is_valid_red_black_tree());
+            }
+            assert!(tree.is_empty());
+            assert_eq!(tree.height(), 0);
+        }
+    }
+
+    #[test]
+    fn test_insert_many_unique() {
+        let mut tree = RBTree::new();
+        assert_eq!(tree.insert_many_unique(vec![1, 2, 3, 2, 1]), 3);
+        assert_eq!(tree.insert_many_unique(vec![2, 3, 4, 5, 5]), 2);
+        assert_eq!(tree.insert_many_unique(Vec::new()), 0);
+        assert_eq!(tree.to_sorted_vec(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_entry_or_insert_with() {
+        let mut tree = RBTree::new();
+        let mut calls = 0;
+        [3, 1, 3, 2, 1, 3].iter().for_each(|v| {
+            let entry = tree.entry_or_insert_with(*v, || {
+                calls += 1;
+                *v
+            });
+            assert_eq!(entry, *v);
+        });
+        assert_eq!(calls, 3);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_verify_parent_child_consistency() {
+        let mut tree = RBTree::new();
+        tree.verify_parent_child_consistency();
+        for v in 0..200 {
+            tree.insert((v * 37) % 200);
+            tree.verify_parent_child_consistency();
+        }
+        for v in 0..100 {
+            tree.delete(v * 2);
+            tree.verify_parent_child_consistency();
+        }
+        tree.clone().verify_parent_child_consistency();
+
+        // holding a node while the tree changes is fine
+        let root = tree.get_root();
+        tree.insert(500);
+        tree.delete(1);
+        tree.verify_parent_child_consistency();
+        assert!(root.is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not point back to")]
+    fn test_verify_parent_child_consistency_wrong_parent() {
+        let mut tree = RBTree::new();
+        (1..=3).for_each(|v| tree.insert(v));
+        let root = tree.root.clone().unwrap();
+        let left = root.borrow().left.clone().unwrap();
+        let right = root.borrow().right.clone().unwrap();
+        // the left child now points to its sibling as its parent
+        left.borrow_mut().parent = Some(right);
+        drop(root);
+        drop(left);
+        tree.verify_parent_child_consistency();
+    }
+
+    #[test]
+    fn test_insert_equal_value() {
+        use crate::utils::test_item::Item;
+
+        let mut tree = RBTree::new();
+        [
+            Item {
+                key: 1,
+                payload: 10,
+            },
+            Item {
+                key: 2,
+                payload: 20,
+            },
+            Item {
+                key: 1,
+                payload: 11,
+            },
+        ]
+        .iter()
+        .for_each(|v| tree.insert(*v));
+        let values = tree.to_sorted_vec();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].payload, 11);
+        assert!(tree.is_valid_red_black_tree());
+    }
+}
@@ -0,0 +1,557 @@
+//! Red-black tree stored in an arena
+//!
+//! [ArenaRBTree](struct.ArenaRBTree.html) is a left-leaning red-black tree like
+//! [FastRBTree](../rb_tree_fast/struct.FastRBTree.html), but its nodes live in one `Vec`
+//! and point to each other by index instead of by `Rc`. Inserting a node does not call
+//! the allocator unless the arena is full, and deleted nodes are kept on a free list
+//! and reused by later inserts, so the arena never shrinks.
+
+use std::fmt::{Debug, Display};
+
+use crate::rb_tree_fast::NodeColor;
+
+/// Node of [ArenaRBTree](struct.ArenaRBTree.html), children are indices into the arena
+#[derive(Clone, Debug)]
+struct ArenaNode<T> {
+    value: T,
+    color: NodeColor,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// A left-leaning red-black tree whose nodes are stored in a `Vec`
+#[derive(Clone, Debug)]
+pub struct ArenaRBTree<T: Ord + Copy + Debug + Display> {
+    nodes: Vec<ArenaNode<T>>,
+    /// Indices of deleted nodes, reused by insert
+    free: Vec<usize>,
+    root: Option<usize>,
+    len: usize,
+}
+
+impl<T: Ord + Copy + Debug + Display> ArenaRBTree<T> {
+    /// Create an empty tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let tree: ArenaRBTree<u32> = ArenaRBTree::new();
+    /// ```
+    pub fn new() -> Self {
+        return ArenaRBTree::with_capacity(0);
+    }
+
+    /// Create an empty tree whose arena has room for `capacity` nodes
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let tree: ArenaRBTree<u32> = ArenaRBTree::with_capacity(1000);
+    /// assert!(tree.capacity() >= 1000);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        return ArenaRBTree {
+            nodes: Vec::with_capacity(capacity),
+            free: vec![],
+            root: None,
+            len: 0,
+        };
+    }
+
+    /// Return the number of nodes the arena can hold without allocating
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let tree: ArenaRBTree<u32> = ArenaRBTree::with_capacity(10);
+    /// assert!(tree.capacity() >= 10);
+    /// ```
+    #[must_use = "calling capacity without using the result has no effect"]
+    pub fn capacity(&self) -> usize {
+        return self.nodes.capacity();
+    }
+
+    /// Return the number of values in the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let mut tree = ArenaRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(1);
+    /// assert_eq!(1, tree.len());
+    /// ```
+    #[must_use = "calling len without using the result has no effect"]
+    pub fn len(&self) -> usize {
+        return self.len;
+    }
+
+    /// Determine whether the tree has no values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let tree: ArenaRBTree<u32> = ArenaRBTree::new();
+    /// assert_eq!(true, tree.is_empty());
+    /// ```
+    #[must_use = "calling is_empty without using the result has no effect"]
+    pub fn is_empty(&self) -> bool {
+        return self.len == 0;
+    }
+
+    /// Determine whether the tree contains the value, in O(log n)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let mut tree = ArenaRBTree::new();
+    /// tree.insert(1);
+    /// assert_eq!(true, tree.contains(1));
+    /// assert_eq!(false, tree.contains(2));
+    /// ```
+    #[must_use = "calling contains without using the result has no effect"]
+    pub fn contains(&self, value: T) -> bool {
+        let mut current = self.root;
+        while let Some(index) = current {
+            let node = &self.nodes[index];
+            if value == node.value {
+                return true;
+            } else if value < node.value {
+                current = node.left;
+            } else {
+                current = node.right;
+            }
+        }
+        return false;
+    }
+
+    /// Return the smallest value of the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let mut tree = ArenaRBTree::new();
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// assert_eq!(Some(1), tree.min());
+    /// ```
+    #[must_use = "calling min without using the result has no effect"]
+    pub fn min(&self) -> Option<T> {
+        let mut index = self.root?;
+        while let Some(left) = self.nodes[index].left {
+            index = left;
+        }
+        return Some(self.nodes[index].value);
+    }
+
+    /// Return the largest value of the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let mut tree = ArenaRBTree::new();
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// assert_eq!(Some(2), tree.max());
+    /// ```
+    #[must_use = "calling max without using the result has no effect"]
+    pub fn max(&self) -> Option<T> {
+        let mut index = self.root?;
+        while let Some(right) = self.nodes[index].right {
+            index = right;
+        }
+        return Some(self.nodes[index].value);
+    }
+
+    /// Return all values in ascending order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let mut tree = ArenaRBTree::new();
+    /// vec![3, 1, 2].iter().for_each(|v| tree.insert(*v));
+    /// assert_eq!(vec![1, 2, 3], tree.to_vec());
+    /// ```
+    #[must_use = "calling to_vec without using the result has no effect"]
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut container = Vec::with_capacity(self.len);
+        let mut stack = vec![];
+        let mut current = self.root;
+        while current.is_some() || !stack.is_empty() {
+            while let Some(index) = current {
+                stack.push(index);
+                current = self.nodes[index].left;
+            }
+            let index = stack.pop().unwrap();
+            container.push(self.nodes[index].value);
+            current = self.nodes[index].right;
+        }
+        return container;
+    }
+
+    /// Insert a value in O(log n), an equal value already in the tree is replaced
+    /// by the new one, as [FastRBTree.insert](../rb_tree_fast/struct.FastRBTree.html#method.insert) does
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let mut tree = ArenaRBTree::new();
+    /// tree.insert(1);
+    /// assert_eq!(true, tree.contains(1));
+    /// ```
+    pub fn insert(&mut self, insert_value: T) {
+        let root = self.node_insert(self.root, insert_value);
+        self.nodes[root].color = NodeColor::Black;
+        self.root = Some(root);
+    }
+
+    /// Delete a value in O(log n)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_arena::ArenaRBTree;
+    /// let mut tree = ArenaRBTree::new();
+    /// tree.insert(1);
+    /// tree.delete(1);
+    /// assert_eq!(false, tree.contains(1));
+    /// ```
+    pub fn delete(&mut self, delete_value: T) {
+        // node_delete expects the value to be in the tree
+        if !self.contains(delete_value) {
+            return;
+        }
+        let root = self.root.unwrap();
+        if !self.is_red(self.nodes[root].left) && !self.is_red(self.nodes[root].right) {
+            self.nodes[root].color = NodeColor::Red;
+        }
+        self.root = self.node_delete(root, delete_value);
+        if let Some(root) = self.root {
+            self.nodes[root].color = NodeColor::Black;
+        }
+    }
+
+    // Helper function for insert, take a slot from the free list or grow the arena
+    fn allocate(&mut self, value: T) -> usize {
+        let node = ArenaNode {
+            value,
+            color: NodeColor::Red,
+            left: None,
+            right: None,
+        };
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                return index;
+            }
+            None => {
+                self.nodes.push(node);
+                return self.nodes.len() - 1;
+            }
+        }
+    }
+
+    // Insert into the subtree and return the index of its new root
+    fn node_insert(&mut self, node: Option<usize>, insert_value: T) -> usize {
+        let index = match node {
+            None => return self.allocate(insert_value),
+            Some(index) => index,
+        };
+        let node_value = self.nodes[index].value;
+        if insert_value < node_value {
+            let left = self.node_insert(self.nodes[index].left, insert_value);
+            self.nodes[index].left = Some(left);
+        } else if insert_value > node_value {
+            let right = self.node_insert(self.nodes[index].right, insert_value);
+            self.nodes[index].right = Some(right);
+        } else {
+            self.nodes[index].value = insert_value; // equal, update value
+        }
+        return self.maintain(index);
+    }
+
+    // Delete from the subtree, which must contain the value,
+    // and return the index of its new root
+    fn node_delete(&mut self, node: usize, delete_value: T) -> Option<usize> {
+        let mut index = node;
+        if delete_value < self.nodes[index].value {
+            let left = self.nodes[index].left.unwrap();
+            if !self.is_red(Some(left)) && !self.is_red(self.nodes[left].left) {
+                index = self.move_red_left(index);
+            }
+            let left = self.nodes[index].left.unwrap();
+            self.nodes[index].left = self.node_delete(left, delete_value);
+        } else {
+            if self.is_red(self.nodes[index].left) {
+                index = self.right_rotate(index);
+            }
+            // the rotation may have changed the root of this subtree
+            if delete_value == self.nodes[index].value && self.nodes[index].right.is_none() {
+                self.len -= 1;
+                self.free.push(index);
+                return None;
+            }
+            let right = self.nodes[index].right.unwrap();
+            if !self.is_red(Some(right)) && !self.is_red(self.nodes[right].left) {
+                index = self.move_red_right(index);
+            }
+            let right = self.nodes[index].right.unwrap();
+            if delete_value == self.nodes[index].value {
+                // replace the value by the minimum of the right subtree, then delete that
+                let mut min = right;
+                while let Some(left) = self.nodes[min].left {
+                    min = left;
+                }
+                let min_value = self.nodes[min].value;
+                self.nodes[index].value = min_value;
+                self.nodes[index].right = self.node_delete(right, min_value);
+            } else {
+                self.nodes[index].right = self.node_delete(right, delete_value);
+            }
+        }
+        return Some(self.maintain(index));
+    }
+
+    // Repair the coloring after inserting or deleting
+    fn maintain(&mut self, node: usize) -> usize {
+        let mut index = node;
+        if self.is_red(self.nodes[index].right) && !self.is_red(self.nodes[index].left) {
+            index = self.left_rotate(index);
+        }
+        if let Some(left) = self.nodes[index].left {
+            if self.is_red(Some(left)) && self.is_red(self.nodes[left].left) {
+                index = self.right_rotate(index);
+            }
+        }
+        if self.is_red(self.nodes[index].left) && self.is_red(self.nodes[index].right) {
+            self.flip_color(index);
+        }
+        return index;
+    }
+
+    fn move_red_left(&mut self, node: usize) -> usize {
+        let mut index = node;
+        self.flip_color(index);
+        let right = self.nodes[index].right.unwrap();
+        if self.is_red(self.nodes[right].left) {
+            let right = self.right_rotate(right);
+            self.nodes[index].right = Some(right);
+            index = self.left_rotate(index);
+            self.flip_color(index);
+        }
+        return index;
+    }
+
+    fn move_red_right(&mut self, node: usize) -> usize {
+        let mut index = node;
+        self.flip_color(index);
+        let left = self.nodes[index].left.unwrap();
+        if self.is_red(self.nodes[left].left) {
+            index = self.right_rotate(index);
+            self.flip_color(index);
+        }
+        return index;
+    }
+
+    fn left_rotate(&mut self, node: usize) -> usize {
+        let right = self.nodes[node].right.unwrap();
+        self.nodes[node].right = self.nodes[right].left;
+        self.nodes[right].left = Some(node);
+        self.nodes[right].color = self.nodes[node].color.clone();
+        self.nodes[node].color = NodeColor::Red;
+        return right;
+    }
+
+    fn right_rotate(&mut self, node: usize) -> usize {
+        let left = self.nodes[node].left.unwrap();
+        self.nodes[node].left = self.nodes[left].right;
+        self.nodes[left].right = Some(node);
+        self.nodes[left].color = self.nodes[node].color.clone();
+        self.nodes[node].color = NodeColor::Red;
+        return left;
+    }
+
+    // Helper function for maintaining
+    // flip the colors of the node and its two children
+    fn flip_color(&mut self, node: usize) {
+        let left = self.nodes[node].left.unwrap();
+        let right = self.nodes[node].right.unwrap();
+        for index in [node, left, right].iter() {
+            self.nodes[*index].color = match self.nodes[*index].color {
+                NodeColor::Red => NodeColor::Black,
+                NodeColor::Black => NodeColor::Red,
+            };
+        }
+    }
+
+    // None is a black leaf
+    fn is_red(&self, node: Option<usize>) -> bool {
+        match node {
+            None => false,
+            Some(index) => self.nodes[index].color == NodeColor::Red,
+        }
+    }
+
+    // Helper function for eq, compare the subtree under `node` with the subtree
+    // under `other_node` of `other`, whichever slots their nodes are stored in
+    fn subtree_eq(&self, node: Option<usize>, other: &Self, other_node: Option<usize>) -> bool {
+        match (node, other_node) {
+            (None, None) => return true,
+            (Some(index), Some(other_index)) => {
+                let (a, b) = (&self.nodes[index], &other.nodes[other_index]);
+                return a.value == b.value
+                    && a.color == b.color
+                    && self.subtree_eq(a.left, other, b.left)
+                    && self.subtree_eq(a.right, other, b.right);
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Trees are equal when they have the same shape, values and colors,
+/// whichever slots of the arena their nodes are stored in
+impl<T: Ord + Copy + Debug + Display> PartialEq for ArenaRBTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        return self.subtree_eq(self.root, other, other.root);
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Eq for ArenaRBTree<T> {}
+
+impl<T: Ord + Copy + Debug + Display> Default for ArenaRBTree<T> {
+    fn default() -> Self {
+        return ArenaRBTree::new();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::BTreeSet;
+
+    // Return the black height of the subtree, None if it is not a valid
+    // left-leaning red-black tree
+    fn black_height(tree: &ArenaRBTree<u32>, node: Option<usize>) -> Option<usize> {
+        let index = match node {
+            None => return Some(1),
+            Some(index) => index,
+        };
+        let node = &tree.nodes[index];
+        if tree.is_red(node.right) {
+            return None;
+        }
+        if node.color == NodeColor::Red && tree.is_red(node.left) {
+            return None;
+        }
+        let left = black_height(tree, node.left)?;
+        let right = black_height(tree, node.right)?;
+        if left != right {
+            return None;
+        }
+        return Some(left + (node.color == NodeColor::Black) as usize);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut rng: StdRng = SeedableRng::from_seed([7u8; 32]);
+        let mut tree = ArenaRBTree::new();
+        let mut expected = BTreeSet::new();
+        for _ in 0..5000 {
+            let value = rng.gen_range(0, 500);
+            if rng.gen_range(0, 3) == 0 {
+                tree.delete(value);
+                expected.remove(&value);
+            } else {
+                tree.insert(value);
+                expected.insert(value);
+            }
+            assert_eq!(tree.len(), expected.len());
+        }
+        assert!(black_height(&tree, tree.root).is_some());
+        assert_eq!(tree.to_vec(), expected.iter().copied().collect::<Vec<_>>());
+        assert_eq!(tree.min(), expected.iter().next().copied());
+        assert_eq!(tree.max(), expected.iter().next_back().copied());
+        for v in 0..500 {
+            assert_eq!(tree.contains(v), expected.contains(&v));
+        }
+    }
+
+    #[test]
+    fn test_free_list() {
+        let mut tree = ArenaRBTree::with_capacity(100);
+        (0..100).for_each(|v| tree.insert(v));
+        (0..100).for_each(|v| tree.delete(v));
+        assert!(tree.is_empty());
+        assert_eq!(tree.min(), None);
+        assert_eq!(tree.free.len(), 100);
+
+        // deleted nodes are reused, the arena does not grow
+        (100..200).for_each(|v| tree.insert(v));
+        assert_eq!(tree.nodes.len(), 100);
+        assert_eq!(tree.free.len(), 0);
+        assert!(black_height(&tree, tree.root).is_some());
+        assert_eq!(tree.to_vec(), (100..200).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_eq() {
+        let mut tree = ArenaRBTree::new();
+        (0..10).for_each(|v| tree.insert(v));
+        // the same inserts into reused slots give the same tree in another layout
+        let mut reused = ArenaRBTree::new();
+        (100..110).for_each(|v| reused.insert(v));
+        (100..110).for_each(|v| reused.delete(v));
+        (0..10).for_each(|v| reused.insert(v));
+        assert_ne!(tree.root, reused.root);
+        assert_eq!(tree, reused);
+
+        // the same values in another shape are not equal
+        let mut reversed = ArenaRBTree::new();
+        (0..10).rev().for_each(|v| reversed.insert(v));
+        assert_eq!(tree.to_vec(), reversed.to_vec());
+        assert_ne!(tree, reversed);
+        reused.delete(5);
+        assert_ne!(tree, reused);
+        assert_eq!(ArenaRBTree::<u32>::new(), ArenaRBTree::default());
+    }
+
+    #[test]
+    fn test_insert_equal_value() {
+        use crate::utils::test_item::Item;
+
+        let mut tree = ArenaRBTree::new();
+        [
+            Item {
+                key: 1,
+                payload: 10,
+            },
+            Item {
+                key: 2,
+                payload: 20,
+            },
+            Item {
+                key: 1,
+                payload: 11,
+            },
+        ]
+        .iter()
+        .for_each(|v| tree.insert(*v));
+        let values = tree.to_vec();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].payload, 11);
+        assert_eq!(tree.len(), 2);
+    }
+}
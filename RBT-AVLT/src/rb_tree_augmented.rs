@@ -0,0 +1,568 @@
+//! Red-black tree with parent pointers
+//!
+//! A left-leaning red-black tree like [FastRBTree](../rb_tree_fast/struct.FastRBTree.html),
+//! where every node also keeps a weak pointer to its parent, so that the successor
+//! and the predecessor of a node can be found by walking up from it.
+
+use std::cmp::max;
+use std::fmt::{Debug, Display};
+
+use crate::shared_node::{NodeCell, Shared, WeakShared};
+
+pub use crate::common_trait::{CommonTreeNodeTrait, CommonTreeTrait};
+pub use crate::rb_tree_fast::NodeColor;
+
+type AugmentedRBTreeNode<T> = Shared<NodeCell<TreeNode<T>>>;
+type OptionAugmentedRBTreeNode<T> = Option<AugmentedRBTreeNode<T>>;
+
+/// Structure of AugmentedRBTree
+#[derive(Debug)]
+pub struct AugmentedRBTree<T: Ord + Copy + Debug + Display> {
+    root: OptionAugmentedRBTreeNode<T>,
+}
+
+/// Node struct for [AugmentedRBTree](struct.AugmentedRBTree.html) struct
+#[derive(Debug)]
+pub struct TreeNode<T: Ord + Copy + Debug + Display> {
+    /// The color of the node
+    color: NodeColor,
+    /// Data stored in the node
+    value: T,
+    /// The parent node, empty for the root
+    parent: WeakShared<NodeCell<TreeNode<T>>>,
+    left: OptionAugmentedRBTreeNode<T>,
+    right: OptionAugmentedRBTreeNode<T>,
+}
+
+// extend from common tree trait
+impl<T: Ord + Copy + Debug + Display> CommonTreeTrait<T, TreeNode<T>> for AugmentedRBTree<T> {
+    fn get_root(&self) -> OptionAugmentedRBTreeNode<T> {
+        return self.root.clone();
+    }
+}
+
+// extend from common tree node trait
+impl<T: Ord + Copy + Debug + Display> CommonTreeNodeTrait<T> for TreeNode<T> {
+    fn get_left(&self) -> OptionAugmentedRBTreeNode<T> {
+        return self.left.clone();
+    }
+
+    fn get_right(&self) -> OptionAugmentedRBTreeNode<T> {
+        return self.right.clone();
+    }
+
+    fn get_value(&self) -> T {
+        return self.value;
+    }
+
+    fn get_value_to_print(&self) -> String {
+        let value = self.value.to_string();
+        let color = match self.color {
+            NodeColor::Red => "r",
+            NodeColor::Black => "b",
+        };
+        return value + color;
+    }
+
+    // @Override
+    fn get_height(&self) -> u32 {
+        let left_height = match self.get_left() {
+            None => 1,
+            Some(l) => l.borrow().get_height(),
+        };
+        let right_height = match self.get_right() {
+            None => 1,
+            Some(r) => r.borrow().get_height(),
+        };
+        return max(left_height, right_height) + 1;
+    }
+
+    // @override
+    fn get_nil_height(&self) -> u32 {
+        return 1;
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Default for AugmentedRBTree<T> {
+    fn default() -> Self {
+        return AugmentedRBTree::new();
+    }
+}
+
+/// Implementations of AugmentedRBTree
+impl<T: Ord + Copy + Debug + Display> AugmentedRBTree<T> {
+    /// Create a new red-black tree with parent pointers
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_augmented::AugmentedRBTree;
+    /// let mut tree = AugmentedRBTree::<u32>::new();
+    /// ```
+    pub fn new() -> Self {
+        AugmentedRBTree { root: None }
+    }
+
+    /// Insert a new value to the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_augmented::AugmentedRBTree;
+    /// let mut tree = AugmentedRBTree::new();
+    /// tree.insert(1);
+    /// ```
+    pub fn insert(&mut self, insert_value: T) {
+        self.root = TreeNode::node_insert(self.root.clone(), insert_value);
+        self.reset_root();
+    }
+
+    /// Return the stored value equal to `value`, or insert the value computed by `f`
+    /// and return it if there is none. `f` is only called when the value is missing,
+    /// and the value it returns is expected to compare equal to `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = AugmentedRBTree::new();
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || 1));
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || unreachable!()));
+    /// ```
+    pub fn entry_or_insert_with<F: FnOnce() -> T>(&mut self, value: T, f: F) -> T {
+        if let Some(existing) = self.get(value) {
+            return existing;
+        }
+        let inserted = f();
+        self.insert(inserted);
+        return inserted;
+    }
+
+    /// Delete a value from the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_augmented::AugmentedRBTree;
+    /// let mut tree = AugmentedRBTree::new();
+    /// tree.insert(1);
+    /// tree.delete(1);
+    /// ```
+    pub fn delete(&mut self, delete_value: T) {
+        // node_delete expects the value to be in the tree
+        if !self.contains(delete_value) {
+            return;
+        }
+        let root = self.root.clone().unwrap();
+        if TreeNode::is_black(root.borrow().get_left())
+            && TreeNode::is_black(root.borrow().get_right())
+        {
+            root.borrow_mut().color = NodeColor::Red;
+        }
+        self.root = TreeNode::node_delete(Some(root), delete_value);
+        self.reset_root();
+    }
+
+    /// Return the smallest value greater than the given value, which must be in the tree.
+    /// Found by walking up the parent pointers from the node of the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_augmented::AugmentedRBTree;
+    /// let mut tree = AugmentedRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(Some(2), tree.successor_of(1));
+    /// assert_eq!(None, tree.successor_of(2));
+    /// assert_eq!(None, tree.successor_of(3));
+    /// ```
+    #[must_use = "calling successor_of without using the result has no effect"]
+    pub fn successor_of(&self, value: T) -> Option<T> {
+        let node = self.find_node(value)?;
+        let right = node.borrow().right.clone();
+        if let Some(right) = right {
+            return Some(right.borrow().get_min_value_in_children());
+        }
+        // the first ancestor which has the node in its left subtree
+        let mut child = node;
+        loop {
+            let parent = child.borrow().parent.upgrade()?;
+            if matches!(parent.borrow().left.clone(), Some(left) if Shared::ptr_eq(&left, &child)) {
+                return Some(parent.borrow().value);
+            }
+            child = parent;
+        }
+    }
+
+    /// Return the largest value less than the given value, which must be in the tree.
+    /// Found by walking up the parent pointers from the node of the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_augmented::AugmentedRBTree;
+    /// let mut tree = AugmentedRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(Some(1), tree.predecessor_of(2));
+    /// assert_eq!(None, tree.predecessor_of(1));
+    /// ```
+    #[must_use = "calling predecessor_of without using the result has no effect"]
+    pub fn predecessor_of(&self, value: T) -> Option<T> {
+        let node = self.find_node(value)?;
+        let left = node.borrow().left.clone();
+        if let Some(left) = left {
+            return Some(left.borrow().get_max_value_in_children());
+        }
+        // the first ancestor which has the node in its right subtree
+        let mut child = node;
+        loop {
+            let parent = child.borrow().parent.upgrade()?;
+            if matches!(parent.borrow().right.clone(), Some(right) if Shared::ptr_eq(&right, &child))
+            {
+                return Some(parent.borrow().value);
+            }
+            child = parent;
+        }
+    }
+
+    // Helper function for successor_of and predecessor_of
+    fn find_node(&self, value: T) -> OptionAugmentedRBTreeNode<T> {
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let node_value = node.borrow().value;
+            if value == node_value {
+                return Some(node);
+            }
+            if value < node_value {
+                current = node.borrow().left.clone();
+            } else {
+                current = node.borrow().right.clone();
+            }
+        }
+        return None;
+    }
+
+    // The root is black and has no parent
+    fn reset_root(&mut self) {
+        if let Some(root) = self.root.clone() {
+            root.borrow_mut().color = NodeColor::Black;
+            root.borrow_mut().parent = WeakShared::new();
+        }
+    }
+}
+
+/// Implementations of TreeNode
+impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
+    /// Create a new node
+    /// ,which will be called by [AugmentedRBTree](struct.AugmentedRBTree.html)
+    fn new(value: T) -> Self {
+        TreeNode {
+            color: NodeColor::Red,
+            value,
+            parent: WeakShared::new(),
+            left: None,
+            right: None,
+        }
+    }
+
+    /// Insert data into the subtree and return the new root to this subtree,
+    /// which will be called by [AugmentedRBTree.insert](struct.AugmentedRBTree.html#method.insert)
+    fn node_insert(
+        node: OptionAugmentedRBTreeNode<T>,
+        insert_value: T,
+    ) -> OptionAugmentedRBTreeNode<T> {
+        match node {
+            None => return Some(Shared::new(NodeCell::new(TreeNode::new(insert_value)))),
+            Some(n) => {
+                let node_value = n.borrow().value;
+                if insert_value < node_value {
+                    let left = n.borrow().left.clone();
+                    n.borrow_mut().left = Self::node_insert(left, insert_value);
+                } else if insert_value > node_value {
+                    let right = n.borrow().right.clone();
+                    n.borrow_mut().right = Self::node_insert(right, insert_value);
+                } else {
+                    n.borrow_mut().value = insert_value; // equal, update value
+                }
+                return Self::maintain(n);
+            }
+        }
+    }
+
+    /// Delete data from the subtree and return the new root to this subtree,
+    /// which will be called by [AugmentedRBTree.delete](struct.AugmentedRBTree.html#method.delete)
+    fn node_delete(
+        node: OptionAugmentedRBTreeNode<T>,
+        delete_value: T,
+    ) -> OptionAugmentedRBTreeNode<T> {
+        let mut n = node?;
+        if delete_value < n.borrow().value {
+            let left = n.borrow().left.clone();
+            if Self::is_black(left.clone())
+                && Self::is_black(left.and_then(|left| left.borrow().left.clone()))
+            {
+                n = Self::move_red_left(n);
+            }
+            let left = n.borrow().left.clone();
+            n.borrow_mut().left = Self::node_delete(left, delete_value);
+        } else {
+            if Self::is_red(n.borrow().left.clone()) {
+                n = Self::right_rotate(n);
+            }
+            if delete_value == n.borrow().value && n.borrow().right.is_none() {
+                return None;
+            }
+            let right = n.borrow().right.clone();
+            if Self::is_black(right.clone())
+                && Self::is_black(right.and_then(|right| right.borrow().left.clone()))
+            {
+                n = Self::move_red_right(n);
+            }
+            let right = n.borrow().right.clone();
+            if delete_value == n.borrow().value {
+                // replace the value by the minimum of the right subtree and delete that one
+                let min_value = right.clone().unwrap().borrow().get_min_value_in_children();
+                n.borrow_mut().value = min_value;
+                n.borrow_mut().right = Self::node_delete(right, min_value);
+            } else {
+                n.borrow_mut().right = Self::node_delete(right, delete_value);
+            }
+        }
+        return Self::maintain(n);
+    }
+
+    /// Repair the coloring from inserting or deleting into a tree,
+    /// and point the children of the subtree root back to it.
+    fn maintain(node: AugmentedRBTreeNode<T>) -> OptionAugmentedRBTreeNode<T> {
+        Self::set_parent_of_children(&node);
+        let mut n = node;
+        if Self::is_red(n.borrow().right.clone()) && Self::is_black(n.borrow().left.clone()) {
+            n = Self::left_rotate(n);
+        }
+        let left = n.borrow().left.clone();
+        if Self::is_red(left.clone())
+            && Self::is_red(left.and_then(|left| left.borrow().left.clone()))
+        {
+            n = Self::right_rotate(n);
+        }
+        if Self::is_red(n.borrow().left.clone()) && Self::is_red(n.borrow().right.clone()) {
+            Self::flip_color(&n);
+        }
+        return Some(n);
+    }
+
+    fn move_red_left(node: AugmentedRBTreeNode<T>) -> AugmentedRBTreeNode<T> {
+        let mut n = node;
+        Self::flip_color(&n);
+        let right = n.borrow().right.clone().unwrap();
+        if Self::is_red(right.borrow().left.clone()) {
+            n.borrow_mut().right = Some(Self::right_rotate(right));
+            n = Self::left_rotate(n);
+            Self::flip_color(&n);
+        }
+        return n;
+    }
+
+    fn move_red_right(node: AugmentedRBTreeNode<T>) -> AugmentedRBTreeNode<T> {
+        let mut n = node;
+        Self::flip_color(&n);
+        let left = n.borrow().left.clone().unwrap();
+        if Self::is_red(left.borrow().left.clone()) {
+            n = Self::right_rotate(n);
+            Self::flip_color(&n);
+        }
+        return n;
+    }
+
+    /// Rotate the subtree rooted at this node to the left and
+    /// return the new root to this subtree.
+    fn left_rotate(node: AugmentedRBTreeNode<T>) -> AugmentedRBTreeNode<T> {
+        let node_right = node.borrow().right.clone().unwrap();
+        let temp = node_right.borrow().left.clone();
+        node_right.borrow_mut().left = Some(node.clone());
+        node.borrow_mut().right = temp;
+        node_right.borrow_mut().color = node.borrow().color.clone();
+        node.borrow_mut().color = NodeColor::Red;
+        // the new root takes the place of the old one under its parent
+        node_right.borrow_mut().parent = node.borrow().parent.clone();
+        Self::set_parent_of_children(&node);
+        Self::set_parent_of_children(&node_right);
+        return node_right;
+    }
+
+    /// Rotate the subtree rooted at this node to the right and
+    /// returns the new root to this subtree.
+    fn right_rotate(node: AugmentedRBTreeNode<T>) -> AugmentedRBTreeNode<T> {
+        let node_left = node.borrow().left.clone().unwrap();
+        let temp = node_left.borrow().right.clone();
+        node_left.borrow_mut().right = Some(node.clone());
+        node.borrow_mut().left = temp;
+        node_left.borrow_mut().color = node.borrow().color.clone();
+        node.borrow_mut().color = NodeColor::Red;
+        // the new root takes the place of the old one under its parent
+        node_left.borrow_mut().parent = node.borrow().parent.clone();
+        Self::set_parent_of_children(&node);
+        Self::set_parent_of_children(&node_left);
+        return node_left;
+    }
+
+    // Point both children of the node back to it
+    fn set_parent_of_children(node: &AugmentedRBTreeNode<T>) {
+        let left = node.borrow().left.clone();
+        let right = node.borrow().right.clone();
+        for child in [left, right].iter().flatten() {
+            child.borrow_mut().parent = Shared::downgrade(node);
+        }
+    }
+
+    fn is_red(node: OptionAugmentedRBTreeNode<T>) -> bool {
+        match node {
+            None => false,
+            Some(node) => node.borrow().color == NodeColor::Red,
+        }
+    }
+
+    fn is_black(node: OptionAugmentedRBTreeNode<T>) -> bool {
+        return !Self::is_red(node);
+    }
+
+    // flip the colors of the node and its two children
+    fn flip_color(node: &AugmentedRBTreeNode<T>) {
+        let left = node.borrow().left.clone().unwrap();
+        let right = node.borrow().right.clone().unwrap();
+        for n in [node.clone(), left, right].iter() {
+            let color = match n.borrow().color {
+                NodeColor::Red => NodeColor::Black,
+                NodeColor::Black => NodeColor::Red,
+            };
+            n.borrow_mut().color = color;
+        }
+    }
+
+    fn clear(&mut self) {
+        if let Some(node) = self.left.take() {
+            node.borrow_mut().clear();
+        }
+        if let Some(node) = self.right.take() {
+            node.borrow_mut().clear();
+        }
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Drop for AugmentedRBTree<T> {
+    fn drop(&mut self) {
+        match self.root.take() {
+            Some(node) => node.borrow_mut().clear(),
+            None => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // Check that each child points back to its parent, return the number of nodes
+    fn check_parents(node: &AugmentedRBTreeNode<u32>) -> usize {
+        let mut count = 1;
+        let left = node.borrow().left.clone();
+        let right = node.borrow().right.clone();
+        for child in [left, right].iter().flatten() {
+            let parent = child.borrow().parent.upgrade().unwrap();
+            assert!(Shared::ptr_eq(&parent, node));
+            count += check_parents(child);
+        }
+        return count;
+    }
+
+    #[test]
+    fn test_parent_pointers() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut tree = AugmentedRBTree::new();
+        let mut values = std::collections::BTreeSet::new();
+        for i in 0..2000 {
+            let value = rng.gen_range(0, 500);
+            if i % 2 == 0 {
+                tree.insert(value);
+                values.insert(value);
+            } else {
+                tree.delete(value);
+                values.remove(&value);
+            }
+            if let Some(root) = tree.root.clone() {
+                assert!(root.borrow().parent.upgrade().is_none());
+                assert_eq!(check_parents(&root), values.len());
+            }
+        }
+        assert!(tree.is_valid_bst());
+        assert_eq!(
+            tree.iter().collect::<Vec<_>>(),
+            values.iter().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_successor_and_predecessor_of() {
+        let mut tree = AugmentedRBTree::new();
+        assert_eq!(tree.successor_of(1), None);
+        (0..100).for_each(|v| {
+            tree.insert(v * 3);
+        });
+        [0, 3, 9, 27].iter().for_each(|v| {
+            tree.delete(*v);
+        });
+        let values: Vec<u32> = tree.iter().collect();
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(tree.successor_of(*value), values.get(i + 1).copied());
+            assert_eq!(tree.predecessor_of(*value), tree.predecessor(*value));
+            assert_eq!(tree.successor_of(*value), tree.successor(*value));
+        }
+        // values which are not in the tree have no node to start from
+        assert_eq!(tree.successor_of(4), None);
+        assert_eq!(tree.predecessor_of(27), None);
+    }
+
+    #[test]
+    fn test_entry_or_insert_with() {
+        let mut tree = AugmentedRBTree::new();
+        let mut calls = 0;
+        [3, 1, 3, 2, 1, 3].iter().for_each(|v| {
+            let entry = tree.entry_or_insert_with(*v, || {
+                calls += 1;
+                *v
+            });
+            assert_eq!(entry, *v);
+        });
+        assert_eq!(calls, 3);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_insert_equal_value() {
+        use crate::utils::test_item::Item;
+
+        let mut tree = AugmentedRBTree::new();
+        [
+            Item {
+                key: 1,
+                payload: 10,
+            },
+            Item {
+                key: 2,
+                payload: 20,
+            },
+            Item {
+                key: 1,
+                payload: 11,
+            },
+        ]
+        .iter()
+        .for_each(|v| tree.insert(*v));
+        let values = tree.to_sorted_vec();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].payload, 11);
+    }
+}
@@ -0,0 +1,477 @@
+//! Red-black tree with boxed nodes
+//!
+//! [BoxRBTree](struct.BoxRBTree.html) is a left-leaning red-black tree like
+//! [FastRBTree](../rb_tree_fast/struct.FastRBTree.html), but every node is owned by
+//! exactly one parent through a `Box`. There is no reference count and no runtime
+//! borrow check. A rotation takes the children out of their parents and puts them
+//! back in their new places, so nodes are moved and never shared.
+
+use std::fmt::{Debug, Display};
+
+use crate::rb_tree_fast::NodeColor;
+
+/// Node of [BoxRBTree](struct.BoxRBTree.html)
+#[derive(Clone, Debug, PartialEq)]
+struct TreeNode<T> {
+    value: T,
+    color: NodeColor,
+    left: OptionBoxRBTreeNode<T>,
+    right: OptionBoxRBTreeNode<T>,
+}
+
+type BoxRBTreeNode<T> = Box<TreeNode<T>>;
+type OptionBoxRBTreeNode<T> = Option<BoxRBTreeNode<T>>;
+
+/// A left-leaning red-black tree whose nodes are owned through `Box`
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoxRBTree<T: Ord + Copy + Debug + Display> {
+    root: OptionBoxRBTreeNode<T>,
+    len: usize,
+}
+
+impl<T: Ord + Copy + Debug + Display> BoxRBTree<T> {
+    /// Create an empty tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_box::BoxRBTree;
+    /// let tree: BoxRBTree<u32> = BoxRBTree::new();
+    /// ```
+    pub fn new() -> Self {
+        return BoxRBTree { root: None, len: 0 };
+    }
+
+    /// Return the number of values in the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_box::BoxRBTree;
+    /// let mut tree = BoxRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(1);
+    /// assert_eq!(1, tree.len());
+    /// ```
+    #[must_use = "calling len without using the result has no effect"]
+    pub fn len(&self) -> usize {
+        return self.len;
+    }
+
+    /// Determine whether the tree has no values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_box::BoxRBTree;
+    /// let tree: BoxRBTree<u32> = BoxRBTree::new();
+    /// assert_eq!(true, tree.is_empty());
+    /// ```
+    #[must_use = "calling is_empty without using the result has no effect"]
+    pub fn is_empty(&self) -> bool {
+        return self.len == 0;
+    }
+
+    /// Determine whether the tree contains the value, in O(log n)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_box::BoxRBTree;
+    /// let mut tree = BoxRBTree::new();
+    /// tree.insert(1);
+    /// assert_eq!(true, tree.contains(1));
+    /// assert_eq!(false, tree.contains(2));
+    /// ```
+    #[must_use = "calling contains without using the result has no effect"]
+    pub fn contains(&self, value: T) -> bool {
+        let mut current = &self.root;
+        while let Some(node) = current {
+            if value == node.value {
+                return true;
+            } else if value < node.value {
+                current = &node.left;
+            } else {
+                current = &node.right;
+            }
+        }
+        return false;
+    }
+
+    /// Return the smallest value of the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_box::BoxRBTree;
+    /// let mut tree = BoxRBTree::new();
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// assert_eq!(Some(1), tree.min());
+    /// ```
+    #[must_use = "calling min without using the result has no effect"]
+    pub fn min(&self) -> Option<T> {
+        let mut node = self.root.as_ref()?;
+        while let Some(left) = &node.left {
+            node = left;
+        }
+        return Some(node.value);
+    }
+
+    /// Return the largest value of the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_box::BoxRBTree;
+    /// let mut tree = BoxRBTree::new();
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// assert_eq!(Some(2), tree.max());
+    /// ```
+    #[must_use = "calling max without using the result has no effect"]
+    pub fn max(&self) -> Option<T> {
+        let mut node = self.root.as_ref()?;
+        while let Some(right) = &node.right {
+            node = right;
+        }
+        return Some(node.value);
+    }
+
+    /// Return all values in ascending order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_box::BoxRBTree;
+    /// let mut tree = BoxRBTree::new();
+    /// vec![3, 1, 2].iter().for_each(|v| tree.insert(*v));
+    /// assert_eq!(vec![1, 2, 3], tree.to_vec());
+    /// ```
+    #[must_use = "calling to_vec without using the result has no effect"]
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut container = Vec::with_capacity(self.len);
+        let mut stack: Vec<&BoxRBTreeNode<T>> = vec![];
+        let mut current = &self.root;
+        loop {
+            while let Some(node) = current {
+                stack.push(node);
+                current = &node.left;
+            }
+            match stack.pop() {
+                None => return container,
+                Some(node) => {
+                    container.push(node.value);
+                    current = &node.right;
+                }
+            }
+        }
+    }
+
+    /// Insert a value in O(log n), an equal value already in the tree is replaced
+    /// by the new one, as [FastRBTree.insert](../rb_tree_fast/struct.FastRBTree.html#method.insert) does
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_box::BoxRBTree;
+    /// let mut tree = BoxRBTree::new();
+    /// tree.insert(1);
+    /// assert_eq!(true, tree.contains(1));
+    /// ```
+    pub fn insert(&mut self, insert_value: T) {
+        if !self.contains(insert_value) {
+            self.len += 1;
+        }
+        let mut root = TreeNode::node_insert(self.root.take(), insert_value);
+        root.color = NodeColor::Black;
+        self.root = Some(root);
+    }
+
+    /// Delete a value in O(log n)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_box::BoxRBTree;
+    /// let mut tree = BoxRBTree::new();
+    /// tree.insert(1);
+    /// tree.delete(1);
+    /// assert_eq!(false, tree.contains(1));
+    /// ```
+    pub fn delete(&mut self, delete_value: T) {
+        // node_delete expects the value to be in the tree
+        if !self.contains(delete_value) {
+            return;
+        }
+        let mut root = self.root.take().unwrap();
+        if !TreeNode::is_red(&root.left) && !TreeNode::is_red(&root.right) {
+            root.color = NodeColor::Red;
+        }
+        self.root = TreeNode::node_delete(root, delete_value);
+        if let Some(root) = &mut self.root {
+            root.color = NodeColor::Black;
+        }
+        self.len -= 1;
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Default for BoxRBTree<T> {
+    fn default() -> Self {
+        return BoxRBTree::new();
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
+    // Insert into the subtree, replacing an equal value, and return its new root
+    fn node_insert(node: OptionBoxRBTreeNode<T>, insert_value: T) -> BoxRBTreeNode<T> {
+        let mut node = match node {
+            None => {
+                return Box::new(TreeNode {
+                    value: insert_value,
+                    color: NodeColor::Red,
+                    left: None,
+                    right: None,
+                })
+            }
+            Some(node) => node,
+        };
+        if insert_value < node.value {
+            node.left = Some(Self::node_insert(node.left.take(), insert_value));
+        } else if insert_value > node.value {
+            node.right = Some(Self::node_insert(node.right.take(), insert_value));
+        } else {
+            node.value = insert_value; // equal, update value
+        }
+        return Self::maintain(node);
+    }
+
+    // Delete from the subtree, which must contain the value,
+    // and return its new root
+    fn node_delete(node: BoxRBTreeNode<T>, delete_value: T) -> OptionBoxRBTreeNode<T> {
+        let mut node = node;
+        if delete_value < node.value {
+            if Self::is_black_with_black_left(&node.left) {
+                node = Self::move_red_left(node);
+            }
+            let left = node.left.take().unwrap();
+            node.left = Self::node_delete(left, delete_value);
+        } else {
+            if Self::is_red(&node.left) {
+                node = Self::right_rotate(node);
+            }
+            // the rotation may have changed the root of this subtree
+            if delete_value == node.value && node.right.is_none() {
+                return None;
+            }
+            if Self::is_black_with_black_left(&node.right) {
+                node = Self::move_red_right(node);
+            }
+            let right = node.right.take().unwrap();
+            if delete_value == node.value {
+                // replace the value by the minimum of the right subtree, then delete that
+                let mut min = &right;
+                while let Some(left) = &min.left {
+                    min = left;
+                }
+                node.value = min.value;
+                node.right = Self::node_delete(right, node.value);
+            } else {
+                node.right = Self::node_delete(right, delete_value);
+            }
+        }
+        return Some(Self::maintain(node));
+    }
+
+    // Repair the coloring after inserting or deleting
+    fn maintain(node: BoxRBTreeNode<T>) -> BoxRBTreeNode<T> {
+        let mut node = node;
+        if Self::is_red(&node.right) && !Self::is_red(&node.left) {
+            node = Self::left_rotate(node);
+        }
+        if Self::is_red_with_red_left(&node.left) {
+            node = Self::right_rotate(node);
+        }
+        if Self::is_red(&node.left) && Self::is_red(&node.right) {
+            Self::flip_color(&mut node);
+        }
+        return node;
+    }
+
+    fn move_red_left(node: BoxRBTreeNode<T>) -> BoxRBTreeNode<T> {
+        let mut node = node;
+        Self::flip_color(&mut node);
+        if Self::is_red(&node.right.as_ref().unwrap().left) {
+            let right = node.right.take().unwrap();
+            node.right = Some(Self::right_rotate(right));
+            node = Self::left_rotate(node);
+            Self::flip_color(&mut node);
+        }
+        return node;
+    }
+
+    fn move_red_right(node: BoxRBTreeNode<T>) -> BoxRBTreeNode<T> {
+        let mut node = node;
+        Self::flip_color(&mut node);
+        if Self::is_red(&node.left.as_ref().unwrap().left) {
+            node = Self::right_rotate(node);
+            Self::flip_color(&mut node);
+        }
+        return node;
+    }
+
+    fn left_rotate(node: BoxRBTreeNode<T>) -> BoxRBTreeNode<T> {
+        let mut node = node;
+        let mut right = node.right.take().unwrap();
+        node.right = right.left.take();
+        right.color = node.color.clone();
+        node.color = NodeColor::Red;
+        right.left = Some(node);
+        return right;
+    }
+
+    fn right_rotate(node: BoxRBTreeNode<T>) -> BoxRBTreeNode<T> {
+        let mut node = node;
+        let mut left = node.left.take().unwrap();
+        node.left = left.right.take();
+        left.color = node.color.clone();
+        node.color = NodeColor::Red;
+        left.right = Some(node);
+        return left;
+    }
+
+    // Helper function for maintaining
+    // flip the colors of the node and its two children
+    fn flip_color(node: &mut BoxRBTreeNode<T>) {
+        Self::reverse_color(node);
+        Self::reverse_color(node.left.as_mut().unwrap());
+        Self::reverse_color(node.right.as_mut().unwrap());
+    }
+
+    // Helper function for maintaining
+    fn reverse_color(node: &mut BoxRBTreeNode<T>) {
+        node.color = match node.color {
+            NodeColor::Red => NodeColor::Black,
+            NodeColor::Black => NodeColor::Red,
+        };
+    }
+
+    // None is a black leaf
+    fn is_red(node: &OptionBoxRBTreeNode<T>) -> bool {
+        match node {
+            None => false,
+            Some(node) => node.color == NodeColor::Red,
+        }
+    }
+
+    // Helper function for maintaining
+    // true if the node and its left child are both red
+    fn is_red_with_red_left(node: &OptionBoxRBTreeNode<T>) -> bool {
+        match node {
+            None => false,
+            Some(node) => node.color == NodeColor::Red && Self::is_red(&node.left),
+        }
+    }
+
+    // Helper function for deleting
+    // true if the node and its left child are both black, None counts as black
+    fn is_black_with_black_left(node: &OptionBoxRBTreeNode<T>) -> bool {
+        match node {
+            None => true,
+            Some(node) => node.color == NodeColor::Black && !Self::is_red(&node.left),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::BTreeSet;
+
+    // Return the black height of the subtree, None if it is not a valid
+    // left-leaning red-black tree
+    fn black_height(node: &OptionBoxRBTreeNode<u32>) -> Option<usize> {
+        let node = match node {
+            None => return Some(1),
+            Some(node) => node,
+        };
+        if TreeNode::is_red(&node.right) {
+            return None;
+        }
+        if node.color == NodeColor::Red && TreeNode::is_red(&node.left) {
+            return None;
+        }
+        let left = black_height(&node.left)?;
+        let right = black_height(&node.right)?;
+        if left != right {
+            return None;
+        }
+        return Some(left + (node.color == NodeColor::Black) as usize);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut rng: StdRng = SeedableRng::from_seed([7u8; 32]);
+        let mut tree = BoxRBTree::new();
+        let mut expected = BTreeSet::new();
+        for _ in 0..5000 {
+            let value = rng.gen_range(0, 500);
+            if rng.gen_range(0, 3) == 0 {
+                tree.delete(value);
+                expected.remove(&value);
+            } else {
+                tree.insert(value);
+                expected.insert(value);
+            }
+            assert_eq!(tree.len(), expected.len());
+        }
+        assert!(black_height(&tree.root).is_some());
+        assert_eq!(tree.to_vec(), expected.iter().copied().collect::<Vec<_>>());
+        assert_eq!(tree.min(), expected.iter().next().copied());
+        assert_eq!(tree.max(), expected.iter().next_back().copied());
+        for v in 0..500 {
+            assert_eq!(tree.contains(v), expected.contains(&v));
+        }
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let mut tree = BoxRBTree::new();
+        (0..100).for_each(|v| tree.insert(v));
+        let mut copy = tree.clone();
+        assert_eq!(copy, tree);
+        (0..50).for_each(|v| copy.delete(v));
+        assert_eq!(tree.len(), 100);
+        assert_eq!(copy.to_vec(), (50..100).collect::<Vec<_>>());
+        assert!(black_height(&copy.root).is_some());
+        assert!(BoxRBTree::<u32>::default().is_empty());
+    }
+
+    #[test]
+    fn test_insert_equal_value() {
+        use crate::utils::test_item::Item;
+
+        let mut tree = BoxRBTree::new();
+        [
+            Item {
+                key: 1,
+                payload: 10,
+            },
+            Item {
+                key: 2,
+                payload: 20,
+            },
+            Item {
+                key: 1,
+                payload: 11,
+            },
+        ]
+        .iter()
+        .for_each(|v| tree.insert(*v));
+        let values = tree.to_vec();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].payload, 11);
+        assert_eq!(tree.len(), 2);
+    }
+}
@@ -0,0 +1,3456 @@
+//! Red-black tree
+//!
+//! You can generate a red-black tree, and insert or delete nodes.
+
+#[cfg(feature = "stats")]
+use std::cell::Cell;
+use std::cmp::max;
+use std::fmt::{self, Debug, Display};
+use std::iter::Peekable;
+use std::str::FromStr;
+
+use crate::avl_tree::AVLTree;
+use crate::common_trait::{
+    handle_not_found, AlreadyExists, DebugSubtree, InOrderIter, DEBUG_DEPTH,
+};
+pub use crate::common_trait::{CommonTreeNodeTrait, CommonTreeTrait};
+use crate::rb_tree::RBTree;
+#[cfg(feature = "stats")]
+use crate::shared_node::Counter;
+use crate::shared_node::{CachedU32, NodeCell, Shared};
+
+/// Color representation for the [TreeNode](struct.TreeNode.html)
+/// of [FastRBTree](struct.FastRBTree.html) struct
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeColor {
+    /// Red color
+    Red,
+    /// Black color, the root of [FastRBTree](struct.FastRBTree.html) will be set to Black
+    Black,
+}
+
+/// Structure of FastRBTree
+pub struct FastRBTree<T: Ord + Copy + Debug + Display> {
+    root: OptionFastRBTreeNode<T>,
+    /// Height of the tree, `None` after the tree changed until the next call to `height`
+    cached_height: CachedU32,
+    /// Number of rotations done by insert and delete
+    #[cfg(feature = "stats")]
+    rotation_count: Counter,
+}
+
+/// Fraction of the tree above which `bulk_delete` rebuilds the tree
+/// instead of deleting the values one by one
+const BULK_DELETE_REBUILD_RATIO: f64 = 0.3;
+
+#[cfg(feature = "stats")]
+thread_local! {
+    // rotations done by the node functions on this thread,
+    // the tree adds the difference around each insert and delete
+    #[allow(clippy::missing_const_for_thread_local)]
+    static ROTATIONS: Cell<u64> = Cell::new(0);
+}
+
+/// Node struct for [FastRBTree](struct.FastRBTree.html) struct
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeNode<T: Ord + Copy + Debug + Display> {
+    /// The color of the node
+    color: NodeColor,
+    /// Data stored in the node
+    value: T,
+    left: OptionFastRBTreeNode<T>,
+    right: OptionFastRBTreeNode<T>,
+}
+
+type FastRBTreeNode<T> = Shared<NodeCell<TreeNode<T>>>;
+type OptionFastRBTreeNode<T> = Option<FastRBTreeNode<T>>;
+
+/// Implementations of NodeColor
+impl NodeColor {
+    fn to_short_string(&self) -> &str {
+        if self == &NodeColor::Red {
+            "r"
+        } else {
+            "b"
+        }
+    }
+}
+
+impl fmt::Display for NodeColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeColor::Red => write!(f, "Red"),
+            NodeColor::Black => write!(f, "Black"),
+        }
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> fmt::Display for TreeNode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({})", self.value, self.color)
+    }
+}
+
+// extend from common tree trait
+impl<T: Ord + Copy + Debug + Display> CommonTreeTrait<T, TreeNode<T>> for FastRBTree<T> {
+    fn get_root(&self) -> OptionFastRBTreeNode<T> {
+        return self.root.clone();
+    }
+
+    // @Override
+    // check the binary search tree property and the red-black tree properties
+    fn is_valid_red_black_tree(&self) -> bool {
+        return FastRBTree::is_valid_subtree(self.root.clone());
+    }
+    // @Override
+    // insert and delete only drop the cached height, so the first call after a
+    // change walks the whole tree in O(n) and later calls return the cache in O(1);
+    // a height read after every change therefore costs O(n) each time
+    fn height(&self) -> u32 {
+        if let Some(height) = self.cached_height.get() {
+            return height;
+        }
+        let height = self.compute_height();
+        self.cached_height.set(Some(height));
+        return height;
+    }
+}
+
+/// Iterate over the values of a borrowed tree in ascending order
+impl<'a, T: Ord + Copy + Debug + Display> IntoIterator for &'a FastRBTree<T> {
+    type Item = T;
+    type IntoIter = InOrderIter<'a, T, TreeNode<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+/// Copy every node, so that the copy and the original can change independently
+impl<T: Ord + Copy + Debug + Display> Clone for FastRBTree<T> {
+    fn clone(&self) -> Self {
+        return FastRBTree {
+            root: TreeNode::deep_clone(&self.root),
+            cached_height: self.cached_height.clone(),
+            #[cfg(feature = "stats")]
+            rotation_count: self.rotation_count.clone(),
+        };
+    }
+}
+
+/// Trees are equal when they have the same shape and values,
+/// whether their height is cached or not and however many rotations built them
+impl<T: Ord + Copy + Debug + Display> PartialEq for FastRBTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        return self.root == other.root;
+    }
+}
+
+/// Trees are equal when they have the same shape and values,
+/// which is an equivalence relation since `T: Ord` implies `T: Eq`
+impl<T: Ord + Copy + Debug + Display> Eq for FastRBTree<T> {}
+
+/// Print the structure of the tree, nodes deeper than five levels are shown as `...`
+impl<T: Ord + Copy + Debug + Display> Debug for FastRBTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let root: DebugSubtree<T, TreeNode<T>> = DebugSubtree::new(self.root.clone(), DEBUG_DEPTH);
+        return f.debug_struct("FastRBTree").field("root", &root).finish();
+    }
+}
+
+/// Insert every value of an iterator, values already in the tree are kept once
+impl<T: Ord + Copy + Debug + Display> Extend<T> for FastRBTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for v in iter {
+            self.insert(v);
+        }
+    }
+}
+
+// extend from common tree node trait
+impl<T: Ord + Copy + Debug + Display> CommonTreeNodeTrait<T> for TreeNode<T> {
+    #[inline(always)]
+    fn get_left(&self) -> OptionFastRBTreeNode<T> {
+        return self.left.clone();
+    }
+
+    #[inline(always)]
+    fn get_right(&self) -> OptionFastRBTreeNode<T> {
+        return self.right.clone();
+    }
+
+    #[inline(always)]
+    fn get_value(&self) -> T {
+        return self.value;
+    }
+
+    fn get_value_to_print(&self) -> String {
+        let value = self.value.to_string();
+        let color = self.color.to_short_string();
+        return value + color;
+    }
+
+    // @Override
+    fn get_height(&self) -> u32 {
+        // match on references, cloning the children would cost two
+        // reference count updates per node
+        let left_height = match &self.left {
+            None => 1,
+            Some(l) => l.borrow().get_height(),
+        };
+        let right_height = match &self.right {
+            None => 1,
+            Some(r) => r.borrow().get_height(),
+        };
+        return max(left_height, right_height) + 1;
+    }
+
+    // @override
+    fn get_nil_height(&self) -> u32 {
+        return 1;
+    }
+
+    // @override
+    fn count_leaves(&self) -> u32 {
+        let left = self.get_left();
+        let right = self.get_right();
+        match (left, right) {
+            (None, None) => return 2,
+            (None, Some(right)) => return 1 + right.borrow().count_leaves(),
+            (Some(left), None) => return 1 + left.borrow().count_leaves(),
+            (Some(left), Some(right)) => {
+                return right.borrow().count_leaves() + left.borrow().count_leaves();
+            }
+        }
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Default for FastRBTree<T> {
+    fn default() -> Self {
+        return FastRBTree::new();
+    }
+}
+
+/// Implementations of FastRBTree
+// FastRBTree
+impl<T: Ord + Copy + Debug + Display> FastRBTree<T> {
+    /// Create a new red-black Tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::<u32>::new();
+    /// ```
+    pub fn new() -> Self {
+        FastRBTree {
+            root: None,
+            cached_height: CachedU32::new(None),
+            #[cfg(feature = "stats")]
+            rotation_count: Counter::new(0),
+        }
+    }
+
+    /// Return a [TreeBuilder](struct.TreeBuilder.html) to build a tree by method chaining
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = FastRBTree::builder().with_values(0..10).with_value(42).build();
+    /// assert_eq!(Some(42), tree.max());
+    /// ```
+    pub fn builder() -> TreeBuilder<T> {
+        return TreeBuilder::new();
+    }
+
+    /// Build a red-black tree of the minimum height from a sorted slice without
+    /// duplicates in O(n), the input is not checked
+    ///
+    /// The black height is the largest one the values fill completely, and the
+    /// other values go into red left children, at most one on each path, so the
+    /// tree has the minimum height and its red links lean left.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let tree = FastRBTree::from_sorted_slice(&[1, 2, 3, 4, 5]);
+    /// ```
+    pub fn from_sorted_slice(data: &[T]) -> Self {
+        let root = TreeNode::build_from_sorted(data, Self::sorted_black_height(data.len()));
+        return Self::from_built_root(root);
+    }
+
+    /// Build a red-black tree from sorted values without duplicates in O(n) like
+    /// [from_sorted_slice](struct.FastRBTree.html#method.from_sorted_slice), but the
+    /// input is checked first and the index of the first value which is not greater
+    /// than the one before it is returned in an error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// use tree_collections::rb_tree_fast::NotSortedError;
+    /// let tree = FastRBTree::from_sorted_dedup(vec![1, 2, 3]).unwrap();
+    /// assert_eq!(3, tree.count_nodes());
+    /// assert_eq!(Err(NotSortedError { index: 2 }), FastRBTree::from_sorted_dedup(vec![1, 3, 2]));
+    /// ```
+    pub fn from_sorted_dedup(data: Vec<T>) -> Result<Self, NotSortedError> {
+        if let Some(index) = (1..data.len()).find(|i| data[*i - 1] >= data[*i]) {
+            return Err(NotSortedError { index });
+        }
+        return Ok(Self::from_sorted_slice(&data));
+    }
+
+    // Helper function for building from sorted data
+    // the largest black height whose all-black tree fits in the values
+    fn sorted_black_height(len: usize) -> u32 {
+        let mut black_height = 0;
+        while (2 << black_height) - 1 <= len {
+            black_height += 1;
+        }
+        return black_height;
+    }
+
+    // Helper function for building from sorted data
+    // wrap the built root into a tree
+    fn from_built_root(root: OptionFastRBTreeNode<T>) -> Self {
+        if let Some(root) = root.clone() {
+            root.borrow_mut().color = NodeColor::Black;
+        }
+        return FastRBTree {
+            root,
+            cached_height: CachedU32::new(None),
+            #[cfg(feature = "stats")]
+            rotation_count: Counter::new(0),
+        };
+    }
+
+    /// Convert the tree into an AVL tree holding the same values, in O(n)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+    /// let avl = tree.into_avl();
+    /// assert_eq!(vec![1, 2, 3], avl.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn into_avl(self) -> AVLTree<T> {
+        let data: Vec<T> = self.iter().collect();
+        return AVLTree::from_sorted_slice(&data);
+    }
+
+    /// Build a red-black tree holding the same values as an AVL tree, in O(n)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let avl = AVLTree::from_sorted_slice(&[1, 2, 3]);
+    /// let tree = FastRBTree::from_avl(avl);
+    /// assert_eq!(vec![1, 2, 3], tree.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn from_avl(avl: AVLTree<T>) -> Self {
+        let data: Vec<T> = avl.iter().collect();
+        return FastRBTree::from_sorted_slice(&data);
+    }
+
+    /// Convert the tree into an [RBTree](../rb_tree/struct.RBTree.html) holding the
+    /// same values, in O(n log n) as `RBTree` is filled by insertion
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+    /// let rbt = tree.into_rbt();
+    /// assert_eq!(vec![1, 2, 3], rbt.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn into_rbt(self) -> RBTree<T> {
+        let mut rbt = RBTree::new();
+        rbt.extend(self.iter());
+        return rbt;
+    }
+
+    /// Build a red-black tree holding the same values as an
+    /// [RBTree](../rb_tree/struct.RBTree.html), in O(n)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut rbt = RBTree::new();
+    /// rbt.insert(2);
+    /// rbt.insert(1);
+    /// let tree = FastRBTree::from_rbt(rbt);
+    /// assert_eq!(vec![1, 2], tree.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn from_rbt(rbt: RBTree<T>) -> Self {
+        let data: Vec<T> = rbt.iter().collect();
+        return FastRBTree::from_sorted_slice(&data);
+    }
+
+    /// Insert a new value to the red-black Tree
+    ///
+    /// If the tree already holds a value which compares equal to the new one,
+    /// the stored value is replaced by the new one, so for a type whose `Ord`
+    /// only looks at part of its fields the other fields are updated.
+    /// Use [insert_if_absent](struct.FastRBTree.html#method.insert_if_absent)
+    /// to keep the stored value instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::<u32>::new();
+    /// tree.insert(1);
+    /// ```
+    pub fn insert(&mut self, insert_value: T) {
+        #[cfg(feature = "stats")]
+        let rotations = ROTATIONS.with(|r| r.get());
+        self.root = TreeNode::node_insert(self.root.take(), insert_value);
+        self.root.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
+        self.cached_height.set(None);
+        #[cfg(feature = "stats")]
+        self.add_rotations_since(rotations);
+    }
+
+    /// Insert a new value only if no equal value is in the tree,
+    /// return true if the value was inserted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// assert_eq!(true, tree.insert_if_absent(1));
+    /// assert_eq!(false, tree.insert_if_absent(1));
+    /// ```
+    pub fn insert_if_absent(&mut self, insert_value: T) -> bool {
+        if self.contains(insert_value) {
+            return false;
+        }
+        self.insert(insert_value);
+        return true;
+    }
+
+    /// Insert every value which is not in the tree yet, return how many were inserted
+    ///
+    /// Values already in the tree, or repeated in `iter`, are skipped like
+    /// [insert_if_absent](struct.FastRBTree.html#method.insert_if_absent) does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// assert_eq!(3, tree.insert_many_unique(vec![1, 2, 3, 2, 1]));
+    /// assert_eq!(1, tree.insert_many_unique(vec![3, 4]));
+    /// ```
+    pub fn insert_many_unique<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        return iter
+            .into_iter()
+            .filter(|value| self.insert_if_absent(*value))
+            .count();
+    }
+
+    /// Insert a new value, or return the stored value in an error
+    /// if an equal value is already in the tree, which is then left unchanged
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// use tree_collections::common_trait::AlreadyExists;
+    /// let mut tree = FastRBTree::new();
+    /// assert_eq!(Ok(()), tree.try_insert(1));
+    /// assert_eq!(Err(AlreadyExists(1)), tree.try_insert(1));
+    /// ```
+    pub fn try_insert(&mut self, insert_value: T) -> Result<(), AlreadyExists<T>> {
+        match self.get(insert_value) {
+            Some(existing) => return Err(AlreadyExists(existing)),
+            None => {
+                self.insert(insert_value);
+                return Ok(());
+            }
+        }
+    }
+
+    /// Return the stored value equal to `value`, or insert the value computed by `f`
+    /// and return it if there is none. `f` is only called when the value is missing,
+    /// and the value it returns is expected to compare equal to `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || 1));
+    /// assert_eq!(1, tree.entry_or_insert_with(1, || unreachable!()));
+    /// ```
+    pub fn entry_or_insert_with<F: FnOnce() -> T>(&mut self, value: T, f: F) -> T {
+        if let Some(existing) = self.get(value) {
+            return existing;
+        }
+        let inserted = f();
+        self.insert(inserted);
+        return inserted;
+    }
+
+    /// Delete a value from the red-black Tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.delete(1);
+    /// ```
+    pub fn delete(&mut self, delete_value: T) {
+        // node_delete expects the value to be in the tree
+        if !self.contains(delete_value) {
+            return;
+        }
+        match self.root.take() {
+            None => (),
+            Some(root) => {
+                if TreeNode::is_black(&root.borrow().left)
+                    && TreeNode::is_black(&root.borrow().right)
+                {
+                    root.borrow_mut().color = NodeColor::Red;
+                }
+                #[cfg(feature = "stats")]
+                let rotations = ROTATIONS.with(|r| r.get());
+                self.root = TreeNode::node_delete(Some(root), delete_value);
+                if let Some(root) = &self.root {
+                    root.borrow_mut().color = NodeColor::Black;
+                }
+                self.cached_height.set(None);
+                #[cfg(feature = "stats")]
+                self.add_rotations_since(rotations);
+            }
+        }
+    }
+
+    /// Delete several values from the red-black Tree
+    ///
+    /// When the values make up a large fraction of the tree, the remaining values
+    /// are collected and the tree is rebuilt in O(n), otherwise they are deleted
+    /// one by one. Values which are not in the tree are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::from_sorted_slice(&[1, 2, 3, 4, 5]);
+    /// tree.bulk_delete(vec![2, 4, 6]);
+    /// assert_eq!(vec![1, 3, 5], tree.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn bulk_delete<I: IntoIterator<Item = T>>(&mut self, to_delete: I) {
+        let mut to_delete: Vec<T> = to_delete.into_iter().collect();
+        let len = self.count_nodes();
+        if (to_delete.len() as f64) < len as f64 * BULK_DELETE_REBUILD_RATIO {
+            for v in to_delete {
+                self.delete(v);
+            }
+            return;
+        }
+        to_delete.sort_unstable();
+        let remaining: Vec<T> = self
+            .iter()
+            .filter(|v| to_delete.binary_search(v).is_err())
+            .collect();
+        self.replace_with(FastRBTree::from_sorted_slice(&remaining));
+    }
+
+    /// Delete every value `x` with `lo <= x <= hi`, nothing is deleted if `lo > hi`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::from_sorted_slice(&[1, 2, 3, 4, 5]);
+    /// tree.remove_range_inclusive(2, 4);
+    /// assert_eq!(vec![1, 5], tree.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn remove_range_inclusive(&mut self, lo: T, hi: T) {
+        let to_delete: Vec<T> = self.iter_from(lo).take_while(|v| *v <= hi).collect();
+        self.bulk_delete(to_delete);
+    }
+
+    /// Delete every value `x` with `lo < x < hi`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::from_sorted_slice(&[1, 2, 3, 4, 5]);
+    /// tree.remove_range_exclusive(2, 4);
+    /// assert_eq!(vec![1, 2, 4, 5], tree.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn remove_range_exclusive(&mut self, lo: T, hi: T) {
+        let to_delete: Vec<T> = self
+            .iter_from(lo)
+            .skip_while(|v| *v == lo)
+            .take_while(|v| *v < hi)
+            .collect();
+        self.bulk_delete(to_delete);
+    }
+
+    /// Delete every value `x` with `x >= lo`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::from_sorted_slice(&[1, 2, 3, 4, 5]);
+    /// tree.remove_range_from(4);
+    /// assert_eq!(vec![1, 2, 3], tree.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn remove_range_from(&mut self, lo: T) {
+        let to_delete: Vec<T> = self.iter_from(lo).collect();
+        self.bulk_delete(to_delete);
+    }
+
+    /// Delete every value `x` with `x <= hi`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::from_sorted_slice(&[1, 2, 3, 4, 5]);
+    /// tree.remove_range_to(2);
+    /// assert_eq!(vec![3, 4, 5], tree.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn remove_range_to(&mut self, hi: T) {
+        let to_delete: Vec<T> = self.iter().take_while(|v| *v <= hi).collect();
+        self.bulk_delete(to_delete);
+    }
+
+    /// Rebuild the tree from its values in O(n), which gives the minimum height
+    ///
+    /// Inserting and deleting keeps the tree within the red-black height bound,
+    /// but the tree can be higher than needed. The values are built again like
+    /// [from_sorted_slice](struct.FastRBTree.html#method.from_sorted_slice) does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// (0..100).for_each(|v| tree.insert(v * 37 % 100));
+    /// assert_eq!(10, tree.height());
+    /// tree.rebalance_from_scratch();
+    /// assert_eq!(8, tree.height());
+    /// assert_eq!(true, tree.is_valid_red_black_tree());
+    /// assert_eq!(true, tree.is_left_leaning());
+    /// ```
+    pub fn rebalance_from_scratch(&mut self) {
+        let values = self.to_sorted_vec();
+        self.replace_with(Self::from_sorted_slice(&values));
+    }
+
+    // Helper function for bulk_delete and rebalance_from_scratch,
+    // take the nodes of a rebuilt tree
+    fn replace_with(&mut self, mut rebuilt: FastRBTree<T>) {
+        self.clear();
+        self.root = rebuilt.root.take();
+        self.cached_height.set(rebuilt.cached_height.get());
+    }
+
+    /// Return the number of rotations done by insert and delete since the tree
+    /// was created or the count was reset. Only available with the `stats` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(1, tree.rotation_count());
+    /// tree.reset_rotation_count();
+    /// assert_eq!(0, tree.rotation_count());
+    /// ```
+    #[cfg(feature = "stats")]
+    #[must_use = "calling rotation_count without using the result has no effect"]
+    pub fn rotation_count(&self) -> u64 {
+        return self.rotation_count.get();
+    }
+
+    /// Set the rotation count back to 0. Only available with the `stats` feature.
+    #[cfg(feature = "stats")]
+    pub fn reset_rotation_count(&self) {
+        self.rotation_count.set(0);
+    }
+
+    // Add the rotations done on this thread since the given count
+    #[cfg(feature = "stats")]
+    fn add_rotations_since(&self, rotations: u64) {
+        let done = ROTATIONS.with(|r| r.get()) - rotations;
+        self.rotation_count.set(self.rotation_count.get() + done);
+    }
+
+    /// Return a deep copy of the tree as it is now
+    ///
+    /// The snapshot shares no node with the tree, so later inserts and deletes on
+    /// either one do not show up in the other: it is a point-in-time view. With the
+    /// `arc` feature a tree kept in `Arc<RwLock<FastRBTree<T>>>` can be read by taking
+    /// a snapshot under a short read lock, then reading the snapshot without the lock
+    /// while writers go on. The copy takes O(n).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// let snapshot = tree.snapshot();
+    /// tree.insert(2);
+    /// assert_eq!(false, snapshot.contains(2));
+    /// ```
+    #[must_use = "calling snapshot without using the result has no effect"]
+    pub fn snapshot(&self) -> FastRBTree<T> {
+        return self.clone();
+    }
+
+    /// Remove all values from the tree, returning them in order as an iterator
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// let values: Vec<u32> = tree.drain().collect();
+    /// assert_eq!(values, vec![1, 2]);
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        return Drain { tree: self };
+    }
+
+    /// Return a lazy in-order iterator which starts at the first value not less
+    /// than `start`. The iterator is positioned in O(log n), the values before
+    /// `start` are not visited.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// vec![10, 20, 30, 40].iter().for_each(|v| tree.insert(*v));
+    /// assert_eq!(vec![30, 40], tree.iter_from(25).collect::<Vec<_>>());
+    /// ```
+    #[must_use = "calling iter_from without using the result has no effect"]
+    pub fn iter_from(&self, start: T) -> InOrderIter<'_, T, TreeNode<T>> {
+        return InOrderIter::with_lower_bound(self.root.clone(), start);
+    }
+
+    /// Return a lazy iterator over the values in both trees, in ascending order
+    ///
+    /// The two trees are walked together in O(m + n) time and O(log m + log n)
+    /// space, no tree is built for the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let a = FastRBTree::from_sorted_slice(&[1, 2, 3, 4]);
+    /// let b = FastRBTree::from_sorted_slice(&[2, 4, 6]);
+    /// assert_eq!(vec![2, 4], a.intersection_iter(&b).collect::<Vec<_>>());
+    /// ```
+    #[must_use = "calling intersection_iter without using the result has no effect"]
+    pub fn intersection_iter<'a>(&'a self, other: &'a FastRBTree<T>) -> IntersectionIter<'a, T> {
+        return IntersectionIter {
+            first: self.iter().peekable(),
+            second: other.iter().peekable(),
+        };
+    }
+
+    /// Return a lazy iterator over the values in either tree, in ascending order
+    /// and without duplicates, see
+    /// [intersection_iter](struct.FastRBTree.html#method.intersection_iter)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let a = FastRBTree::from_sorted_slice(&[1, 2, 3, 4]);
+    /// let b = FastRBTree::from_sorted_slice(&[2, 4, 6]);
+    /// assert_eq!(vec![1, 2, 3, 4, 6], a.union_iter(&b).collect::<Vec<_>>());
+    /// ```
+    #[must_use = "calling union_iter without using the result has no effect"]
+    pub fn union_iter<'a>(&'a self, other: &'a FastRBTree<T>) -> UnionIter<'a, T> {
+        return UnionIter {
+            first: self.iter().peekable(),
+            second: other.iter().peekable(),
+        };
+    }
+
+    /// Return a lazy iterator over the values in this tree but not in the other
+    /// one, in ascending order, see
+    /// [intersection_iter](struct.FastRBTree.html#method.intersection_iter)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let a = FastRBTree::from_sorted_slice(&[1, 2, 3, 4]);
+    /// let b = FastRBTree::from_sorted_slice(&[2, 4, 6]);
+    /// assert_eq!(vec![1, 3], a.difference_iter(&b).collect::<Vec<_>>());
+    /// ```
+    #[must_use = "calling difference_iter without using the result has no effect"]
+    pub fn difference_iter<'a>(&'a self, other: &'a FastRBTree<T>) -> DifferenceIter<'a, T> {
+        return DifferenceIter {
+            first: self.iter().peekable(),
+            second: other.iter().peekable(),
+        };
+    }
+
+    /// Return a lazy iterator over the values in exactly one of the trees, in
+    /// ascending order, see
+    /// [intersection_iter](struct.FastRBTree.html#method.intersection_iter)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let a = FastRBTree::from_sorted_slice(&[1, 2, 3, 4]);
+    /// let b = FastRBTree::from_sorted_slice(&[2, 4, 6]);
+    /// assert_eq!(vec![1, 3, 6], a.symmetric_difference_iter(&b).collect::<Vec<_>>());
+    /// ```
+    #[must_use = "calling symmetric_difference_iter without using the result has no effect"]
+    pub fn symmetric_difference_iter<'a>(&'a self, other: &'a FastRBTree<T>) -> SymDiffIter<'a, T> {
+        return SymDiffIter {
+            first: self.iter().peekable(),
+            second: other.iter().peekable(),
+        };
+    }
+
+    // Remove all the nodes, used when a drain is dropped
+    fn clear(&mut self) {
+        self.cached_height.set(None);
+        match self.root.take() {
+            Some(node) => node.borrow_mut().clear(),
+            None => return,
+        }
+    }
+
+    // Helper function for the tests, run after insert and delete while no one
+    // else holds a node: every node is then owned by its parent or by the root
+    // field alone, so a strong count above 1 means a node is shared by two parents
+    #[cfg(test)]
+    fn check_cycle_free(&self) {
+        if let Some(root) = &self.root {
+            TreeNode::check_cycle_free(root);
+        }
+    }
+
+    // Helper function for height, walks the whole tree
+    fn compute_height(&self) -> u32 {
+        match self.root.clone() {
+            None => 0,
+            Some(node) => node.borrow().get_height(),
+        }
+    }
+
+    pub fn pre_order_traverse(&self, node: FastRBTreeNode<T>, container: &mut Vec<T>) {
+        container.push(node.borrow().value);
+        let left = node.borrow().left.clone();
+        if let Some(left) = left {
+            self.pre_order_traverse(left, container);
+        }
+        let right = node.borrow().right.clone();
+        if let Some(right) = right {
+            self.pre_order_traverse(right, container);
+        }
+    }
+
+    /// Return the color of the node storing the given value,
+    /// or None if the value is not in the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::{FastRBTree, NodeColor};
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// assert_eq!(Some(NodeColor::Black), tree.get_node_color(1));
+    /// assert_eq!(None, tree.get_node_color(2));
+    /// ```
+    #[must_use = "calling get_node_color without using the result has no effect"]
+    pub fn get_node_color(&self, value: T) -> Option<NodeColor> {
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let node_value = node.borrow().value;
+            if value == node_value {
+                return Some(node.borrow().color.clone());
+            } else if value < node_value {
+                current = node.borrow().left.clone();
+            } else {
+                current = node.borrow().right.clone();
+            }
+        }
+        return None;
+    }
+
+    /// Count the red and the black nodes of the tree in a single traversal,
+    /// returned as `(red, black)`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!((1, 1), tree.count_nodes_by_color());
+    /// ```
+    #[must_use = "calling count_nodes_by_color without using the result has no effect"]
+    pub fn count_nodes_by_color(&self) -> (usize, usize) {
+        let (mut red, mut black) = (0, 0);
+        if let Some(root) = self.root.clone() {
+            root.borrow().count_colors(&mut red, &mut black);
+        }
+        return (red, black);
+    }
+
+    /// Count the red nodes of the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(1, tree.count_red_nodes());
+    /// ```
+    #[must_use = "calling count_red_nodes without using the result has no effect"]
+    pub fn count_red_nodes(&self) -> usize {
+        return self.count_nodes_by_color().0;
+    }
+
+    /// Count the black nodes of the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(1, tree.count_black_nodes());
+    /// ```
+    #[must_use = "calling count_black_nodes without using the result has no effect"]
+    pub fn count_black_nodes(&self) -> usize {
+        return self.count_nodes_by_color().1;
+    }
+
+    /// Return the proportion of red nodes, 0 for an empty tree.
+    /// The tree is left-leaning, so a black node has at most one red child and every
+    /// red node can be paired with its own black parent; the result is therefore
+    /// never above one half.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(0.5, tree.red_fraction());
+    /// ```
+    #[must_use = "calling red_fraction without using the result has no effect"]
+    pub fn red_fraction(&self) -> f64 {
+        let (red, black) = self.count_nodes_by_color();
+        if red + black == 0 {
+            return 0.0;
+        }
+        return red as f64 / (red + black) as f64;
+    }
+
+    /// Return the number of nodes on the longest path from the root to a nil leaf
+    /// divided by the number on the shortest one, None for an empty tree.
+    /// A red-black tree keeps it at most 2.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+    /// assert_eq!(Some(1.0), tree.max_path_imbalance());
+    /// ```
+    #[must_use = "calling max_path_imbalance without using the result has no effect"]
+    pub fn max_path_imbalance(&self) -> Option<f64> {
+        match self.root.clone() {
+            None => None,
+            Some(root) => {
+                let (shortest, longest) = root.borrow().path_lengths();
+                Some(longest as f64 / shortest as f64)
+            }
+        }
+    }
+
+    /// Return every path from the root to a nil leaf as the values on the path,
+    /// from left to right
+    ///
+    /// A node without children ends two paths, one for each nil leaf. There are
+    /// n + 1 paths of O(log n) values each, so this is meant for small trees.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+    /// let paths = tree.get_all_paths_to_leaves();
+    /// assert_eq!(vec![vec![2, 1], vec![2, 1], vec![2, 3], vec![2, 3]], paths);
+    /// ```
+    #[must_use = "calling get_all_paths_to_leaves without using the result has no effect"]
+    pub fn get_all_paths_to_leaves(&self) -> Vec<Vec<T>> {
+        return self
+            .paths_to_leaves()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+    }
+
+    /// Return every path from the root to a leaf, where a leaf is a node with at
+    /// least one missing child, in preorder of the leaves
+    ///
+    /// Unlike [get_all_paths_to_leaves](struct.FastRBTree.html#method.get_all_paths_to_leaves),
+    /// which ends one path at each nil leaf, every node is listed at most once, so
+    /// there are `count_nodes() - count_full_nodes()` paths. There are O(n) paths of
+    /// O(log n) values each, so this is meant for small trees.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let mut tree = FastRBTree::new();
+    /// (1..=4).for_each(|v| tree.insert(v));
+    /// let paths = tree.all_root_to_leaf_paths();
+    /// assert_eq!(vec![vec![2, 1], vec![2, 4], vec![2, 4, 3]], paths);
+    /// assert_eq!(tree.count_nodes() - tree.count_full_nodes(), paths.len());
+    /// ```
+    #[must_use = "calling all_root_to_leaf_paths without using the result has no effect"]
+    pub fn all_root_to_leaf_paths(&self) -> Vec<Vec<T>> {
+        let mut paths = vec![];
+        if let Some(root) = &self.root {
+            root.borrow().collect_leaf_paths(&mut vec![], &mut paths);
+        }
+        return paths;
+    }
+
+    /// Return the number of black nodes on every path from the root to a nil leaf,
+    /// in the order of [get_all_paths_to_leaves](struct.FastRBTree.html#method.get_all_paths_to_leaves).
+    /// They are all equal in a valid red-black tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// assert_eq!(vec![1, 1, 1], tree.get_black_heights_of_all_paths());
+    /// ```
+    #[must_use = "calling get_black_heights_of_all_paths without using the result has no effect"]
+    pub fn get_black_heights_of_all_paths(&self) -> Vec<usize> {
+        return self
+            .paths_to_leaves()
+            .into_iter()
+            .map(|(_, black_height)| black_height)
+            .collect();
+    }
+
+    // Helper function for get_all_paths_to_leaves and get_black_heights_of_all_paths
+    fn paths_to_leaves(&self) -> Vec<(Vec<T>, usize)> {
+        let mut paths = vec![];
+        if let Some(root) = self.root.clone() {
+            root.borrow().collect_paths(&mut vec![], 0, &mut paths);
+        }
+        return paths;
+    }
+
+    /// Return the tree in DOT format, where the subtree rooted at the given value
+    /// is drawn with a thick blue border so that the red-black colors stay visible
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.insert(2);
+    /// tree.insert(3);
+    /// let dot = tree.to_dot_with_subtree_highlighted(1);
+    /// assert!(dot.starts_with("digraph FastRBTree {"));
+    /// ```
+    #[must_use = "calling to_dot_with_subtree_highlighted without using the result has no effect"]
+    pub fn to_dot_with_subtree_highlighted(&self, value: T) -> String {
+        let mut dot = String::from("digraph FastRBTree {\n");
+        dot += "    node [style=filled, fontcolor=white];\n";
+        if let Some(root) = self.root.clone() {
+            root.borrow().to_dot_helper(value, false, &mut dot);
+        }
+        dot += "}\n";
+        return dot;
+    }
+
+    /// Return true if no node has a red right child
+    ///
+    /// This is the extra invariant of a Left-Leaning Red-Black tree, which keeps
+    /// every 3-node of the matching 2-3 tree as a black node with a red left child.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// (1..=10).for_each(|v| tree.insert(v));
+    /// assert_eq!(true, tree.is_left_leaning());
+    /// ```
+    #[must_use = "calling is_left_leaning without using the result has no effect"]
+    pub fn is_left_leaning(&self) -> bool {
+        return !TreeNode::has_red_right_child(self.root.clone());
+    }
+
+    /// Judge if the tree under `root` is a valid red-black tree, replaced by
+    /// [is_valid_subtree](struct.FastRBTree.html#method.is_valid_subtree) so that it no longer
+    /// shares its name with the trait method
+    #[deprecated(since = "0.1.9", note = "use is_valid_subtree instead")]
+    #[must_use = "calling is_valid_red_black_tree without using the result has no effect"]
+    pub fn is_valid_red_black_tree(root: OptionFastRBTreeNode<T>) -> bool {
+        return FastRBTree::is_valid_subtree(root);
+    }
+
+    /// Return true if the tree under `root` keeps the binary search tree property
+    /// and the red-black tree properties, the same check as
+    /// [is_valid_red_black_tree](../common_trait/trait.CommonTreeTrait.html#method.is_valid_red_black_tree)
+    /// on a whole FastRBTree
+    #[must_use = "calling is_valid_subtree without using the result has no effect"]
+    pub fn is_valid_subtree(root: OptionFastRBTreeNode<T>) -> bool {
+        let is_bst = match &root {
+            None => true,
+            Some(node) => node.borrow().is_valid_bst(None, None),
+        };
+        return is_bst
+            && TreeNode::get_color(&root) == NodeColor::Black
+            && !TreeNode::has_red_violation(root.clone())
+            && TreeNode::calculate_black_height(root).is_some();
+    }
+}
+
+/// Error returned by [FastRBTree.from_newick](struct.FastRBTree.html#method.from_newick)
+/// for malformed input, with a message describing the problem
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError(pub String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid Newick string: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Error returned by [FastRBTree.from_sorted_dedup](struct.FastRBTree.html#method.from_sorted_dedup)
+/// when the input is not strictly increasing, holding the index of the first value
+/// which is not greater than the one before it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NotSortedError {
+    pub index: usize,
+}
+
+impl fmt::Display for NotSortedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "input is not sorted without duplicates at index {}",
+            self.index
+        )
+    }
+}
+
+impl std::error::Error for NotSortedError {}
+
+/// Subtrees with fewer values are built on the current thread
+#[cfg(feature = "rayon")]
+const PARALLEL_BUILD_THRESHOLD: usize = 4096;
+
+#[cfg(feature = "rayon")]
+impl<T: Ord + Copy + Debug + Display + Send + Sync> FastRBTree<T> {
+    /// Build a red-black tree from a sorted slice without duplicates like
+    /// [from_sorted_slice](struct.FastRBTree.html#method.from_sorted_slice), but the
+    /// two halves of large subtrees are built in parallel with `rayon::join`.
+    /// The resulting tree has the same shape as the one from `from_sorted_slice`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let data: Vec<u32> = (0..100_000).collect();
+    /// let tree = FastRBTree::parallel_from_sorted_slice(&data);
+    /// assert_eq!(true, tree.is_valid_red_black_tree());
+    /// assert_eq!(Some(99_999), tree.max());
+    /// ```
+    pub fn parallel_from_sorted_slice(data: &[T]) -> Self {
+        let root =
+            TreeNode::parallel_build_from_sorted(data, Self::sorted_black_height(data.len()));
+        return Self::from_built_root(root);
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display + FromStr> FastRBTree<T> {
+    /// Build a red-black tree from a Newick string, as produced by
+    /// [to_newick](../common_trait/trait.CommonTreeTrait.html#method.to_newick).
+    /// A trailing `;` is accepted. The labels must be in binary search tree order,
+    /// the tree is rebuilt balanced from them, so the shape may differ from the input.
+    /// Subtrees nested more than `NEWICK_MAX_DEPTH` (1000) levels deep are rejected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = FastRBTree::<u32>::from_newick("((1,3)2,(5,7)6)4;").unwrap();
+    /// assert_eq!(true, tree.contains(5));
+    /// assert!(FastRBTree::<u32>::from_newick("((1,3)2,(5,7)6").is_err());
+    /// ```
+    pub fn from_newick(s: &str) -> Result<Self, ParseError> {
+        let input = s.trim();
+        let input = input.strip_suffix(';').unwrap_or(input);
+        let mut values = vec![];
+        if input.is_empty() {
+            return Ok(FastRBTree::new());
+        }
+        let mut pos = 0;
+        if !parse_newick_subtree(input, &mut pos, &mut values, 0)? {
+            return Err(ParseError(String::from("missing root label")));
+        }
+        if pos != input.len() {
+            return Err(ParseError(format!(
+                "unexpected character at position {}",
+                pos
+            )));
+        }
+        if values.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(ParseError(String::from(
+                "labels are not in binary search tree order",
+            )));
+        }
+        return Ok(FastRBTree::from_sorted_slice(&values));
+    }
+
+    /// Build a red-black tree from a file with one value per line,
+    /// empty lines are skipped. Only available with the `io` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let tree = FastRBTree::<u32>::from_csv("values.csv").unwrap();
+    /// ```
+    #[cfg(feature = "io")]
+    pub fn from_csv(path: &str) -> std::io::Result<Self> {
+        use std::io::BufRead;
+
+        let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut tree = FastRBTree::new();
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.parse::<T>() {
+                Ok(value) => tree.insert(value),
+                Err(_) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("invalid value '{}'", line),
+                    ))
+                }
+            }
+        }
+        return Ok(tree);
+    }
+
+    /// Write the values of the tree in order to a file, one value per line.
+    /// Only available with the `io` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tree_collections::rb_tree_fast::FastRBTree;
+    /// let mut tree = FastRBTree::new();
+    /// tree.insert(1);
+    /// tree.to_csv("values.csv").unwrap();
+    /// ```
+    #[cfg(feature = "io")]
+    pub fn to_csv(&self, path: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        for value in self.iter() {
+            writeln!(writer, "{}", value)?;
+        }
+        return writer.flush();
+    }
+}
+
+/// Deepest nesting accepted by `from_newick`, so that the recursive parser
+/// cannot overflow the stack on an input like `"(((((..."`
+const NEWICK_MAX_DEPTH: usize = 1000;
+
+// Parse the subtree starting at pos and push its values in order,
+// return false if the subtree is an empty child
+fn parse_newick_subtree<T: FromStr>(
+    input: &str,
+    pos: &mut usize,
+    values: &mut Vec<T>,
+    depth: usize,
+) -> Result<bool, ParseError> {
+    let bytes = input.as_bytes();
+    let mut right_values = vec![];
+    let mut has_children = false;
+    if *pos < bytes.len() && bytes[*pos] == b'(' {
+        if depth >= NEWICK_MAX_DEPTH {
+            return Err(ParseError(String::from("nesting too deep")));
+        }
+        *pos += 1;
+        let has_left = parse_newick_subtree(input, pos, values, depth + 1)?;
+        if *pos >= bytes.len() || bytes[*pos] != b',' {
+            return Err(ParseError(format!("expected ',' at position {}", pos)));
+        }
+        *pos += 1;
+        let has_right = parse_newick_subtree(input, pos, &mut right_values, depth + 1)?;
+        if *pos >= bytes.len() || bytes[*pos] != b')' {
+            return Err(ParseError(format!("expected ')' at position {}", pos)));
+        }
+        *pos += 1;
+        if !has_left && !has_right {
+            return Err(ParseError(format!(
+                "empty children before position {}",
+                pos
+            )));
+        }
+        has_children = true;
+    }
+
+    let start = *pos;
+    while *pos < bytes.len() && !matches!(bytes[*pos], b'(' | b')' | b',' | b';') {
+        *pos += 1;
+    }
+    let label = input[start..*pos].trim();
+    if label.is_empty() {
+        if has_children {
+            return Err(ParseError(format!("missing label at position {}", start)));
+        }
+        return Ok(false);
+    }
+    match label.parse::<T>() {
+        Ok(value) => values.push(value),
+        Err(_) => return Err(ParseError(format!("invalid value '{}'", label))),
+    }
+    values.append(&mut right_values);
+    return Ok(true);
+}
+
+/// Implementations of TreeNode
+// TreeNode
+impl<T: Ord + Copy + Debug + Display> TreeNode<T> {
+    // Helper function for Clone, copy every node of the subtree
+    fn deep_clone(node: &OptionFastRBTreeNode<T>) -> OptionFastRBTreeNode<T> {
+        let node = node.as_ref()?.borrow();
+        return Some(Shared::new(NodeCell::new(TreeNode {
+            color: node.color.clone(),
+            value: node.value,
+            left: Self::deep_clone(&node.left),
+            right: Self::deep_clone(&node.right),
+        })));
+    }
+
+    /// Create a new node
+    /// ,which will be called by [FastRBTree](struct.FastRBTree.html)
+    fn new(value: T) -> Self {
+        TreeNode {
+            color: NodeColor::Red,
+            value,
+            left: None,
+            right: None,
+        }
+    }
+
+    // Build a node with the given color and children, so that tests can
+    // construct any shape, including invalid ones
+    #[cfg(test)]
+    pub(crate) fn new_with_children(
+        value: T,
+        color: NodeColor,
+        left: OptionFastRBTreeNode<T>,
+        right: OptionFastRBTreeNode<T>,
+    ) -> FastRBTreeNode<T> {
+        Shared::new(NodeCell::new(TreeNode {
+            color,
+            value,
+            left,
+            right,
+        }))
+    }
+
+    /// Build a subtree of the given black height from sorted data,
+    /// which will be called by
+    /// [FastRBTree.from_sorted_slice](struct.FastRBTree.html#method.from_sorted_slice)
+    // The subtree is built as a 2-3 tree, a 2-node is a single black node and
+    // a 3-node is a black node with a red left child, so all red links lean left.
+    // The values are between 2^h - 1 and 2^(h + 1) - 2 for black height h, the ones
+    // above 2^h - 1 go into red left children so that no path has two of them.
+    fn build_from_sorted(data: &[T], black_height: u32) -> OptionFastRBTreeNode<T> {
+        if black_height == 0 {
+            return None;
+        }
+        let (mid, red_index) = Self::split_sorted(data.len(), black_height);
+        let first = match red_index {
+            None => {
+                // 2-node
+                let mut node = TreeNode::new(data[mid]);
+                node.color = NodeColor::Black;
+                node.left = Self::build_from_sorted(&data[..mid], black_height - 1);
+                node.right = Self::build_from_sorted(&data[mid + 1..], black_height - 1);
+                return Some(Shared::new(NodeCell::new(node)));
+            }
+            Some(first) => first,
+        };
+        // 3-node, the other values are split into three children
+        let mut red = TreeNode::new(data[first]);
+        red.left = Self::build_from_sorted(&data[..first], black_height - 1);
+        red.right = Self::build_from_sorted(&data[first + 1..mid], black_height - 1);
+        let mut node = TreeNode::new(data[mid]);
+        node.color = NodeColor::Black;
+        node.left = Some(Shared::new(NodeCell::new(red)));
+        node.right = Self::build_from_sorted(&data[mid + 1..], black_height - 1);
+        return Some(Shared::new(NodeCell::new(node)));
+    }
+
+    // Helper function for building from sorted data
+    // return the index of the black root of the subtree, and the index of
+    // its red left child if the root is a 3-node
+    fn split_sorted(len: usize, black_height: u32) -> (usize, Option<usize>) {
+        // size of an all-black child subtree
+        let half = (1usize << (black_height - 1)) - 1;
+        let extra = len - (2 * half + 1);
+        if extra <= half {
+            // 2-node, the extra values are shared between the children
+            return (half + extra.div_ceil(2), None);
+        }
+        // 3-node, the red child and its children are all-black below it,
+        // the right child takes the remaining extra values
+        return (2 * half + 1, Some(half));
+    }
+
+    // Parallel version of build_from_sorted, the children of large subtrees
+    // are built on the rayon thread pool
+    #[cfg(feature = "rayon")]
+    fn parallel_build_from_sorted(data: &[T], black_height: u32) -> OptionFastRBTreeNode<T>
+    where
+        T: Send + Sync,
+    {
+        if data.len() < PARALLEL_BUILD_THRESHOLD {
+            return Self::build_from_sorted(data, black_height);
+        }
+        let build = |part: &[T]| Self::parallel_build_from_sorted(part, black_height - 1);
+        let (mid, red_index) = Self::split_sorted(data.len(), black_height);
+        let mut node = TreeNode::new(data[mid]);
+        node.color = NodeColor::Black;
+        match red_index {
+            None => {
+                let (left, right) =
+                    rayon_crate::join(|| build(&data[..mid]), || build(&data[mid + 1..]));
+                node.left = left;
+                node.right = right;
+            }
+            Some(first) => {
+                let (red_left, (red_right, right)) = rayon_crate::join(
+                    || build(&data[..first]),
+                    || {
+                        rayon_crate::join(
+                            || build(&data[first + 1..mid]),
+                            || build(&data[mid + 1..]),
+                        )
+                    },
+                );
+                let mut red = TreeNode::new(data[first]);
+                red.left = red_left;
+                red.right = red_right;
+                node.left = Some(Shared::new(NodeCell::new(red)));
+                node.right = right;
+            }
+        }
+        return Some(Shared::new(NodeCell::new(node)));
+    }
+
+    /// Insert data into the subtree, performs any rotations
+    /// necessary to maintain banlance, and then returns the new root to this subtree
+    /// , which will be called by
+    /// [FastRBTree.insert](struct.FastRBTree.html#method.insert)
+    fn node_insert(node: OptionFastRBTreeNode<T>, insert_value: T) -> OptionFastRBTreeNode<T> {
+        // if h is none, then return the first node
+        match node {
+            None => return Some(Shared::new(NodeCell::new(TreeNode::new(insert_value)))),
+            Some(n) => {
+                // compare with root
+                let node_value = n.borrow().value;
+                if insert_value < node_value {
+                    // insert to left, the child is taken out so that it is not shared
+                    let left = n.borrow_mut().left.take();
+                    n.borrow_mut().left = Self::node_insert(left, insert_value);
+                } else if insert_value > node_value {
+                    // insert to right
+                    let right = n.borrow_mut().right.take();
+                    n.borrow_mut().right = Self::node_insert(right, insert_value);
+                } else {
+                    // insert here
+                    n.borrow_mut().value = insert_value; // equal, update value
+                }
+                // maintain the tree by the RB tree rule
+                return Self::maintain(n);
+            }
+        }
+    }
+
+    /// Delete data from the subtree, performs any rotations
+    /// necessary to maintain banlance, and then returns the new root to this subtree
+    /// , which will be called by
+    /// [FastRBTree.delete](struct.FastRBTree.html#method.delete)
+    fn node_delete(node: OptionFastRBTreeNode<T>, delete_value: T) -> OptionFastRBTreeNode<T> {
+        match node {
+            None => {
+                // delete checks that the value is in the tree first
+                handle_not_found();
+                return None;
+            }
+            Some(mut n) => {
+                let node_value = n.borrow().value;
+                if delete_value < node_value {
+                    if Self::is_black_with_black_left(&n.borrow().left) {
+                        n = Self::move_red_left(n);
+                    }
+                    let left = n.borrow_mut().left.take();
+                    n.borrow_mut().left = Self::node_delete(left, delete_value);
+                } else {
+                    if Self::is_red(&n.borrow().left) {
+                        n = Self::right_rotate(n);
+                    }
+                    // the rotation may have changed the root of this subtree
+                    let node_value = n.borrow().value;
+                    if delete_value == node_value && n.borrow().right.is_none() {
+                        return None;
+                    }
+                    if Self::is_black_with_black_left(&n.borrow().right) {
+                        n = Self::move_red_right(n);
+                    }
+                    if delete_value == n.borrow().value {
+                        // Find the value of node A which is the minimum value of the right subtree
+                        let min_value = n
+                            .borrow()
+                            .right
+                            .as_ref()
+                            .unwrap()
+                            .borrow()
+                            .get_min_value_in_children();
+                        n.borrow_mut().value = min_value; // Change the value of node n to the value of node A.
+                        let right = n.borrow_mut().right.take();
+                        n.borrow_mut().right = Self::node_delete(right, min_value);
+                    // Delete the node A in the right subtree.
+                    } else {
+                        let right = n.borrow_mut().right.take();
+                        n.borrow_mut().right = Self::node_delete(right, delete_value);
+                    }
+                }
+                return Self::maintain(n);
+            }
+        };
+    }
+
+    /// Repair the coloring from inserting or deleting into a tree.
+    fn maintain(node: FastRBTreeNode<T>) -> OptionFastRBTreeNode<T> {
+        // if right is red and left is black, then left rotate
+        if Self::is_red(&node.borrow().right) && Self::is_black(&node.borrow().left) {
+            let temp1 = Self::left_rotate(node);
+            // if left and left's left are both red, then right rotate
+            if Self::is_red_with_red_left(&temp1.borrow().left) {
+                let temp2 = Self::right_rotate(temp1);
+                // if left and right are both red, then change color
+                if Self::has_red_children(&temp2) {
+                    Self::flip_color(&temp2);
+                }
+                return Some(temp2);
+            }
+            return Some(temp1);
+        }
+
+        // if left and left's left are both red, then right rotate
+        if Self::is_red_with_red_left(&node.borrow().left) {
+            let temp1 = Self::right_rotate(node);
+            // if left and right are both red, then change color
+            if Self::has_red_children(&temp1) {
+                Self::flip_color(&temp1);
+            }
+            return Some(temp1);
+        }
+
+        // if left and right are both red, then change color
+        if Self::has_red_children(&node) {
+            Self::flip_color(&node);
+        }
+
+        return Some(node);
+    }
+
+    fn move_red_left(node: FastRBTreeNode<T>) -> FastRBTreeNode<T> {
+        let mut n = node;
+        Self::flip_color(&n);
+        if Self::is_red(&n.borrow().right.as_ref().unwrap().borrow().left) {
+            let right = n.borrow_mut().right.take();
+            n.borrow_mut().right = Some(Self::right_rotate(right.unwrap()));
+            n = Self::left_rotate(n);
+            Self::flip_color(&n);
+        }
+
+        return n;
+    }
+
+    fn move_red_right(node: FastRBTreeNode<T>) -> FastRBTreeNode<T> {
+        let mut n = node;
+        Self::flip_color(&n);
+        if Self::is_red(&n.borrow().left.as_ref().unwrap().borrow().left) {
+            n = Self::right_rotate(n);
+            Self::flip_color(&n);
+        }
+
+        return n;
+    }
+
+    // left and right rotate
+    // node is the root of the subtree
+
+    /// Rotate the subtree rooted at this node to the left and
+    /// return the new root to this subtree.
+    fn left_rotate(node: FastRBTreeNode<T>) -> FastRBTreeNode<T> {
+        #[cfg(feature = "stats")]
+        ROTATIONS.with(|r| r.set(r.get() + 1));
+        let node_right = node.borrow_mut().right.take().unwrap();
+        // left rotate
+        node.borrow_mut().right = node_right.borrow_mut().left.take();
+        node_right.borrow_mut().color = node.borrow().color.clone();
+        node.borrow_mut().color = NodeColor::Red;
+        node_right.borrow_mut().left = Some(node);
+        return node_right;
+    }
+
+    /// Rotate the subtree rooted at this node to the right and
+    /// returns the new root to this subtree.
+    fn right_rotate(node: FastRBTreeNode<T>) -> FastRBTreeNode<T> {
+        #[cfg(feature = "stats")]
+        ROTATIONS.with(|r| r.set(r.get() + 1));
+        let node_left = node.borrow_mut().left.take().unwrap();
+        // right rotate
+        node.borrow_mut().left = node_left.borrow_mut().right.take();
+        node_left.borrow_mut().color = node.borrow().color.clone();
+        node.borrow_mut().color = NodeColor::Red;
+        node_left.borrow_mut().right = Some(node);
+        return node_left;
+    }
+
+    #[inline(always)]
+    fn is_red(node: &OptionFastRBTreeNode<T>) -> bool {
+        return Self::get_color(node) == NodeColor::Red;
+    }
+
+    #[inline(always)]
+    fn is_black(node: &OptionFastRBTreeNode<T>) -> bool {
+        return Self::get_color(node) == NodeColor::Black;
+    }
+
+    // Helper function for maintaining
+    // true if the node and its left child are both red
+    #[inline]
+    fn is_red_with_red_left(node: &OptionFastRBTreeNode<T>) -> bool {
+        match node {
+            None => false,
+            Some(node) => {
+                let node = node.borrow();
+                node.color == NodeColor::Red && Self::is_red(&node.left)
+            }
+        }
+    }
+
+    // Helper function for deleting
+    // true if the node and its left child are both black, None counts as black
+    #[inline]
+    fn is_black_with_black_left(node: &OptionFastRBTreeNode<T>) -> bool {
+        match node {
+            None => true,
+            Some(node) => {
+                let node = node.borrow();
+                node.color == NodeColor::Black && Self::is_black(&node.left)
+            }
+        }
+    }
+
+    // Helper function for maintaining
+    // true if both children of the node are red
+    #[inline]
+    fn has_red_children(node: &FastRBTreeNode<T>) -> bool {
+        let node = node.borrow();
+        return Self::is_red(&node.left) && Self::is_red(&node.right);
+    }
+
+    // Helper function for maintaining
+    // flip the colors of the node and its two children, so that it also
+    // works for move_red_left and move_red_right when deleting
+    fn flip_color(node: &FastRBTreeNode<T>) {
+        {
+            let n = node.borrow();
+            Self::reverse_color(n.left.as_ref().unwrap());
+            Self::reverse_color(n.right.as_ref().unwrap());
+        }
+        Self::reverse_color(node);
+    }
+
+    // Helper function for maintaining
+    #[inline]
+    fn reverse_color(node: &FastRBTreeNode<T>) {
+        let mut node = node.borrow_mut();
+        node.color = match node.color {
+            NodeColor::Red => NodeColor::Black,
+            NodeColor::Black => NodeColor::Red,
+        };
+    }
+
+    // Helper function for maintaining
+    // make None to be real leaves with black color
+    #[inline(always)]
+    fn get_color(node: &OptionFastRBTreeNode<T>) -> NodeColor {
+        match node {
+            None => NodeColor::Black,
+            Some(node) => node.borrow().color.clone(),
+        }
+    }
+
+    // Helper function for FastRBTree::is_valid_subtree
+    // Return true if a red node has a red child
+    fn has_red_violation(node: OptionFastRBTreeNode<T>) -> bool {
+        match node {
+            None => false,
+            Some(node) => {
+                let left = node.borrow().left.clone();
+                let right = node.borrow().right.clone();
+                if node.borrow().color == NodeColor::Red
+                    && (Self::get_color(&left) == NodeColor::Red
+                        || Self::get_color(&right) == NodeColor::Red)
+                {
+                    return true;
+                }
+                Self::has_red_violation(left) || Self::has_red_violation(right)
+            }
+        }
+    }
+
+    // Helper function for FastRBTree::is_left_leaning
+    fn has_red_right_child(node: OptionFastRBTreeNode<T>) -> bool {
+        match node {
+            None => false,
+            Some(node) => {
+                let left = node.borrow().left.clone();
+                let right = node.borrow().right.clone();
+                if Self::is_red(&right) {
+                    return true;
+                }
+                Self::has_red_right_child(left) || Self::has_red_right_child(right)
+            }
+        }
+    }
+
+    // Helper function for FastRBTree::is_valid_subtree
+    fn calculate_black_height(node: OptionFastRBTreeNode<T>) -> Option<usize> {
+        match node {
+            None => Some(1),
+            Some(node) => {
+                let left_height = Self::calculate_black_height(node.borrow().left.clone());
+                let right_height = Self::calculate_black_height(node.borrow().right.clone());
+                match (left_height, right_height) {
+                    (Some(left_height), Some(right_height)) => {
+                        if left_height != right_height {
+                            //The 2 children have unequal depths
+                            None
+                        } else {
+                            let node_color = &node.borrow().color;
+                            //Return the black depth of children,plus 1 if the node is black
+                            match node_color {
+                                NodeColor::Red => Some(left_height),
+                                NodeColor::Black => Some(left_height + 1),
+                            }
+                        }
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    // Helper function for FastRBTree::check_cycle_free
+    // the nodes are borrowed, not cloned, so that the counts are not changed
+    #[cfg(test)]
+    fn check_cycle_free(node: &FastRBTreeNode<T>) {
+        assert_eq!(
+            Shared::strong_count(node),
+            1,
+            "node {} has more than one owner",
+            node.borrow().value
+        );
+        let node = node.borrow();
+        if let Some(left) = &node.left {
+            Self::check_cycle_free(left);
+        }
+        if let Some(right) = &node.right {
+            Self::check_cycle_free(right);
+        }
+    }
+
+    // Helper function for counting the nodes by color in order
+    fn count_colors(&self, red: &mut usize, black: &mut usize) {
+        if let Some(left) = self.left.clone() {
+            left.borrow().count_colors(red, black);
+        }
+        match self.color {
+            NodeColor::Red => *red += 1,
+            NodeColor::Black => *black += 1,
+        }
+        if let Some(right) = self.right.clone() {
+            right.borrow().count_colors(red, black);
+        }
+    }
+
+    // Return the number of nodes on the shortest and the longest path
+    // from this node to a nil leaf
+    fn path_lengths(&self) -> (usize, usize) {
+        let subtree_lengths = |child: OptionFastRBTreeNode<T>| match child {
+            None => (0, 0),
+            Some(node) => node.borrow().path_lengths(),
+        };
+        let (left_shortest, left_longest) = subtree_lengths(self.left.clone());
+        let (right_shortest, right_longest) = subtree_lengths(self.right.clone());
+        return (
+            left_shortest.min(right_shortest) + 1,
+            left_longest.max(right_longest) + 1,
+        );
+    }
+
+    // Helper function for FastRBTree::paths_to_leaves
+    // push the path and its black nodes count once for each nil child
+    fn collect_paths(&self, path: &mut Vec<T>, black: usize, paths: &mut Vec<(Vec<T>, usize)>) {
+        path.push(self.value);
+        let black = match self.color {
+            NodeColor::Red => black,
+            NodeColor::Black => black + 1,
+        };
+        for child in [self.left.clone(), self.right.clone()] {
+            match child {
+                None => paths.push((path.clone(), black)),
+                Some(node) => node.borrow().collect_paths(path, black, paths),
+            }
+        }
+        path.pop();
+    }
+
+    // Helper function for FastRBTree::all_root_to_leaf_paths
+    // push the path once if the node has a missing child
+    fn collect_leaf_paths(&self, path: &mut Vec<T>, paths: &mut Vec<Vec<T>>) {
+        path.push(self.value);
+        if self.left.is_none() || self.right.is_none() {
+            paths.push(path.clone());
+        }
+        for child in [&self.left, &self.right].iter().copied().flatten() {
+            child.borrow().collect_leaf_paths(path, paths);
+        }
+        path.pop();
+    }
+
+    // Helper function for FastRBTree::to_dot_with_subtree_highlighted
+    fn to_dot_helper(&self, highlight_value: T, highlighted: bool, dot: &mut String) {
+        let highlighted = highlighted || self.value == highlight_value;
+        let fill_color = match self.color {
+            NodeColor::Red => "red",
+            NodeColor::Black => "black",
+        };
+        if highlighted {
+            *dot += &format!(
+                "    \"{}\" [fillcolor={}, color=blue, penwidth=3];\n",
+                self.value, fill_color
+            );
+        } else {
+            *dot += &format!("    \"{}\" [fillcolor={}];\n", self.value, fill_color);
+        }
+        for child in [self.left.clone(), self.right.clone()].iter().flatten() {
+            *dot += &format!("    \"{}\" -> \"{}\";\n", self.value, child.borrow().value);
+            child
+                .borrow()
+                .to_dot_helper(highlight_value, highlighted, dot);
+        }
+    }
+
+    fn clear(&mut self) {
+        match self.left.take() {
+            None => {}
+            Some(node) => {
+                node.borrow_mut().clear();
+            }
+        }
+        self.left = None;
+        match self.right.take() {
+            None => {}
+            Some(node) => {
+                node.borrow_mut().clear();
+            }
+        }
+        self.right = None;
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Drop for FastRBTree<T> {
+    fn drop(&mut self) {
+        match self.root.take() {
+            Some(node) => node.borrow_mut().clear(),
+            None => return,
+        }
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Drop for TreeNode<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// A draining iterator over a [FastRBTree](struct.FastRBTree.html),
+/// created by [FastRBTree.drain](struct.FastRBTree.html#method.drain)
+///
+/// The values are yielded in order and removed from the tree one by one.
+/// If the iterator is dropped early, the remaining values are removed as well.
+pub struct Drain<'a, T: Ord + Copy + Debug + Display> {
+    tree: &'a mut FastRBTree<T>,
+}
+
+impl<'a, T: Ord + Copy + Debug + Display> Iterator for Drain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self.tree.min() {
+            None => return None,
+            Some(value) => {
+                self.tree.delete(value);
+                return Some(value);
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord + Copy + Debug + Display> Drop for Drain<'a, T> {
+    fn drop(&mut self) {
+        self.tree.clear();
+    }
+}
+
+/// A lazy iterator over the values in both of two trees, created by
+/// [FastRBTree.intersection_iter](struct.FastRBTree.html#method.intersection_iter)
+pub struct IntersectionIter<'a, T: Ord + Copy + Debug + Display> {
+    first: Peekable<InOrderIter<'a, T, TreeNode<T>>>,
+    second: Peekable<InOrderIter<'a, T, TreeNode<T>>>,
+}
+
+impl<'a, T: Ord + Copy + Debug + Display> Iterator for IntersectionIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        // skip the smaller value until both sides agree
+        while let (Some(a), Some(b)) = (self.first.peek(), self.second.peek()) {
+            if a < b {
+                self.first.next();
+            } else if a > b {
+                self.second.next();
+            } else {
+                self.second.next();
+                return self.first.next();
+            }
+        }
+        return None;
+    }
+}
+
+/// A lazy iterator over the values in either of two trees, created by
+/// [FastRBTree.union_iter](struct.FastRBTree.html#method.union_iter)
+pub struct UnionIter<'a, T: Ord + Copy + Debug + Display> {
+    first: Peekable<InOrderIter<'a, T, TreeNode<T>>>,
+    second: Peekable<InOrderIter<'a, T, TreeNode<T>>>,
+}
+
+impl<'a, T: Ord + Copy + Debug + Display> Iterator for UnionIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match (self.first.peek(), self.second.peek()) {
+            (Some(a), Some(b)) => {
+                if a < b {
+                    return self.first.next();
+                } else if a > b {
+                    return self.second.next();
+                }
+                // a value in both trees is yielded once
+                self.second.next();
+                return self.first.next();
+            }
+            (Some(_), None) => return self.first.next(),
+            (None, _) => return self.second.next(),
+        }
+    }
+}
+
+/// A lazy iterator over the values in the first of two trees but not in the
+/// second one, created by
+/// [FastRBTree.difference_iter](struct.FastRBTree.html#method.difference_iter)
+pub struct DifferenceIter<'a, T: Ord + Copy + Debug + Display> {
+    first: Peekable<InOrderIter<'a, T, TreeNode<T>>>,
+    second: Peekable<InOrderIter<'a, T, TreeNode<T>>>,
+}
+
+impl<'a, T: Ord + Copy + Debug + Display> Iterator for DifferenceIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(a) = self.first.peek() {
+            match self.second.peek() {
+                Some(b) if b < a => {
+                    self.second.next();
+                }
+                Some(b) if b == a => {
+                    self.first.next();
+                    self.second.next();
+                }
+                _ => return self.first.next(),
+            }
+        }
+        return None;
+    }
+}
+
+/// A lazy iterator over the values in exactly one of two trees, created by
+/// [FastRBTree.symmetric_difference_iter](struct.FastRBTree.html#method.symmetric_difference_iter)
+pub struct SymDiffIter<'a, T: Ord + Copy + Debug + Display> {
+    first: Peekable<InOrderIter<'a, T, TreeNode<T>>>,
+    second: Peekable<InOrderIter<'a, T, TreeNode<T>>>,
+}
+
+impl<'a, T: Ord + Copy + Debug + Display> Iterator for SymDiffIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            match (self.first.peek(), self.second.peek()) {
+                (Some(a), Some(b)) => {
+                    if a < b {
+                        return self.first.next();
+                    } else if a > b {
+                        return self.second.next();
+                    }
+                    // a value in both trees is skipped
+                    self.first.next();
+                    self.second.next();
+                }
+                (Some(_), None) => return self.first.next(),
+                (None, _) => return self.second.next(),
+            }
+        }
+    }
+}
+
+/// A builder for [FastRBTree](struct.FastRBTree.html), created by
+/// [FastRBTree.builder](struct.FastRBTree.html#method.builder)
+///
+/// The values are sorted when `build` is called, and only one of several equal
+/// values is kept: the last one given, as repeated calls to
+/// [insert](struct.FastRBTree.html#method.insert) would keep, unless
+/// [keep_first](struct.TreeBuilder.html#method.keep_first) is set.
+#[derive(Clone, Debug)]
+pub struct TreeBuilder<T: Ord + Copy + Debug + Display> {
+    values: Vec<T>,
+    keep_first: bool,
+}
+
+impl<T: Ord + Copy + Debug + Display> TreeBuilder<T> {
+    /// Create a builder without values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_fast::TreeBuilder;
+    /// let tree = TreeBuilder::<u32>::new().build();
+    /// ```
+    pub fn new() -> Self {
+        return TreeBuilder {
+            values: vec![],
+            keep_first: false,
+        };
+    }
+
+    /// Add a value to the tree to build
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = FastRBTree::builder().with_value(2).with_value(1).build();
+    /// assert_eq!(vec![1, 2], tree.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn with_value(mut self, value: T) -> Self {
+        self.values.push(value);
+        return self;
+    }
+
+    /// Add every value of an iterator to the tree to build
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = FastRBTree::builder().with_values(0..10).with_value(42).build();
+    /// assert_eq!(11, tree.iter().count());
+    /// ```
+    pub fn with_values<I: IntoIterator<Item = T>>(mut self, iter: I) -> Self {
+        self.values.extend(iter);
+        return self;
+    }
+
+    /// Choose which of several equal values is kept: the first one given with
+    /// `true`, as repeated calls to
+    /// [insert_if_absent](struct.FastRBTree.html#method.insert_if_absent) would do,
+    /// or the last one with `false`, which is the default and matches
+    /// [insert](struct.FastRBTree.html#method.insert)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = FastRBTree::builder().keep_first(true).with_values(vec![1, 1]).build();
+    /// assert_eq!(1, tree.iter().count());
+    /// ```
+    pub fn keep_first(mut self, keep_first: bool) -> Self {
+        self.keep_first = keep_first;
+        return self;
+    }
+
+    /// Build the tree in O(n log n), by sorting the values and building from them
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::prelude::*;
+    /// let tree = FastRBTree::builder().with_values(vec![3, 1, 2]).build();
+    /// assert_eq!(true, tree.is_valid_red_black_tree());
+    /// ```
+    pub fn build(self) -> FastRBTree<T> {
+        let mut values = self.values;
+        if !self.keep_first {
+            values.reverse();
+        }
+        // the sort is stable and dedup keeps the first of equal values
+        values.sort();
+        values.dedup();
+        return FastRBTree::from_sorted_slice(&values);
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Default for TreeBuilder<T> {
+    fn default() -> Self {
+        return TreeBuilder::new();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::test_item::Item;
+
+    #[test]
+    fn tree_traversal() {
+        // Test the three different tree traversal functions.
+        let mut tree = FastRBTree::new();
+        tree.insert(0);
+        [16, 16, 8, 24, 20, 22].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let mut container = vec![];
+        tree.pre_order_traversal_for_test(&mut container);
+        assert_eq!(container, vec![20, 8, 0, 16, 24, 22]);
+        assert!(tree.is_valid_red_black_tree());
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut rb_tree = FastRBTree::new();
+        rb_tree.insert(12);
+        rb_tree.insert(1);
+        rb_tree.insert(9);
+        rb_tree.insert(2);
+        rb_tree.insert(0);
+        rb_tree.insert(11);
+        rb_tree.insert(7);
+        rb_tree.insert(19);
+        rb_tree.insert(4);
+        rb_tree.insert(15);
+        rb_tree.insert(18);
+        rb_tree.insert(5);
+        rb_tree.insert(14);
+        rb_tree.insert(13);
+        rb_tree.insert(10);
+        rb_tree.insert(16);
+        rb_tree.insert(6);
+        rb_tree.insert(3);
+        rb_tree.insert(8);
+        rb_tree.insert(17);
+
+        let result = FastRBTree::is_valid_subtree(rb_tree.root.clone());
+        assert!(result);
+        assert!(rb_tree.is_valid_red_black_tree());
+
+        // a red root or two red nodes in a row break the red-black tree properties
+        rb_tree.root.clone().unwrap().borrow_mut().color = NodeColor::Red;
+        assert!(!rb_tree.is_valid_red_black_tree());
+        assert!(!FastRBTree::is_valid_subtree(rb_tree.root.clone()));
+        rb_tree.root.clone().unwrap().borrow_mut().color = NodeColor::Black;
+        let left = rb_tree.root.clone().unwrap().borrow().left.clone().unwrap();
+        left.borrow_mut().color = NodeColor::Red;
+        left.borrow().left.clone().unwrap().borrow_mut().color = NodeColor::Red;
+        assert!(!rb_tree.is_valid_red_black_tree());
+    }
+
+    #[test]
+    fn test_insert_two_ascending() {
+        // the second value is a red right child with no left sibling, so maintain
+        // rotates left and the new root has a red left child without children
+        let mut rb_tree = FastRBTree::new();
+        rb_tree.insert(1);
+        rb_tree.insert(2);
+        assert!(rb_tree.is_valid_red_black_tree());
+        assert!(rb_tree.is_left_leaning());
+        assert_eq!(rb_tree.get_node_color(2), Some(NodeColor::Black));
+        assert_eq!(rb_tree.get_node_color(1), Some(NodeColor::Red));
+        assert_eq!(rb_tree.to_sorted_vec(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_insert_many_unique() {
+        let mut tree = FastRBTree::new();
+        assert_eq!(tree.insert_many_unique(vec![1, 2, 3, 2, 1]), 3);
+        assert_eq!(tree.insert_many_unique(vec![2, 3, 4, 5, 5]), 2);
+        assert_eq!(tree.insert_many_unique(Vec::new()), 0);
+        assert_eq!(tree.to_sorted_vec(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_to_dot_with_subtree_highlighted() {
+        let mut tree = FastRBTree::new();
+        [1, 2, 3, 4, 5, 6, 7].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        // pre order: 4 2 1 3 6 5 7
+        let dot = tree.to_dot_with_subtree_highlighted(2);
+        assert!(dot.starts_with("digraph FastRBTree {"));
+        assert!(dot.contains("\"2\" [fillcolor=black, color=blue, penwidth=3];"));
+        assert!(dot.contains("\"1\" [fillcolor=black, color=blue, penwidth=3];"));
+        assert!(dot.contains("\"3\" [fillcolor=black, color=blue, penwidth=3];"));
+        assert!(dot.contains("\"4\" [fillcolor=black];"));
+        assert!(dot.contains("\"6\" [fillcolor=black];"));
+        assert!(dot.contains("\"4\" -> \"2\";"));
+        assert!(dot.contains("\"6\" -> \"7\";"));
+
+        // a missing value highlights nothing
+        let dot = tree.to_dot_with_subtree_highlighted(42);
+        assert!(!dot.contains("penwidth"));
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut tree = FastRBTree::new();
+        tree.insert(1);
+        tree.insert(2);
+        tree.delete(2);
+        let mut container = vec![];
+        tree.in_order_traversal_for_test(&mut container);
+        assert_eq!(container, vec![1]);
+
+        [12, 1, 9, 2, 0, 11, 7, 19, 4, 15].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        [9, 1, 30, 12, 0, 19].iter().for_each(|v| {
+            tree.delete(*v);
+        });
+        let mut container = vec![];
+        tree.in_order_traversal_for_test(&mut container);
+        assert_eq!(container, vec![2, 4, 7, 11, 15]);
+        let result = FastRBTree::is_valid_subtree(tree.root.clone());
+        assert!(result);
+        assert!(tree.is_valid_red_black_tree());
+    }
+
+    #[test]
+    fn test_delete_single_node() {
+        let mut tree = FastRBTree::new();
+        tree.insert(5);
+        tree.delete(5);
+        assert!(tree.is_empty());
+        assert_eq!(tree.height(), 0);
+        assert!(!tree.contains(5));
+
+        // the emptied tree is still usable
+        tree.delete(5);
+        tree.insert(3);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(tree.get_node_color(3), Some(NodeColor::Black));
+    }
+
+    #[test]
+    fn test_from_sorted_slice() {
+        for size in 0..200 {
+            let data: Vec<u32> = (0..size).collect();
+            let tree = FastRBTree::from_sorted_slice(&data);
+            let mut container = vec![];
+            tree.in_order_traversal_for_test(&mut container);
+            assert_eq!(container, data);
+            let result = FastRBTree::is_valid_subtree(tree.root.clone());
+            assert!(result);
+            assert!(!TreeNode::is_red(&tree.root));
+            assert!(tree.is_left_leaning());
+            if size > 0 {
+                // the minimum height, with the nil leaves
+                let levels = (size + 1).next_power_of_two().trailing_zeros();
+                assert_eq!(tree.height(), levels + 1);
+            }
+        }
+
+        // the tree still works after being built
+        let mut tree = FastRBTree::from_sorted_slice(&[1, 3, 5, 7, 9, 11]);
+        tree.insert(4);
+        tree.delete(9);
+        tree.delete(1);
+        let mut container = vec![];
+        tree.in_order_traversal_for_test(&mut container);
+        assert_eq!(container, vec![3, 4, 5, 7, 11]);
+        let result = FastRBTree::is_valid_subtree(tree.root.clone());
+        assert!(result);
+    }
+
+    #[test]
+    fn test_cached_height() {
+        let mut tree = FastRBTree::new();
+        assert_eq!(tree.height(), tree.compute_height());
+        for v in vec![
+            12, 1, 9, 2, 0, 11, 7, 19, 4, 15, 18, 5, 14, 13, 10, 16, 6, 3, 8, 17,
+        ] {
+            tree.insert(v);
+            assert_eq!(tree.height(), tree.compute_height());
+        }
+        for v in vec![
+            9, 1, 30, 12, 0, 19, 4, 15, 18, 5, 14, 13, 10, 16, 6, 3, 8, 17, 2, 11, 7,
+        ] {
+            tree.delete(v);
+            assert_eq!(tree.height(), tree.compute_height());
+        }
+        assert_eq!(tree.height(), 0);
+
+        let tree = FastRBTree::from_sorted_slice(&[1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(tree.height(), tree.compute_height());
+
+        // a change drops the cached height, the next call to height fills it again
+        let mut tree = FastRBTree::new();
+        tree.extend(0..10);
+        assert_eq!(tree.cached_height.get(), None);
+        let height = tree.height();
+        assert_eq!(tree.cached_height.get(), Some(height));
+        tree.delete(0);
+        assert_eq!(tree.cached_height.get(), None);
+        assert_eq!(tree.height(), tree.compute_height());
+    }
+
+    #[test]
+    fn test_get_node_color() {
+        let mut tree = FastRBTree::new();
+        assert_eq!(tree.get_node_color(1), None);
+        tree.insert(1);
+        assert_eq!(tree.get_node_color(1), Some(NodeColor::Black));
+        // 2 becomes the root after the left rotation, 1 leans left as a red node
+        tree.insert(2);
+        assert_eq!(tree.get_node_color(2), Some(NodeColor::Black));
+        assert_eq!(tree.get_node_color(1), Some(NodeColor::Red));
+        // the flip makes both children black
+        tree.insert(3);
+        assert_eq!(tree.get_node_color(2), Some(NodeColor::Black));
+        assert_eq!(tree.get_node_color(1), Some(NodeColor::Black));
+        assert_eq!(tree.get_node_color(3), Some(NodeColor::Black));
+        assert_eq!(tree.get_node_color(4), None);
+
+        for v in 4..50 {
+            tree.insert(v);
+            let root_value = tree.root.clone().unwrap().borrow().value;
+            assert_eq!(tree.get_node_color(root_value), Some(NodeColor::Black));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", NodeColor::Red), "Red");
+        assert_eq!(format!("{}", NodeColor::Black), "Black");
+        assert_eq!(NodeColor::Red.to_short_string(), "r");
+        assert_eq!(NodeColor::Black.to_short_string(), "b");
+
+        let mut tree = FastRBTree::new();
+        tree.insert(5);
+        let root = tree.root.clone().unwrap();
+        assert_eq!(root.borrow().to_string(), "5(Black)");
+        assert_eq!(root.borrow().get_value_to_print(), "5b");
+    }
+
+    #[test]
+    fn test_count_nodes_by_color() {
+        let mut tree = FastRBTree::new();
+        assert_eq!((tree.count_red_nodes(), tree.count_black_nodes()), (0, 0));
+        assert_eq!(tree.count_nodes_by_color(), (0, 0));
+        assert_eq!(tree.red_fraction(), 0.0);
+        // the root alone is black
+        tree.insert(1);
+        assert_eq!((tree.count_red_nodes(), tree.count_black_nodes()), (0, 1));
+        assert_eq!(tree.count_nodes_by_color(), (0, 1));
+        assert_eq!(tree.red_fraction(), 0.0);
+
+        for v in vec![
+            12, 9, 2, 0, 11, 7, 19, 4, 15, 18, 5, 14, 13, 10, 16, 6, 3, 8, 17,
+        ] {
+            tree.insert(v);
+            let (red, black) = tree.count_nodes_by_color();
+            assert_eq!(red, tree.count_red_nodes());
+            assert_eq!(black, tree.count_black_nodes());
+            assert_eq!(red + black, tree.count_nodes());
+            assert!(red <= black);
+            assert!(tree.red_fraction() <= 0.5);
+        }
+        assert_eq!(tree.count_red_nodes() + tree.count_black_nodes(), 20);
+        while let Some(v) = tree.min() {
+            tree.delete(v);
+            let (red, black) = tree.count_nodes_by_color();
+            assert_eq!(red + black, tree.count_nodes());
+            assert!(red <= black);
+        }
+    }
+
+    #[test]
+    fn test_insert_equal_value() {
+        let mut tree = FastRBTree::new();
+        tree.insert(Item {
+            key: 1,
+            payload: 10,
+        });
+        tree.insert(Item {
+            key: 2,
+            payload: 20,
+        });
+        tree.insert(Item {
+            key: 1,
+            payload: 11,
+        });
+        let mut container = vec![];
+        tree.in_order_traversal_for_test(&mut container);
+        assert_eq!(container.len(), 2);
+        assert_eq!(container[0].payload, 11);
+
+        assert!(!tree.insert_if_absent(Item {
+            key: 1,
+            payload: 12
+        }));
+        assert!(tree.insert_if_absent(Item {
+            key: 3,
+            payload: 30
+        }));
+        let mut container = vec![];
+        tree.in_order_traversal_for_test(&mut container);
+        assert_eq!(container.len(), 3);
+        assert_eq!(container[0].payload, 11);
+        assert_eq!(container[2].payload, 30);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut tree = FastRBTree::new();
+        [5, 3, 8, 1, 4, 7, 9, 2, 6].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let drained: Vec<u32> = tree.drain().collect();
+        assert_eq!(drained, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert!(tree.is_empty());
+
+        [5, 3, 8, 1, 4].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        {
+            let mut drain = tree.drain();
+            assert_eq!(drain.next(), Some(1));
+            assert_eq!(drain.next(), Some(3));
+            let mut container = vec![];
+            drain.tree.in_order_traversal_for_test(&mut container);
+            assert_eq!(container, vec![4, 5, 8]);
+        }
+        // dropping the drain early removes the remaining values
+        assert!(tree.is_empty());
+        assert!(!tree.contains(5));
+        tree.insert(2);
+        assert!(tree.contains(2));
+    }
+
+    #[test]
+    fn test_from_newick() {
+        let tree = FastRBTree::from_sorted_slice(&[1, 2, 3, 4, 5, 6, 7]);
+        let newick = tree.to_newick();
+        let parsed = FastRBTree::<u32>::from_newick(&newick).unwrap();
+        assert_eq!(parsed.to_newick(), newick);
+        assert!(parsed.is_valid_red_black_tree());
+
+        let mut tree = FastRBTree::new();
+        (1..=20).for_each(|v| {
+            tree.insert(v);
+        });
+        tree.delete(7);
+        let parsed = FastRBTree::<u32>::from_newick(&tree.to_newick()).unwrap();
+        assert_eq!(
+            parsed.iter().collect::<Vec<_>>(),
+            tree.iter().collect::<Vec<_>>()
+        );
+        assert!(parsed.is_valid_red_black_tree());
+
+        let parsed = FastRBTree::<u32>::from_newick("(,2)1;").unwrap();
+        assert_eq!(parsed.iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert!(FastRBTree::<u32>::from_newick("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_newick_invalid() {
+        let invalid = vec![
+            "((1,3)2,(5,7)6",
+            "((1,3)2,(5,7)6)4)",
+            "(1,3",
+            "(1 3)2",
+            "(1,x)2",
+            "(1,3)",
+            "(,)2",
+            "(3,1)2",
+            "1;2",
+        ];
+        invalid.iter().for_each(|s| {
+            assert!(
+                FastRBTree::<u32>::from_newick(s).is_err(),
+                "{} should not parse",
+                s
+            );
+        });
+        assert_eq!(
+            FastRBTree::<u32>::from_newick("(1,x)2"),
+            Err(ParseError(String::from("invalid value 'x'")))
+        );
+        assert_eq!(
+            FastRBTree::<u32>::from_newick(&"(".repeat(1_000_000)),
+            Err(ParseError(String::from("nesting too deep")))
+        );
+
+        // a chain as deep as the limit still parses
+        let mut chain = String::from("1");
+        for v in 2..=NEWICK_MAX_DEPTH + 1 {
+            chain = format!("({},){}", chain, v);
+        }
+        let tree = FastRBTree::<usize>::from_newick(&chain).unwrap();
+        assert_eq!(tree.count_nodes(), NEWICK_MAX_DEPTH + 1);
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn test_csv_round_trip() {
+        let path = std::env::temp_dir().join(format!("fast_rbt_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut tree = FastRBTree::new();
+        [8, 3, 10, 1, 6, 14, 4, 7, 13].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        tree.to_csv(path).unwrap();
+        let read_back = FastRBTree::<u32>::from_csv(path).unwrap();
+        assert_eq!(
+            read_back.iter().collect::<Vec<_>>(),
+            tree.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(read_back.to_newick(), tree.to_newick());
+
+        std::fs::write(path, "1\n\n2\nthree\n").unwrap();
+        let error = FastRBTree::<u32>::from_csv(path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        std::fs::remove_file(path).unwrap();
+        assert!(FastRBTree::<u32>::from_csv(path).is_err());
+    }
+
+    #[test]
+    fn test_new_with_children() {
+        let leaf = |value, color| Some(TreeNode::new_with_children(value, color, None, None));
+        let tree_with_root = |root: FastRBTreeNode<u32>| FastRBTree {
+            root: Some(root),
+            cached_height: CachedU32::new(None),
+            #[cfg(feature = "stats")]
+            rotation_count: Counter::new(0),
+        };
+
+        // 2-3 tree with a red left leaning node
+        let root = TreeNode::new_with_children(
+            4,
+            NodeColor::Black,
+            Some(TreeNode::new_with_children(
+                2,
+                NodeColor::Red,
+                leaf(1, NodeColor::Black),
+                leaf(3, NodeColor::Black),
+            )),
+            leaf(5, NodeColor::Black),
+        );
+        let tree = tree_with_root(root);
+        assert!(tree.is_valid_red_black_tree());
+        assert_eq!(tree.height(), 4);
+        assert_eq!(tree.to_newick(), "((1,3)2,5)4");
+
+        // unequal black heights
+        let root =
+            TreeNode::new_with_children(2, NodeColor::Black, leaf(1, NodeColor::Black), None);
+        assert!(!FastRBTree::is_valid_subtree(Some(root.clone())));
+        assert!(!tree_with_root(root).is_valid_red_black_tree());
+
+        // two red nodes in a row
+        let root = TreeNode::new_with_children(
+            3,
+            NodeColor::Black,
+            Some(TreeNode::new_with_children(
+                2,
+                NodeColor::Red,
+                leaf(1, NodeColor::Red),
+                None,
+            )),
+            None,
+        );
+        assert!(!tree_with_root(root).is_valid_red_black_tree());
+
+        // not a binary search tree
+        let root = TreeNode::new_with_children(
+            2,
+            NodeColor::Black,
+            leaf(3, NodeColor::Black),
+            leaf(1, NodeColor::Black),
+        );
+        assert!(!tree_with_root(root).is_valid_red_black_tree());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_rotation_count() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut tree = FastRBTree::new();
+        tree.insert(1);
+        assert_eq!(tree.rotation_count(), 0);
+        // the red right child is rotated to the left
+        tree.insert(2);
+        assert_eq!(tree.rotation_count(), 1);
+        // equality only looks at the values and the shape
+        let copy = tree.clone();
+        tree.reset_rotation_count();
+        assert_eq!(tree.rotation_count(), 0);
+        assert_eq!(tree, copy);
+
+        let n = 10000;
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut tree = FastRBTree::new();
+        for _ in 0..n {
+            tree.insert(rng.gen_range(0, u32::MAX));
+        }
+        assert!(tree.rotation_count() > 0);
+        assert!(tree.rotation_count() <= 2 * n);
+
+        // rotations of another tree are not counted
+        let before = tree.rotation_count();
+        let mut other = FastRBTree::new();
+        (0..100).for_each(|v| {
+            other.insert(v);
+        });
+        assert_eq!(tree.rotation_count(), before);
+        assert!(other.rotation_count() > 0);
+    }
+
+    #[test]
+    fn test_paths_to_leaves() {
+        let mut tree = FastRBTree::new();
+        assert!(tree.get_all_paths_to_leaves().is_empty());
+        assert!(tree.get_black_heights_of_all_paths().is_empty());
+        tree.insert(1);
+        assert_eq!(tree.get_all_paths_to_leaves(), vec![vec![1], vec![1]]);
+        assert_eq!(tree.get_black_heights_of_all_paths(), vec![1, 1]);
+
+        let mut tree = FastRBTree::new();
+        for v in 0..200 {
+            if v % 4 == 0 {
+                tree.delete((v * 13) % 200);
+            }
+            tree.insert((v * 37) % 200);
+            let paths = tree.get_all_paths_to_leaves();
+            let black_heights = tree.get_black_heights_of_all_paths();
+            assert_eq!(paths.len(), tree.count_nodes() + 1);
+            assert_eq!(black_heights.len(), paths.len());
+            assert!(black_heights.iter().all(|h| *h == black_heights[0]));
+            // every path starts at the root and goes down to a leaf
+            for path in &paths {
+                assert_eq!(path[0], tree.get_root().unwrap().borrow().value);
+                assert!(path.len() <= tree.height() as usize);
+            }
+        }
+
+        // a broken tree has paths with different black heights
+        let tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+        let left = tree.get_root().unwrap().borrow().left.clone().unwrap();
+        left.borrow_mut().color = NodeColor::Red;
+        assert_eq!(tree.get_black_heights_of_all_paths(), vec![1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_max_path_imbalance() {
+        let mut tree = FastRBTree::new();
+        assert_eq!(tree.max_path_imbalance(), None);
+        tree.insert(1);
+        assert_eq!(tree.max_path_imbalance(), Some(1.0));
+
+        let tree = FastRBTree::from_sorted_slice(&(1..=15).collect::<Vec<u32>>());
+        assert_eq!(tree.max_path_imbalance(), Some(1.0));
+
+        // descending inserts make the tree lean to the left
+        let mut tree = FastRBTree::new();
+        (1..=6).rev().for_each(|v| {
+            tree.insert(v);
+        });
+        let imbalance = tree.max_path_imbalance().unwrap();
+        assert!(imbalance > 1.0 && imbalance <= 2.0);
+
+        let mut tree = FastRBTree::new();
+        for v in 0..2000 {
+            tree.insert((v * 7919) % 2000);
+            assert!(tree.max_path_imbalance().unwrap() <= 2.0);
+            if v % 3 == 0 {
+                tree.delete((v * 31) % 2000);
+                assert!(tree.max_path_imbalance().unwrap_or(1.0) <= 2.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let mut tree = FastRBTree::new();
+        [5, 3, 8, 1, 4].iter().for_each(|v| {
+            tree.insert(*v);
+        });
+        let mut container = vec![];
+        for v in &tree {
+            container.push(v);
+        }
+        assert_eq!(container, vec![1, 3, 4, 5, 8]);
+
+        // the tree is still usable after the loop
+        tree.insert(2);
+        assert_eq!(
+            (&tree).into_iter().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 8]
+        );
+    }
+
+    #[test]
+    fn test_try_insert() {
+        let mut tree = FastRBTree::new();
+        assert_eq!(
+            tree.try_insert(Item {
+                key: 1,
+                payload: 10
+            }),
+            Ok(())
+        );
+        assert_eq!(
+            tree.try_insert(Item {
+                key: 2,
+                payload: 20
+            }),
+            Ok(())
+        );
+
+        let result = tree.try_insert(Item {
+            key: 1,
+            payload: 11,
+        });
+        match result {
+            Err(AlreadyExists(existing)) => assert_eq!(existing.payload, 10),
+            Ok(()) => panic!("the duplicate should not be inserted"),
+        }
+        assert_eq!(tree.count_nodes(), 2);
+        assert_eq!(tree.get(Item { key: 1, payload: 0 }).unwrap().payload, 10);
+        assert!(tree.is_valid_red_black_tree());
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut tree = FastRBTree::new();
+        tree.extend(vec![5, 3, 8]);
+        tree.extend(Vec::new());
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![3, 5, 8]);
+
+        // overlapping values are not duplicated
+        tree.extend(vec![8, 1, 3]);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![1, 3, 5, 8]);
+
+        let mut other = FastRBTree::new();
+        other.extend(tree.iter());
+        assert_eq!(other.iter().collect::<Vec<_>>(), vec![1, 3, 5, 8]);
+
+        let mut extended = FastRBTree::new();
+        let mut inserted = FastRBTree::new();
+        extended.extend(0..10);
+        (0..10).for_each(|v| inserted.insert(v));
+        assert_eq!(
+            extended.iter().collect::<Vec<_>>(),
+            inserted.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(extended.height(), inserted.height());
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut tree = FastRBTree::new();
+        assert_eq!(format!("{:?}", tree), "FastRBTree { root: None }");
+        tree.extend(vec![1, 2, 3]);
+        assert_eq!(
+            format!("{:?}", tree),
+            "FastRBTree { root: Some(TreeNode { value: 2b, \
+             left: Some(TreeNode { value: 1b, left: None, right: None }), \
+             right: Some(TreeNode { value: 3b, left: None, right: None }) }) }"
+        );
+
+        // deep subtrees are truncated
+        let mut tree = FastRBTree::new();
+        tree.extend(0..100);
+        let output = format!("{:?}", tree);
+        assert!(output.contains("Some(...)"));
+        assert!(!output.contains("value: 0b"));
+        assert_eq!(output.matches("TreeNode").count(), 31);
+
+        // a cycle stops at the depth limit
+        let node = TreeNode::new_with_children(1, NodeColor::Black, None, None);
+        node.borrow_mut().right = Some(node.clone());
+        let subtree: DebugSubtree<u32, TreeNode<u32>> =
+            DebugSubtree::new(Some(node.clone()), DEBUG_DEPTH);
+        assert_eq!(format!("{:?}", subtree).matches("1b").count(), 5);
+        node.borrow_mut().right = None;
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut tree = FastRBTree::new();
+        tree.extend(0..20);
+        let mut copy = tree.clone();
+        assert!(copy == tree);
+        assert_eq!(format!("{:?}", copy), format!("{:?}", tree));
+        assert_eq!(copy.height(), tree.height());
+
+        // every node is only held by its parent, the clone shares none of them
+        for root in [tree.root.clone(), copy.root.clone()] {
+            let mut stack = vec![root.unwrap()];
+            while let Some(node) = stack.pop() {
+                assert_eq!(Shared::strong_count(&node), 2);
+                stack.extend(node.borrow().left.clone());
+                stack.extend(node.borrow().right.clone());
+            }
+        }
+
+        copy.delete(5);
+        copy.insert(100);
+        assert!(tree.contains(5));
+        assert!(!tree.contains(100));
+        drop(copy);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), (0..20).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_from_sorted_slice() {
+        for &size in [0, 1, 100, PARALLEL_BUILD_THRESHOLD, 50_000].iter() {
+            let data: Vec<u32> = (0..size as u32).collect();
+            let tree = FastRBTree::parallel_from_sorted_slice(&data);
+            assert!(tree.is_valid_red_black_tree());
+            assert_eq!(tree.iter().collect::<Vec<_>>(), data);
+            assert_eq!(tree.height(), tree.compute_height());
+            // same shape as the sequential build
+            let sequential = FastRBTree::from_sorted_slice(&data);
+            assert_eq!(tree.to_newick(), sequential.to_newick());
+        }
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut tree = FastRBTree::new();
+        tree.extend(0..10);
+        let mut snapshot = tree.snapshot();
+        tree.delete(3);
+        snapshot.insert(20);
+        assert!(snapshot.contains(3));
+        assert!(!tree.contains(20));
+        assert_eq!(snapshot.iter().count(), 11);
+        assert!(snapshot.is_valid_red_black_tree());
+    }
+
+    #[cfg(feature = "arc")]
+    #[test]
+    fn test_snapshot_across_threads() {
+        use std::sync::{Arc, RwLock};
+        use std::thread;
+
+        let shared = Arc::new(RwLock::new(FastRBTree::new()));
+        shared.write().unwrap().extend(0..100);
+        let snapshot = shared.read().unwrap().snapshot();
+        let reader = thread::spawn(move || snapshot.iter().collect::<Vec<_>>());
+        let writer = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || (0..50).for_each(|v| shared.write().unwrap().delete(v)))
+        };
+        writer.join().unwrap();
+        assert_eq!(reader.join().unwrap(), (0..100).collect::<Vec<_>>());
+        assert_eq!(
+            shared.read().unwrap().iter().collect::<Vec<_>>(),
+            (50..100).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let built = FastRBTree::builder()
+            .with_values(vec![5, 3, 8])
+            .with_value(1)
+            .with_values(0..4)
+            .build();
+        let mut inserted = FastRBTree::new();
+        [5, 3, 8, 1, 0, 1, 2, 3].iter().for_each(|v| {
+            inserted.insert(*v);
+        });
+        assert_eq!(built.to_sorted_vec(), inserted.to_sorted_vec());
+        assert!(built.is_valid_red_black_tree());
+        assert!(TreeBuilder::<u32>::default().build().is_empty());
+
+        // the last of equal values is kept, or the first one with keep_first
+        let items = vec![
+            Item {
+                key: 1,
+                payload: 10,
+            },
+            Item {
+                key: 1,
+                payload: 11,
+            },
+        ];
+        let last = FastRBTree::builder().with_values(items.clone()).build();
+        let first = FastRBTree::builder()
+            .keep_first(true)
+            .with_values(items)
+            .build();
+        assert_eq!(first.min().unwrap().payload, 10);
+        assert_eq!(last.min().unwrap().payload, 11);
+    }
+
+    #[test]
+    fn test_conversions() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let mut tree = FastRBTree::new();
+        for _ in 0..1000 {
+            tree.insert(rng.gen_range(0, 5000));
+        }
+        let mut expected = vec![];
+        tree.in_order_traversal_for_test(&mut expected);
+
+        let avl = tree.clone().into_avl();
+        let mut container = vec![];
+        avl.in_order_traversal_for_test(&mut container);
+        assert_eq!(container, expected);
+        assert!(avl.is_valid_bst());
+        assert!(avl.is_height_balanced());
+
+        let back = FastRBTree::from_avl(avl);
+        let mut container = vec![];
+        back.in_order_traversal_for_test(&mut container);
+        assert_eq!(container, expected);
+        assert!(back.is_valid_red_black_tree());
+
+        let rbt = tree.into_rbt();
+        let mut container = vec![];
+        rbt.in_order_traversal_for_test(&mut container);
+        assert_eq!(container, expected);
+        assert!(rbt.is_valid_red_black_tree());
+
+        let back = FastRBTree::from_rbt(rbt);
+        let mut container = vec![];
+        back.in_order_traversal_for_test(&mut container);
+        assert_eq!(container, expected);
+        assert!(back.is_valid_red_black_tree());
+
+        assert!(FastRBTree::<u32>::new().into_avl().is_empty());
+        assert!(FastRBTree::from_rbt(RBTree::<u32>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_bulk_delete() {
+        let mut tree = FastRBTree::new();
+        tree.bulk_delete(vec![1, 2]);
+        assert!(tree.is_empty());
+
+        // 29, 30 and 31 deleted values out of 100 are on both sides of the ratio
+        for count in [0, 10, 29, 30, 31, 50, 100, 150] {
+            let mut tree = FastRBTree::new();
+            tree.extend((0..100).rev());
+            let to_delete: Vec<u32> = (0..count).map(|v| (v * 7) % 150).collect();
+            tree.bulk_delete(to_delete.clone());
+            let expected: Vec<u32> = (0..100).filter(|v| !to_delete.contains(v)).collect();
+            assert_eq!(tree.iter().collect::<Vec<_>>(), expected);
+            assert!(tree.is_valid_red_black_tree());
+            assert_eq!(tree.height(), tree.compute_height());
+            tree.insert(1000);
+            assert!(tree.contains(1000));
+        }
+
+        // duplicated values in the input are deleted once
+        let mut tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+        tree.bulk_delete(vec![2, 2, 2, 2]);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_remove_range() {
+        let values: Vec<u32> = (0..200).map(|v| v * 2).collect();
+        let check = |f: &dyn Fn(&mut FastRBTree<u32>), keep: &dyn Fn(u32) -> bool| {
+            let mut tree = FastRBTree::new();
+            values.iter().for_each(|v| tree.insert(v * 37 % 400));
+            f(&mut tree);
+            let expected: Vec<u32> = values.iter().copied().filter(|v| keep(*v)).collect();
+            assert_eq!(tree.to_sorted_vec(), expected);
+            assert!(tree.is_valid_red_black_tree());
+            assert!(tree.is_left_leaning());
+            assert_eq!(tree.height(), tree.compute_height());
+        };
+
+        // bounds in the tree and between its values, small and large ranges
+        for (lo, hi) in [(10, 20), (11, 19), (0, 398), (100, 300), (20, 20), (30, 10)] {
+            check(&|t| t.remove_range_inclusive(lo, hi), &|v| v < lo || v > hi);
+            check(&|t| t.remove_range_exclusive(lo, hi), &|v| {
+                v <= lo || v >= hi
+            });
+        }
+        for bound in [0, 11, 20, 200, 398, 1000] {
+            check(&|t| t.remove_range_from(bound), &|v| v < bound);
+            check(&|t| t.remove_range_to(bound), &|v| v > bound);
+        }
+
+        let mut tree = FastRBTree::<u32>::new();
+        tree.remove_range_inclusive(1, 5);
+        tree.remove_range_from(0);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_rebalance_from_scratch() {
+        let mut tree = FastRBTree::<u32>::new();
+        tree.rebalance_from_scratch();
+        assert!(tree.is_empty());
+
+        tree.extend(0..1000);
+        (0..400).for_each(|v| tree.delete(v * 2));
+        let values = tree.to_sorted_vec();
+        let height = tree.height();
+        tree.rebalance_from_scratch();
+        assert_eq!(tree.to_sorted_vec(), values);
+        assert!(tree.is_valid_red_black_tree());
+        assert!(tree.height() <= height);
+        assert_eq!(tree.height(), tree.compute_height());
+        // 600 values fit in 10 levels, the nil leaves add one
+        assert_eq!(tree.height(), 11);
+        // the red nodes of an incomplete bottom level used to include right
+        // children, which insert and delete do not expect in a left-leaning tree
+        assert!(tree.is_left_leaning());
+        tree.insert(2000);
+        assert!(tree.contains(2000));
+        (1000..1100).for_each(|v| tree.insert(v));
+        (0..300).for_each(|v| tree.delete(v * 3));
+        assert!(tree.is_valid_red_black_tree());
+        assert!(tree.is_left_leaning());
+
+        for len in 1..100u32 {
+            let mut tree = FastRBTree::new();
+            (0..len).for_each(|v| tree.insert(v));
+            tree.rebalance_from_scratch();
+            assert!(tree.is_valid_red_black_tree());
+            assert!(tree.is_left_leaning());
+            assert_eq!(tree.to_sorted_vec(), (0..len).collect::<Vec<_>>());
+            let levels = (len + 1).next_power_of_two().trailing_zeros();
+            assert_eq!(tree.height(), levels + 1);
+        }
+    }
+
+    #[test]
+    fn test_is_left_leaning() {
+        use rand::seq::SliceRandom;
+
+        let mut rng = rand::thread_rng();
+        let mut values: Vec<u32> = (0..500).collect();
+        values.shuffle(&mut rng);
+        let mut tree = FastRBTree::new();
+        assert!(tree.is_left_leaning());
+        for v in values.iter() {
+            tree.insert(*v);
+            assert!(tree.is_left_leaning());
+        }
+        for v in values.iter().take(300) {
+            tree.delete(*v);
+            assert!(tree.is_left_leaning());
+        }
+
+        for len in 0..100u32 {
+            let data: Vec<u32> = (0..len).collect();
+            assert!(FastRBTree::from_sorted_slice(&data).is_left_leaning());
+            let mut tree = FastRBTree::new();
+            data.iter().for_each(|v| tree.insert(v * 37 % 100));
+            tree.rebalance_from_scratch();
+            assert!(tree.is_left_leaning());
+            assert!(tree.is_valid_red_black_tree());
+        }
+
+        // a red right child is still a valid red-black tree, but not left-leaning
+        let tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+        let root = tree.root.clone().unwrap();
+        root.borrow().left.clone().unwrap().borrow_mut().color = NodeColor::Red;
+        assert!(tree.is_left_leaning());
+        root.borrow().left.clone().unwrap().borrow_mut().color = NodeColor::Black;
+        root.borrow().right.clone().unwrap().borrow_mut().color = NodeColor::Red;
+        assert!(!tree.is_left_leaning());
+    }
+
+    #[test]
+    fn test_height_bound() {
+        use rand::seq::SliceRandom;
+
+        let mut rng = rand::thread_rng();
+        for n in [100, 1000, 10000] {
+            let sorted: Vec<u32> = (0..n).collect();
+            let reverse: Vec<u32> = (0..n).rev().collect();
+            let mut random = sorted.clone();
+            random.shuffle(&mut rng);
+            let bound = 2 * ((n as f64 + 1.0).log2().ceil() as u32 + 1);
+            for data in [sorted, reverse, random] {
+                let mut tree = FastRBTree::new();
+                tree.extend(data);
+                assert!(
+                    tree.height() <= bound,
+                    "height {} above {} for {} values",
+                    tree.height(),
+                    bound,
+                    n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_delete_all_elements() {
+        use rand::seq::SliceRandom;
+
+        let mut inserted: Vec<u32> = (0..=99).collect();
+        inserted.shuffle(&mut rand::thread_rng());
+        let sorted: Vec<u32> = (0..=99).collect();
+        let reverse: Vec<u32> = (0..=99).rev().collect();
+        for deleted in [inserted.clone(), sorted, reverse] {
+            let mut tree = FastRBTree::new();
+            inserted.iter().for_each(|v| {
+                tree.insert(*v);
+            });
+            for (i, v) in deleted.iter().enumerate() {
+                tree.delete(*v);
+                assert!(!tree.contains(*v));
+                assert_eq!(tree.count_nodes(), 99 - i);
+                assert!(tree.is_valid_bst());
+                assert!(tree.is_valid_red_black_tree());
+            }
+            assert!(tree.is_empty());
+            assert_eq!(tree.height(), 0);
+        }
+    }
+
+    // the nodes are behind Rc without the arc feature, so the tree cannot be sent
+    #[cfg(feature = "arc")]
+    #[test]
+    fn test_concurrent_reads() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let tree = Arc::new(FastRBTree::from_sorted_slice(
+            &(0..1000).map(|v| v * 2).collect::<Vec<u32>>(),
+        ));
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let tree = Arc::clone(&tree);
+                thread::spawn(move || {
+                    (0..10_000)
+                        .filter(|v| tree.contains((v + i * 250) % 2000))
+                        .count()
+                })
+            })
+            .collect();
+        for handle in handles {
+            // half of the values looked up are even
+            assert_eq!(handle.join().unwrap(), 5000);
+        }
+        assert_eq!(tree.count_nodes(), 1000);
+        assert!(tree.is_valid_red_black_tree());
+    }
+
+    #[test]
+    fn test_check_cycle_free() {
+        let mut tree = FastRBTree::new();
+        tree.check_cycle_free();
+        for v in 0..200 {
+            tree.insert((v * 37) % 200);
+            tree.check_cycle_free();
+        }
+        for v in 0..100 {
+            tree.delete(v * 2);
+            tree.check_cycle_free();
+        }
+        tree.clone().check_cycle_free();
+
+        // holding a node while the tree changes is fine, the check passes
+        // again once the extra owner is gone
+        let root = tree.get_root();
+        tree.insert(500);
+        tree.delete(1);
+        drop(root);
+        tree.check_cycle_free();
+        assert!(tree.is_valid_red_black_tree());
+    }
+
+    #[test]
+    #[should_panic(expected = "has more than one owner")]
+    fn test_check_cycle_free_shared_node() {
+        let mut tree = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+        let root = tree.root.clone().unwrap();
+        let left = root.borrow().left.clone();
+        // the left child is now also the right child of the root
+        root.borrow_mut().right = left;
+        tree.root = Some(root);
+        tree.check_cycle_free();
+    }
+
+    #[test]
+    fn test_entry_or_insert_with() {
+        let mut tree = FastRBTree::new();
+        let mut calls = 0;
+        [3, 1, 3, 2, 1, 3].iter().for_each(|v| {
+            let entry = tree.entry_or_insert_with(*v, || {
+                calls += 1;
+                *v
+            });
+            assert_eq!(entry, *v);
+        });
+        assert_eq!(calls, 3);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_entry_or_insert_with_payload() {
+        let mut tree = FastRBTree::new();
+        tree.insert(Item {
+            key: 1,
+            payload: 10,
+        });
+        // the key is searched, the stored payload is returned without calling f
+        let entry = tree.entry_or_insert_with(Item { key: 1, payload: 0 }, || unreachable!());
+        assert_eq!(entry.payload, 10);
+        let entry = tree.entry_or_insert_with(Item { key: 2, payload: 0 }, || Item {
+            key: 2,
+            payload: 20,
+        });
+        assert_eq!(entry.payload, 20);
+        assert_eq!(tree.get(Item { key: 2, payload: 0 }).unwrap().payload, 20);
+    }
+
+    #[test]
+    fn test_from_sorted_dedup() {
+        let tree = FastRBTree::<u32>::from_sorted_dedup(vec![]).unwrap();
+        assert!(tree.is_empty());
+
+        for size in 1..50 {
+            let data: Vec<u32> = (0..size).collect();
+            let tree = FastRBTree::from_sorted_dedup(data.clone()).unwrap();
+            assert!(tree == FastRBTree::from_sorted_slice(&data));
+            assert!(tree.is_valid_red_black_tree());
+        }
+
+        assert_eq!(
+            FastRBTree::from_sorted_dedup(vec![2, 1, 3]),
+            Err(NotSortedError { index: 1 })
+        );
+        // a duplicate is reported as well
+        let error = FastRBTree::from_sorted_dedup(vec![1, 2, 3, 3, 4]).unwrap_err();
+        assert_eq!(error.index, 3);
+        assert_eq!(
+            error.to_string(),
+            "input is not sorted without duplicates at index 3"
+        );
+    }
+
+    #[test]
+    fn test_iter_from() {
+        let empty = FastRBTree::<u32>::new();
+        assert_eq!(empty.iter_from(1).next(), None);
+
+        let mut tree = FastRBTree::new();
+        (0..100)
+            .map(|v| (v * 37) % 100 * 2)
+            .for_each(|v| tree.insert(v));
+        for pivot in 0..=201 {
+            let expected: Vec<u32> = tree.iter().skip_while(|v| *v < pivot).collect();
+            assert_eq!(tree.iter_from(pivot).collect::<Vec<_>>(), expected);
+        }
+        // the iterator keeps going like one created by iter
+        let mut iter = tree.iter_from(191);
+        assert_eq!(iter.next(), Some(192));
+        iter.skip_to_lower_bound(0);
+        assert_eq!(iter.next(), Some(0));
+    }
+
+    #[test]
+    fn test_intersection_iter() {
+        let empty = FastRBTree::new();
+        let small = FastRBTree::from_sorted_slice(&[1, 2, 3]);
+        assert_eq!(empty.intersection_iter(&small).next(), None);
+        assert_eq!(small.intersection_iter(&empty).next(), None);
+        assert_eq!(
+            small.intersection_iter(&small).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        let mut evens = FastRBTree::new();
+        let mut threes = FastRBTree::new();
+        (0..100).rev().for_each(|v| evens.insert(v * 2));
+        (0..70).for_each(|v| threes.insert(v * 3));
+        let expected: Vec<u32> = (0..200).filter(|v| v % 6 == 0).collect();
+        assert_eq!(
+            evens.intersection_iter(&threes).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            threes.intersection_iter(&evens).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            evens.intersection_iter(&threes).count(),
+            evens.intersection_size(&threes)
+        );
+
+        let disjoint = FastRBTree::from_sorted_slice(&[1, 3, 5]);
+        assert_eq!(evens.intersection_iter(&disjoint).next(), None);
+    }
+
+    #[test]
+    fn test_set_operation_iters() {
+        use std::collections::BTreeSet;
+
+        let empty = FastRBTree::new();
+        let mut evens = FastRBTree::new();
+        let mut threes = FastRBTree::new();
+        (0..100).rev().for_each(|v| evens.insert(v * 2));
+        (0..70).for_each(|v| threes.insert(v * 3));
+        let disjoint = FastRBTree::from_sorted_slice(&[1, 3, 5]);
+        let trees = [&empty, &evens, &threes, &disjoint];
+
+        for a in trees.iter() {
+            for b in trees.iter() {
+                let a_set: BTreeSet<u32> = a.iter().collect();
+                let b_set: BTreeSet<u32> = b.iter().collect();
+                assert_eq!(
+                    a.union_iter(b).collect::<Vec<_>>(),
+                    a_set.union(&b_set).copied().collect::<Vec<_>>()
+                );
+                assert_eq!(
+                    a.difference_iter(b).collect::<Vec<_>>(),
+                    a_set.difference(&b_set).copied().collect::<Vec<_>>()
+                );
+                assert_eq!(
+                    a.symmetric_difference_iter(b).collect::<Vec<_>>(),
+                    a_set
+                        .symmetric_difference(&b_set)
+                        .copied()
+                        .collect::<Vec<_>>()
+                );
+                assert_eq!(a.union_iter(b).count(), a.union_size(b));
+                assert_eq!(a.difference_iter(b).count(), a.difference_size(b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_root_to_leaf_paths() {
+        assert_eq!(FastRBTree::<u32>::new().all_root_to_leaf_paths().len(), 0);
+        // a node without children is listed once
+        let tree = FastRBTree::from_sorted_slice(&[1]);
+        assert_eq!(tree.all_root_to_leaf_paths(), vec![vec![1]]);
+
+        let mut tree = FastRBTree::new();
+        (0..100).for_each(|v| tree.insert((v * 37) % 100));
+        (0..30).for_each(|v| tree.delete(v * 3));
+        let paths = tree.all_root_to_leaf_paths();
+        assert_eq!(paths.len(), tree.count_nodes() - tree.count_full_nodes());
+        // each leaf ends exactly one path
+        let mut ends: Vec<u32> = paths.iter().map(|path| *path.last().unwrap()).collect();
+        ends.sort_unstable();
+        ends.dedup();
+        assert_eq!(ends.len(), paths.len());
+        for path in paths.iter() {
+            let mut node = tree.root.clone().unwrap();
+            assert_eq!(path[0], node.borrow().value);
+            // every step goes down to a child, and the path ends at a node
+            // with a missing child
+            for value in path.iter().skip(1) {
+                let left = node.borrow().left.clone();
+                let right = node.borrow().right.clone();
+                node = match (left, right) {
+                    (Some(left), _) if left.borrow().value == *value => left,
+                    (_, Some(right)) if right.borrow().value == *value => right,
+                    _ => panic!("{} is not a child on the path", value),
+                };
+            }
+            let last = node.borrow();
+            assert!(last.left.is_none() || last.right.is_none());
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Set backed by a red-black tree
+//!
+//! [RBTreeSet](struct.RBTreeSet.html) wraps a [FastRBTree](../rb_tree_fast/struct.FastRBTree.html)
+//! and uses the method names of `std::collections::BTreeSet`, so that code written
+//! for `BTreeSet` can switch to it with few changes.
+
+use std::fmt::{Debug, Display};
+
+use crate::common_trait::{CommonTreeTrait, InOrderIter};
+use crate::rb_tree_fast::{FastRBTree, TreeNode};
+
+/// An ordered set based on [FastRBTree](../rb_tree_fast/struct.FastRBTree.html)
+#[derive(Debug)]
+pub struct RBTreeSet<T: Ord + Copy + Debug + Display> {
+    tree: FastRBTree<T>,
+    /// Number of values, kept so that `len` is O(1)
+    len: usize,
+}
+
+impl<T: Ord + Copy + Debug + Display> RBTreeSet<T> {
+    /// Create an empty set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let mut set: RBTreeSet<u32> = RBTreeSet::new();
+    /// ```
+    pub fn new() -> Self {
+        RBTreeSet {
+            tree: FastRBTree::new(),
+            len: 0,
+        }
+    }
+
+    /// Add a value to the set, return false if it was already present
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let mut set = RBTreeSet::new();
+    /// assert_eq!(true, set.insert(1));
+    /// assert_eq!(false, set.insert(1));
+    /// ```
+    pub fn insert(&mut self, value: T) -> bool {
+        if !self.tree.insert_if_absent(value) {
+            return false;
+        }
+        self.len += 1;
+        return true;
+    }
+
+    /// Remove a value from the set, return false if it was not present
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let mut set = RBTreeSet::new();
+    /// set.insert(1);
+    /// assert_eq!(true, set.remove(&1));
+    /// assert_eq!(false, set.remove(&1));
+    /// ```
+    pub fn remove(&mut self, value: &T) -> bool {
+        if !self.tree.contains(*value) {
+            return false;
+        }
+        self.tree.delete(*value);
+        self.len -= 1;
+        return true;
+    }
+
+    /// Determine whether the set contains the value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let mut set = RBTreeSet::new();
+    /// set.insert(1);
+    /// assert_eq!(true, set.contains(&1));
+    /// ```
+    #[must_use = "calling contains without using the result has no effect"]
+    pub fn contains(&self, value: &T) -> bool {
+        return self.tree.contains(*value);
+    }
+
+    /// Return the number of values in the set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let mut set = RBTreeSet::new();
+    /// set.insert(1);
+    /// set.insert(2);
+    /// assert_eq!(2, set.len());
+    /// ```
+    #[must_use = "calling len without using the result has no effect"]
+    pub fn len(&self) -> usize {
+        return self.len;
+    }
+
+    /// Determine whether the set has no values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let set: RBTreeSet<u32> = RBTreeSet::new();
+    /// assert_eq!(true, set.is_empty());
+    /// ```
+    #[must_use = "calling is_empty without using the result has no effect"]
+    pub fn is_empty(&self) -> bool {
+        return self.len == 0;
+    }
+
+    /// Return an iterator over the values in ascending order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let mut set = RBTreeSet::new();
+    /// set.insert(2);
+    /// set.insert(1);
+    /// assert_eq!(vec![1, 2], set.iter().collect::<Vec<_>>());
+    /// ```
+    #[must_use = "calling iter without using the result has no effect"]
+    pub fn iter(&self) -> InOrderIter<'_, T, TreeNode<T>> {
+        return self.tree.iter();
+    }
+
+    /// Return the smallest value of the set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let mut set = RBTreeSet::new();
+    /// set.insert(2);
+    /// set.insert(1);
+    /// assert_eq!(Some(1), set.first());
+    /// ```
+    #[must_use = "calling first without using the result has no effect"]
+    pub fn first(&self) -> Option<T> {
+        return self.tree.min();
+    }
+
+    /// Return the largest value of the set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let mut set = RBTreeSet::new();
+    /// set.insert(2);
+    /// set.insert(1);
+    /// assert_eq!(Some(2), set.last());
+    /// ```
+    #[must_use = "calling last without using the result has no effect"]
+    pub fn last(&self) -> Option<T> {
+        return self.tree.max();
+    }
+
+    /// Remove all values from the set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::rb_tree_set::RBTreeSet;
+    /// let mut set = RBTreeSet::new();
+    /// set.insert(1);
+    /// set.clear();
+    /// assert_eq!(true, set.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.tree = FastRBTree::new();
+        self.len = 0;
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display> Default for RBTreeSet<T> {
+    fn default() -> Self {
+        return RBTreeSet::new();
+    }
+}
+
+/// Iterate over the values of a borrowed set in ascending order
+impl<'a, T: Ord + Copy + Debug + Display> IntoIterator for &'a RBTreeSet<T> {
+    type Item = T;
+    type IntoIter = InOrderIter<'a, T, TreeNode<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.iter();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_btree_set_api() {
+        let mut set = RBTreeSet::new();
+        let mut expected = BTreeSet::new();
+        assert_eq!(set.is_empty(), expected.is_empty());
+        [5, 3, 8, 1, 4, 3, 8].iter().for_each(|v| {
+            assert_eq!(set.insert(*v), expected.insert(*v));
+        });
+        assert_eq!(set.len(), expected.len());
+        assert_eq!(set.contains(&4), expected.contains(&4));
+        assert_eq!(set.contains(&6), expected.contains(&6));
+        assert_eq!(set.first(), expected.iter().next().copied());
+        assert_eq!(set.last(), expected.iter().next_back().copied());
+
+        [3, 6, 5].iter().for_each(|v| {
+            assert_eq!(set.remove(v), expected.remove(v));
+        });
+        assert_eq!(set.len(), expected.len());
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            expected.iter().copied().collect::<Vec<_>>()
+        );
+        let mut container = vec![];
+        for v in &set {
+            container.push(v);
+        }
+        assert_eq!(container, vec![1, 4, 8]);
+
+        set.clear();
+        expected.clear();
+        assert_eq!(set.is_empty(), expected.is_empty());
+        assert_eq!(set.len(), 0);
+        assert_eq!(set.first(), None);
+        assert!(RBTreeSet::<u32>::default().is_empty());
+    }
+}
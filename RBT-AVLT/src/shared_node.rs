@@ -0,0 +1,203 @@
+//! Shared pointers used to link tree nodes
+//!
+//! By default nodes are linked with `Rc<RefCell<_>>`. With the `arc` feature they are
+//! linked with `Arc` and a lock instead, which makes the trees `Send + Sync` so that a
+//! tree built on one thread can be read from several others.
+//!
+//! The tree modules only refer to [Shared](type.Shared.html), [WeakShared](type.WeakShared.html)
+//! and [NodeCell](struct.NodeCell.html), so the same code compiles in both configurations.
+//!
+//! # Example
+//!
+//! ```
+//! use tree_collections::shared_node::{NodeCell, Shared};
+//! let node = Shared::new(NodeCell::new(1));
+//! *node.borrow_mut() += 1;
+//! assert_eq!(2, *node.borrow());
+//! ```
+
+#[cfg(not(feature = "arc"))]
+pub use std::cell::RefCell as NodeCell;
+#[cfg(not(feature = "arc"))]
+pub use std::rc::{Rc as Shared, Weak as WeakShared};
+
+#[cfg(feature = "arc")]
+pub use std::sync::{Arc as Shared, Weak as WeakShared};
+
+/// Counter kept inside a tree, `Cell<u64>` unless the `arc` feature is enabled
+#[cfg(not(feature = "arc"))]
+pub type Counter = std::cell::Cell<u64>;
+
+/// Cached value kept inside a tree, `None` until it is computed,
+/// `Cell<Option<u32>>` unless the `arc` feature is enabled
+#[cfg(not(feature = "arc"))]
+pub type CachedU32 = std::cell::Cell<Option<u32>>;
+
+#[cfg(feature = "arc")]
+use std::fmt::{self, Debug};
+#[cfg(feature = "arc")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "arc")]
+use std::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Lock around a node with the `borrow` and `borrow_mut` methods of `RefCell`
+///
+/// A poisoned lock means a panic happened while a node was being modified, so the
+/// tree can no longer be trusted and both methods panic as well.
+#[cfg(feature = "arc")]
+pub struct NodeCell<T: ?Sized>(RwLock<T>);
+
+#[cfg(feature = "arc")]
+impl<T> NodeCell<T> {
+    pub fn new(value: T) -> Self {
+        return NodeCell(RwLock::new(value));
+    }
+}
+
+#[cfg(feature = "arc")]
+impl<T: ?Sized> NodeCell<T> {
+    pub fn borrow(&self) -> RwLockReadGuard<'_, T> {
+        return self.0.read().unwrap();
+    }
+
+    pub fn borrow_mut(&self) -> RwLockWriteGuard<'_, T> {
+        return self.0.write().unwrap();
+    }
+}
+
+#[cfg(feature = "arc")]
+impl<T: Clone> Clone for NodeCell<T> {
+    fn clone(&self) -> Self {
+        return NodeCell::new(self.borrow().clone());
+    }
+}
+
+#[cfg(feature = "arc")]
+impl<T: ?Sized + PartialEq> PartialEq for NodeCell<T> {
+    fn eq(&self, other: &Self) -> bool {
+        return *self.borrow() == *other.borrow();
+    }
+}
+
+#[cfg(feature = "arc")]
+impl<T: ?Sized + Debug> Debug for NodeCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f
+            .debug_struct("NodeCell")
+            .field("value", &&*self.borrow())
+            .finish();
+    }
+}
+
+/// Counter kept inside a tree, an atomic so that the tree stays `Sync`
+#[cfg(feature = "arc")]
+pub struct Counter(AtomicU64);
+
+#[cfg(feature = "arc")]
+impl Counter {
+    pub fn new(value: u64) -> Self {
+        return Counter(AtomicU64::new(value));
+    }
+
+    pub fn get(&self) -> u64 {
+        return self.0.load(Ordering::Relaxed);
+    }
+
+    pub fn set(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "arc")]
+impl Clone for Counter {
+    fn clone(&self) -> Self {
+        return Counter::new(self.get());
+    }
+}
+
+#[cfg(feature = "arc")]
+impl PartialEq for Counter {
+    fn eq(&self, other: &Self) -> bool {
+        return self.get() == other.get();
+    }
+}
+
+#[cfg(feature = "arc")]
+impl Debug for Counter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_tuple("Counter").field(&self.get()).finish();
+    }
+}
+
+/// Cached value kept inside a tree, `None` until it is computed,
+/// behind a lock so that the tree stays `Sync`
+#[cfg(feature = "arc")]
+pub struct CachedU32(Mutex<Option<u32>>);
+
+#[cfg(feature = "arc")]
+impl CachedU32 {
+    pub fn new(value: Option<u32>) -> Self {
+        return CachedU32(Mutex::new(value));
+    }
+
+    pub fn get(&self) -> Option<u32> {
+        return *self.0.lock().unwrap();
+    }
+
+    pub fn set(&self, value: Option<u32>) {
+        *self.0.lock().unwrap() = value;
+    }
+}
+
+#[cfg(feature = "arc")]
+impl Clone for CachedU32 {
+    fn clone(&self) -> Self {
+        return CachedU32::new(self.get());
+    }
+}
+
+#[cfg(feature = "arc")]
+impl PartialEq for CachedU32 {
+    fn eq(&self, other: &Self) -> bool {
+        return self.get() == other.get();
+    }
+}
+
+#[cfg(feature = "arc")]
+impl Debug for CachedU32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_tuple("CachedU32").field(&self.get()).finish();
+    }
+}
+
+#[cfg(all(test, feature = "arc"))]
+mod test {
+    use crate::prelude::*;
+    use static_assertions::assert_impl_all;
+    use std::sync::Arc;
+    use std::thread;
+
+    assert_impl_all!(FastRBTree<u32>: Send, Sync);
+    assert_impl_all!(RBTree<u32>: Send, Sync);
+    assert_impl_all!(AVLTree<u32>: Send, Sync);
+    assert_impl_all!(BSTree<u32>: Send, Sync);
+    assert_impl_all!(AugmentedRBTree<u32>: Send, Sync);
+    assert_impl_all!(RBTreeSet<u32>: Send, Sync);
+
+    #[test]
+    fn test_share_across_threads() {
+        let mut tree = FastRBTree::new();
+        (0..100).for_each(|v| tree.insert(v));
+        let tree = Arc::new(tree);
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let tree = Arc::clone(&tree);
+                thread::spawn(move || (i * 25..(i + 1) * 25).all(|v| tree.contains(v)))
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+        assert_eq!(tree.iter().count(), 100);
+    }
+}
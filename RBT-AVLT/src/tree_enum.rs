@@ -0,0 +1,374 @@
+//! A tree whose implementation is chosen at runtime
+//!
+//! [Tree](enum.Tree.html) holds one of [RBTree](../rb_tree/struct.RBTree.html),
+//! [FastRBTree](../rb_tree_fast/struct.FastRBTree.html) or
+//! [AVLTree](../avl_tree/struct.AVLTree.html) and forwards each call to it, so the
+//! tree type can come from a configuration value instead of a type parameter.
+//!
+//! `CommonTreeTrait` is tied to a single node type through `get_root`, which the
+//! three variants do not share, so the shared methods are provided directly on
+//! the enum instead.
+//!
+//! # Example
+//!
+//! ```
+//! use tree_collections::tree_enum::Tree;
+//!
+//! let kind = "avl";
+//! let mut tree = match kind {
+//!     "rb" => Tree::new_rb(),
+//!     "avl" => Tree::new_avl(),
+//!     _ => Tree::new_fast_rb(),
+//! };
+//! (1..=10).for_each(|v| tree.insert(v));
+//! assert_eq!(true, tree.contains(5));
+//! assert_eq!(Some(10), tree.max());
+//! ```
+
+use std::fmt::{Debug, Display};
+
+use crate::avl_tree::AVLTree;
+use crate::common_trait::CommonTreeTrait;
+use crate::rb_tree::RBTree;
+use crate::rb_tree_fast::FastRBTree;
+
+/// One of the three balanced tree types
+#[derive(Clone, Debug)]
+pub enum Tree<T: Ord + Copy + Debug + Display> {
+    /// A red-black tree whose nodes point to their parents
+    RB(RBTree<T>),
+    /// A left-leaning red-black tree
+    FastRB(FastRBTree<T>),
+    /// An AVL tree
+    AVL(AVLTree<T>),
+}
+
+// Run the same expression on whichever tree the enum holds
+macro_rules! on_inner {
+    ($tree:expr, $inner:ident => $body:expr) => {
+        match $tree {
+            Tree::RB($inner) => $body,
+            Tree::FastRB($inner) => $body,
+            Tree::AVL($inner) => $body,
+        }
+    };
+}
+
+impl<T: Ord + Copy + Debug + Display> Tree<T> {
+    /// Create an empty tree backed by [RBTree](../rb_tree/struct.RBTree.html)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::tree_enum::Tree;
+    /// let tree = Tree::<u32>::new_rb();
+    /// ```
+    pub fn new_rb() -> Self {
+        Tree::RB(RBTree::new())
+    }
+
+    /// Create an empty tree backed by [FastRBTree](../rb_tree_fast/struct.FastRBTree.html)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::tree_enum::Tree;
+    /// let tree = Tree::<u32>::new_fast_rb();
+    /// ```
+    pub fn new_fast_rb() -> Self {
+        Tree::FastRB(FastRBTree::new())
+    }
+
+    /// Create an empty tree backed by [AVLTree](../avl_tree/struct.AVLTree.html)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::tree_enum::Tree;
+    /// let tree = Tree::<u32>::new_avl();
+    /// ```
+    pub fn new_avl() -> Self {
+        Tree::AVL(AVLTree::new())
+    }
+
+    /// Convert the tree into a [FastRBTree](../rb_tree_fast/struct.FastRBTree.html)
+    /// holding the same values
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::tree_enum::Tree;
+    /// let mut tree = Tree::new_avl();
+    /// (1..=5).for_each(|v| tree.insert(v));
+    /// let fast = tree.into_fast_rb();
+    /// assert_eq!(true, fast.is_fast_rb());
+    /// assert_eq!(vec![1, 2, 3, 4, 5], fast.to_sorted_vec());
+    /// ```
+    pub fn into_fast_rb(self) -> Self {
+        match self {
+            Tree::RB(tree) => Tree::FastRB(FastRBTree::from_rbt(tree)),
+            Tree::FastRB(tree) => Tree::FastRB(tree),
+            Tree::AVL(tree) => Tree::FastRB(FastRBTree::from_avl(tree)),
+        }
+    }
+
+    /// Return true if the tree is backed by [RBTree](../rb_tree/struct.RBTree.html)
+    #[must_use = "calling is_rb without using the result has no effect"]
+    pub fn is_rb(&self) -> bool {
+        return matches!(self, Tree::RB(_));
+    }
+
+    /// Return true if the tree is backed by [FastRBTree](../rb_tree_fast/struct.FastRBTree.html)
+    #[must_use = "calling is_fast_rb without using the result has no effect"]
+    pub fn is_fast_rb(&self) -> bool {
+        return matches!(self, Tree::FastRB(_));
+    }
+
+    /// Return true if the tree is backed by [AVLTree](../avl_tree/struct.AVLTree.html)
+    #[must_use = "calling is_avl without using the result has no effect"]
+    pub fn is_avl(&self) -> bool {
+        return matches!(self, Tree::AVL(_));
+    }
+
+    /// Insert a new value to the tree, an equal value already in the tree
+    /// is replaced by the new one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::tree_enum::Tree;
+    /// let mut tree = Tree::new_rb();
+    /// tree.insert(1);
+    /// ```
+    pub fn insert(&mut self, value: T) {
+        on_inner!(self, tree => tree.insert(value))
+    }
+
+    /// Insert a new value only if no equal value is in the tree,
+    /// return true if the value was inserted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::tree_enum::Tree;
+    /// let mut tree = Tree::new_rb();
+    /// assert_eq!(true, tree.insert_if_absent(1));
+    /// assert_eq!(false, tree.insert_if_absent(1));
+    /// ```
+    pub fn insert_if_absent(&mut self, value: T) -> bool {
+        on_inner!(self, tree => tree.insert_if_absent(value))
+    }
+
+    /// Insert every value which is not in the tree yet, return how many were inserted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::tree_enum::Tree;
+    /// let mut tree = Tree::new_avl();
+    /// assert_eq!(3, tree.insert_many_unique(vec![1, 2, 3, 2, 1]));
+    /// ```
+    pub fn insert_many_unique<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        on_inner!(self, tree => tree.insert_many_unique(iter))
+    }
+
+    /// Delete a value from the tree
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::tree_enum::Tree;
+    /// let mut tree = Tree::new_fast_rb();
+    /// tree.insert(1);
+    /// tree.delete(1);
+    /// assert_eq!(true, tree.is_empty());
+    /// ```
+    pub fn delete(&mut self, value: T) {
+        on_inner!(self, tree => tree.delete(value))
+    }
+
+    /// Determine whether the tree contains the value
+    #[must_use = "calling contains without using the result has no effect"]
+    pub fn contains(&self, value: T) -> bool {
+        on_inner!(self, tree => tree.contains(value))
+    }
+
+    /// Return the stored value equal to `value`
+    #[must_use = "calling get without using the result has no effect"]
+    pub fn get(&self, value: T) -> Option<T> {
+        on_inner!(self, tree => tree.get(value))
+    }
+
+    /// Determine whether the tree is empty
+    #[must_use = "calling is_empty without using the result has no effect"]
+    pub fn is_empty(&self) -> bool {
+        on_inner!(self, tree => tree.is_empty())
+    }
+
+    /// Return the number of values in the tree
+    #[must_use = "calling count_nodes without using the result has no effect"]
+    pub fn count_nodes(&self) -> usize {
+        on_inner!(self, tree => tree.count_nodes())
+    }
+
+    /// Return the number of leaves of the tree
+    #[must_use = "calling count_leaves without using the result has no effect"]
+    pub fn count_leaves(&self) -> u32 {
+        on_inner!(self, tree => tree.count_leaves())
+    }
+
+    /// Return the height of the tree, as defined by the inner tree type
+    #[must_use = "calling height without using the result has no effect"]
+    pub fn height(&self) -> u32 {
+        on_inner!(self, tree => tree.height())
+    }
+
+    /// Return the smallest value in the tree
+    #[must_use = "calling min without using the result has no effect"]
+    pub fn min(&self) -> Option<T> {
+        on_inner!(self, tree => tree.min())
+    }
+
+    /// Return the largest value in the tree
+    #[must_use = "calling max without using the result has no effect"]
+    pub fn max(&self) -> Option<T> {
+        on_inner!(self, tree => tree.max())
+    }
+
+    /// Return the number of values less than `value`
+    #[must_use = "calling rank without using the result has no effect"]
+    pub fn rank(&self, value: T) -> usize {
+        on_inner!(self, tree => tree.rank(value))
+    }
+
+    /// Return the `k`-th smallest value, starting from 0
+    #[must_use = "calling select without using the result has no effect"]
+    pub fn select(&self, k: usize) -> Option<T> {
+        on_inner!(self, tree => tree.select(k))
+    }
+
+    /// Return the smallest value greater than `value`
+    #[must_use = "calling successor without using the result has no effect"]
+    pub fn successor(&self, value: T) -> Option<T> {
+        on_inner!(self, tree => tree.successor(value))
+    }
+
+    /// Return the largest value less than `value`
+    #[must_use = "calling predecessor without using the result has no effect"]
+    pub fn predecessor(&self, value: T) -> Option<T> {
+        on_inner!(self, tree => tree.predecessor(value))
+    }
+
+    /// Return the values between `low` and `high`, both included, in ascending order
+    #[must_use = "calling range without using the result has no effect"]
+    pub fn range(&self, low: T, high: T) -> Vec<T> {
+        on_inner!(self, tree => tree.range(low, high))
+    }
+
+    /// Return the values of the tree in ascending order
+    #[must_use = "calling to_sorted_vec without using the result has no effect"]
+    pub fn to_sorted_vec(&self) -> Vec<T> {
+        on_inner!(self, tree => tree.to_sorted_vec())
+    }
+
+    /// Return an iterator over the values in ascending order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tree_collections::tree_enum::Tree;
+    /// let mut tree = Tree::new_rb();
+    /// (1..=5).for_each(|v| tree.insert(v));
+    /// assert_eq!(15, tree.iter().sum::<u32>());
+    /// ```
+    pub fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        on_inner!(self, tree => Box::new(tree.iter()))
+    }
+
+    /// Return true if the values of the tree are in binary search tree order
+    #[must_use = "calling is_valid_bst without using the result has no effect"]
+    pub fn is_valid_bst(&self) -> bool {
+        on_inner!(self, tree => tree.is_valid_bst())
+    }
+
+    /// Print the structure of the tree
+    pub fn print(&self) {
+        on_inner!(self, tree => tree.print())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_variants_behave_the_same() {
+        let mut trees = [Tree::new_rb(), Tree::new_fast_rb(), Tree::new_avl()];
+        assert!(trees[0].is_rb() && trees[1].is_fast_rb() && trees[2].is_avl());
+        let values: Vec<u32> = (0..200).map(|v| v * 37 % 200).collect();
+        for tree in trees.iter_mut() {
+            assert!(tree.is_empty());
+            assert_eq!(tree.insert_many_unique(values.clone()), 200);
+            assert!(!tree.insert_if_absent(5));
+            (0..50).for_each(|v| tree.delete(v * 4));
+        }
+
+        let expected: Vec<u32> = (0..200).filter(|v| v % 4 != 0).collect();
+        for tree in trees.iter() {
+            assert_eq!(tree.to_sorted_vec(), expected);
+            assert_eq!(tree.iter().collect::<Vec<_>>(), expected);
+            assert_eq!(tree.count_nodes(), 150);
+            assert!(!tree.contains(4));
+            assert!(tree.contains(5));
+            assert_eq!(tree.get(7), Some(7));
+            assert_eq!((tree.min(), tree.max()), (Some(1), Some(199)));
+            assert_eq!(tree.rank(10), 7);
+            assert_eq!(tree.select(7), Some(10));
+            assert_eq!(tree.successor(7), Some(9));
+            assert_eq!(tree.predecessor(9), Some(7));
+            assert_eq!(tree.range(10, 15), vec![10, 11, 13, 14, 15]);
+            assert!(tree.is_valid_bst());
+        }
+    }
+
+    #[test]
+    fn test_into_fast_rb() {
+        for mut tree in [Tree::new_rb(), Tree::new_fast_rb(), Tree::new_avl()] {
+            (0..100).for_each(|v| tree.insert(v * 7 % 100));
+            let fast = tree.clone().into_fast_rb();
+            assert!(fast.is_fast_rb());
+            assert_eq!(fast.to_sorted_vec(), tree.to_sorted_vec());
+            if let Tree::FastRB(inner) = fast {
+                assert!(inner.is_valid_red_black_tree());
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_equal_value() {
+        use crate::utils::test_item::Item;
+
+        let mut trees = [Tree::new_rb(), Tree::new_fast_rb(), Tree::new_avl()];
+        for tree in trees.iter_mut() {
+            [
+                Item {
+                    key: 1,
+                    payload: 10,
+                },
+                Item {
+                    key: 2,
+                    payload: 20,
+                },
+                Item {
+                    key: 1,
+                    payload: 11,
+                },
+            ]
+            .iter()
+            .for_each(|v| tree.insert(*v));
+            let values = tree.to_sorted_vec();
+            assert_eq!(values.len(), 2);
+            assert_eq!(values[0].payload, 11);
+        }
+    }
+}
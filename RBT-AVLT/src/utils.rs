@@ -0,0 +1,184 @@
+//! Helper functions which work across the tree types
+//!
+//! # Example
+//!
+//! ```
+//! use tree_collections::prelude::*;
+//! use tree_collections::utils::set_equal;
+//!
+//! let mut avl_tree = AVLTree::new();
+//! let mut rb_tree = FastRBTree::new();
+//! (1..=10).for_each(|v| avl_tree.insert(v));
+//! (1..=10).rev().for_each(|v| rb_tree.insert(v));
+//! assert!(set_equal(&avl_tree, &rb_tree));
+//! ```
+
+use std::fmt::{Debug, Display};
+
+use crate::common_trait::{CommonTreeNodeTrait, CommonTreeTrait};
+
+/// Return true if both trees hold the same values
+///
+/// Unlike `PartialEq`, which compares the layout of the nodes, only the sorted
+/// values are compared, so trees built in a different insertion order, or of
+/// a different tree type, are equal as long as they hold the same values.
+///
+/// # Example
+///
+/// ```
+/// use tree_collections::prelude::*;
+/// use tree_collections::utils::set_equal;
+/// let mut a = FastRBTree::new();
+/// let mut b = FastRBTree::new();
+/// vec![1, 2, 3, 4, 5].iter().for_each(|v| a.insert(*v));
+/// vec![5, 4, 3, 2, 1].iter().for_each(|v| b.insert(*v));
+/// assert!(set_equal(&a, &b));
+/// b.delete(3);
+/// assert!(!set_equal(&a, &b));
+/// ```
+#[must_use = "calling set_equal without using the result has no effect"]
+pub fn set_equal<T, A, ANode, B, BNode>(a: &A, b: &B) -> bool
+where
+    T: Ord + Copy + Debug + Display,
+    A: CommonTreeTrait<T, ANode>,
+    ANode: CommonTreeNodeTrait<T>,
+    B: CommonTreeTrait<T, BNode>,
+    BNode: CommonTreeNodeTrait<T>,
+{
+    return a.to_sorted_vec() == b.to_sorted_vec();
+}
+
+/// Return true if the values can be the path from the root of a binary search
+/// tree down to a node, as returned by
+/// [get_all_paths_to_leaves](../rb_tree_fast/struct.FastRBTree.html#method.get_all_paths_to_leaves)
+///
+/// Going left from a node means that every value after it is less than the node,
+/// going right that every value after it is greater, so each value has to be
+/// within the bounds set by the values before it. Empty and single value paths
+/// are valid.
+///
+/// # Example
+///
+/// ```
+/// use tree_collections::utils::verify_path;
+/// assert!(verify_path(&[8, 4, 6, 5]));
+/// // 9 is after going left from 8
+/// assert!(!verify_path(&[8, 4, 6, 9]));
+/// ```
+#[must_use = "calling verify_path without using the result has no effect"]
+pub fn verify_path<T: Ord + Copy + Debug + Display>(path: &[T]) -> bool {
+    let mut lower: Option<T> = None;
+    let mut upper: Option<T> = None;
+    for pair in path.windows(2) {
+        let (node, next) = (pair[0], pair[1]);
+        if next < node {
+            upper = Some(node);
+        } else if next > node {
+            lower = Some(node);
+        } else {
+            return false;
+        }
+        if let Some(lower) = lower {
+            if next <= lower {
+                return false;
+            }
+        }
+        if let Some(upper) = upper {
+            if next >= upper {
+                return false;
+            }
+        }
+    }
+    return true;
+}
+
+// Value type for the tests of the tree modules
+#[cfg(test)]
+pub(crate) mod test_item {
+    use std::cmp::Ordering;
+    use std::fmt::{self, Display};
+
+    // ordered by key only, so two items with the same key are equal for the tree
+    #[derive(Clone, Copy, Debug)]
+    pub(crate) struct Item {
+        pub(crate) key: u32,
+        pub(crate) payload: u32,
+    }
+
+    impl PartialEq for Item {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Item {}
+
+    impl PartialOrd for Item {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Item {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.key.cmp(&other.key)
+        }
+    }
+
+    impl Display for Item {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_set_equal() {
+        let mut fast_rb_tree = FastRBTree::new();
+        let mut rb_tree = RBTree::new();
+        let mut avl_tree = AVLTree::new();
+        assert!(set_equal(&fast_rb_tree, &avl_tree));
+
+        (1..=50).for_each(|v| fast_rb_tree.insert(v));
+        (1..=50).rev().for_each(|v| rb_tree.insert(v));
+        (1..=50)
+            .map(|v| (v * 7) % 50 + 1)
+            .for_each(|v| avl_tree.insert(v));
+        assert!(set_equal(&fast_rb_tree, &rb_tree));
+        assert!(set_equal(&rb_tree, &avl_tree));
+
+        avl_tree.delete(25);
+        assert!(!set_equal(&fast_rb_tree, &avl_tree));
+        avl_tree.insert(51);
+        assert!(!set_equal(&fast_rb_tree, &avl_tree));
+    }
+
+    #[test]
+    fn test_verify_path() {
+        assert!(verify_path::<u32>(&[]));
+        assert!(verify_path(&[5]));
+        assert!(verify_path(&[1, 2, 3, 4]));
+        assert!(verify_path(&[4, 3, 2, 1]));
+        assert!(verify_path(&[10, 2, 8, 3, 7, 4]));
+
+        // a repeated value
+        assert!(!verify_path(&[4, 4]));
+        assert!(!verify_path(&[4, 2, 4]));
+        // 5 is after going left from 4
+        assert!(!verify_path(&[4, 2, 5]));
+        // 1 is after going right from 2
+        assert!(!verify_path(&[10, 2, 8, 1]));
+
+        let mut tree = FastRBTree::new();
+        (0..100).for_each(|v| tree.insert((v * 37) % 100));
+        assert!(tree
+            .get_all_paths_to_leaves()
+            .iter()
+            .all(|path| verify_path(path)));
+    }
+}
@@ -0,0 +1,110 @@
+// Randomized operations on FastRBTree, RBTree and AVLTree at the same time,
+// checked after every operation against a BTreeSet holding the same values
+
+use tree_collections::prelude::*;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::BTreeSet;
+
+const OPERATIONS: usize = 100_000;
+// small enough for the trees to delete values they hold as often as missing ones
+const VALUE_RANGE: u32 = 500;
+
+// Helper function for test_stress, compare one tree with the reference set
+fn check_tree<Tree, Node>(name: &str, tree: &Tree, expected: &BTreeSet<u32>, step: usize)
+where
+    Tree: CommonTreeTrait<u32, Node>,
+    Node: CommonTreeNodeTrait<u32>,
+{
+    assert_eq!(
+        tree.count_nodes(),
+        expected.len(),
+        "{}: count_nodes differs at step {}",
+        name,
+        step
+    );
+    assert_eq!(
+        tree.min(),
+        expected.iter().next().copied(),
+        "{}: min differs at step {}",
+        name,
+        step
+    );
+    assert_eq!(
+        tree.max(),
+        expected.iter().next_back().copied(),
+        "{}: max differs at step {}",
+        name,
+        step
+    );
+}
+
+// Helper function for test_stress, the heights of both red-black trees count
+// their nil leaves so the bound of the red-black tree holds for all three trees
+fn check_height<Tree, Node>(name: &str, tree: &Tree, size: usize, step: usize)
+where
+    Tree: CommonTreeTrait<u32, Node>,
+    Node: CommonTreeNodeTrait<u32>,
+{
+    let bound = 2 * ((size as f64 + 1.0).log2().ceil() as u32 + 1);
+    assert!(
+        tree.height() <= bound,
+        "{}: height {} above {} for {} values at step {}",
+        name,
+        tree.height(),
+        bound,
+        size,
+        step
+    );
+}
+
+#[test]
+fn test_stress() {
+    let seed = [7u8; 32];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+    let mut fast_rb_tree = FastRBTree::new();
+    let mut rb_tree = RBTree::new();
+    let mut avl_tree = AVLTree::new();
+    let mut expected = BTreeSet::new();
+
+    for step in 0..OPERATIONS {
+        let value = rng.gen_range(0, VALUE_RANGE);
+        match rng.gen_range(0, 7) {
+            0 | 1 => {
+                fast_rb_tree.insert(value);
+                rb_tree.insert(value);
+                avl_tree.insert(value);
+                expected.insert(value);
+            }
+            2 | 3 => {
+                fast_rb_tree.delete(value);
+                rb_tree.delete(value);
+                avl_tree.delete(value);
+                expected.remove(&value);
+            }
+            4 => {
+                let contained = expected.contains(&value);
+                assert_eq!(fast_rb_tree.contains(value), contained, "step {}", step);
+                assert_eq!(rb_tree.contains(value), contained, "step {}", step);
+                assert_eq!(avl_tree.contains(value), contained, "step {}", step);
+            }
+            5 => {
+                check_height("FastRBTree", &fast_rb_tree, expected.len(), step);
+                check_height("RBTree", &rb_tree, expected.len(), step);
+                check_height("AVLTree", &avl_tree, expected.len(), step);
+            }
+            _ => {
+                let values: Vec<u32> = expected.iter().copied().collect();
+                assert_eq!(fast_rb_tree.to_sorted_vec(), values, "step {}", step);
+                assert_eq!(rb_tree.to_sorted_vec(), values, "step {}", step);
+                assert_eq!(avl_tree.to_sorted_vec(), values, "step {}", step);
+            }
+        }
+        check_tree("FastRBTree", &fast_rb_tree, &expected, step);
+        check_tree("RBTree", &rb_tree, &expected, step);
+        check_tree("AVLTree", &avl_tree, &expected, step);
+    }
+    assert!(fast_rb_tree.is_valid_red_black_tree());
+    assert!(rb_tree.is_valid_red_black_tree());
+    assert!(avl_tree.is_height_balanced());
+}